@@ -243,7 +243,9 @@ async function hashPubkey(pubkey: PublicKey): Promise<Buffer> {
  */
 export async function registerBusiness(
   connection: Connection,
-  wallet: WalletContextState
+  wallet: WalletContextState,
+  encryptionContext: PublicKey = INCO_LIGHTNING_ID,
+  refundCommitment: Uint8Array = new Uint8Array(32)
 ): Promise<{ txid: string; entryIndex: number }> {
   if (!wallet.publicKey || !wallet.signTransaction) {
     throw new Error('Wallet not connected');
@@ -256,16 +258,21 @@ export async function registerBusiness(
   // Encrypt employer ID (hash of pubkey)
   const encryptedEmployerId = await hashPubkey(wallet.publicKey);
   
-  // Build instruction data: discriminator + encrypted_employer_id (as Vec<u8>)
+  // Build instruction data: discriminator + encrypted_employer_id (as Vec<u8>) + label (Option<[u8; 32]> = None)
+  //   + encryption_context (Pubkey) + refund_commitment ([u8; 32], zeros = no drain refund account)
   const idLen = Buffer.alloc(4);
   idLen.writeUInt32LE(encryptedEmployerId.length);
-  const data = Buffer.concat([DISCRIMINATORS.register_business, idLen, encryptedEmployerId]);
+  const data = Buffer.concat([
+    DISCRIMINATORS.register_business, idLen, encryptedEmployerId, Buffer.from([0]),
+    encryptionContext.toBuffer(), Buffer.from(refundCommitment),
+  ]);
   
   const instruction = new TransactionInstruction({
     keys: [
       { pubkey: wallet.publicKey, isSigner: true, isWritable: true }, // employer
       { pubkey: masterVaultPDA, isSigner: false, isWritable: true }, // master_vault
       { pubkey: businessEntryPDA, isSigner: false, isWritable: true }, // business_entry (init)
+      { pubkey: BAGEL_PROGRAM_ID, isSigner: false, isWritable: false }, // business_label (None)
       { pubkey: INCO_LIGHTNING_ID, isSigner: false, isWritable: false }, // inco_lightning_program
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }, // system_program
     ],
@@ -332,7 +339,8 @@ export async function deposit(
   // Vec<u8> format: length (u32 LE) + data
   const encLen = Buffer.alloc(4);
   encLen.writeUInt32LE(encryptedAmount.length);
-  const data = Buffer.concat([DISCRIMINATORS.deposit, encLen, encryptedAmount]);
+  // + idempotency_key (Option<[u8; 16]> = None) + input_type (u8, 0 = ciphertext)
+  const data = Buffer.concat([DISCRIMINATORS.deposit, encLen, encryptedAmount, Buffer.from([0]), Buffer.from([0])]);
 
   // Build instruction keys - program expects specific order
   const INCO_TOKEN_ID = incoTokenProgram ||
@@ -348,6 +356,8 @@ export async function deposit(
     { pubkey: INCO_TOKEN_ID, isSigner: false, isWritable: false }, // inco_token_program
     { pubkey: depositorTokenAccount, isSigner: false, isWritable: true }, // depositor_token_account
     { pubkey: vaultTokenAccount, isSigner: false, isWritable: true }, // master_vault_token_account
+    { pubkey: BAGEL_PROGRAM_ID, isSigner: false, isWritable: false }, // deposit_receipt (None)
+    { pubkey: BAGEL_PROGRAM_ID, isSigner: false, isWritable: false }, // deposit_guard (None)
     { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }, // system_program
   ];
   
@@ -386,7 +396,8 @@ export async function addEmployee(
   wallet: WalletContextState,
   entryIndex: number,
   employeePubkey: PublicKey,
-  salaryPerSecond: number
+  salaryPerSecond: number,
+  endTimestamp = 0 // Fixed-term end in unix seconds (0 = open-ended)
 ): Promise<{ txid: string; employeeIndex: number }> {
   if (!wallet.publicKey || !wallet.signTransaction) {
     throw new Error('Wallet not connected');
@@ -401,23 +412,34 @@ export async function addEmployee(
   const encryptedEmployeeId = await hashPubkey(employeePubkey);
   const encryptedSalary = await encryptForInco(salaryPerSecond);
   
-  // Build instruction data: discriminator + encrypted_employee_id (Vec<u8>) + encrypted_salary (Vec<u8>)
+  // Build instruction data: discriminator + encrypted_employee_id (Vec<u8>) + encrypted_salary (Vec<u8>) + end_timestamp (i64)
   const idLen = Buffer.alloc(4);
   idLen.writeUInt32LE(encryptedEmployeeId.length);
   const salaryLen = Buffer.alloc(4);
   salaryLen.writeUInt32LE(encryptedSalary.length);
+  const endBuf = Buffer.alloc(8);
+  endBuf.writeBigInt64LE(BigInt(endTimestamp));
   const data = Buffer.concat([
     DISCRIMINATORS.add_employee,
     idLen, encryptedEmployeeId,
-    salaryLen, encryptedSalary
+    salaryLen, encryptedSalary,
+    endBuf
   ]);
   
+  // salary_band is optional: pass the PDA if the business has one, else the program ID (Anchor's None)
+  const [salaryBandPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from('salary_band'), businessEntryPDA.toBuffer()],
+    BAGEL_PROGRAM_ID
+  );
+  const salaryBandInfo = await connection.getAccountInfo(salaryBandPDA);
+
   const instruction = new TransactionInstruction({
     keys: [
       { pubkey: wallet.publicKey, isSigner: true, isWritable: true }, // employer
       { pubkey: masterVaultPDA, isSigner: false, isWritable: true }, // master_vault
       { pubkey: businessEntryPDA, isSigner: false, isWritable: true }, // business_entry
       { pubkey: employeeEntryPDA, isSigner: false, isWritable: true }, // employee_entry (init)
+      { pubkey: salaryBandInfo ? salaryBandPDA : BAGEL_PROGRAM_ID, isSigner: false, isWritable: false }, // salary_band (optional)
       { pubkey: INCO_LIGHTNING_ID, isSigner: false, isWritable: false }, // inco_lightning_program
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }, // system_program
     ],
@@ -458,6 +480,8 @@ export async function addEmployee(
  * @param vaultTokenAccount - Confidential token account for master vault (REQUIRED)
  * @param employeeTokenAccount - Confidential token account for employee (REQUIRED)
  * @param incoTokenProgram - Inco Confidential Token program ID (from env if not provided)
+ * @param shadowwireProof - Commitment (32 bytes) + range proof (672 bytes), required when useShadowwire is set
+ * @param reference - Optional 16-byte invoice/reference id, emitted in WithdrawalProcessed
  */
 export async function requestWithdrawal(
   connection: Connection,
@@ -468,12 +492,22 @@ export async function requestWithdrawal(
   useShadowwire: boolean = false,
   vaultTokenAccount: PublicKey,
   employeeTokenAccount: PublicKey,
-  incoTokenProgram?: PublicKey
+  incoTokenProgram?: PublicKey,
+  shadowwireProof?: { commitment: Buffer; rangeProof: Buffer },
+  reference?: Buffer
 ): Promise<string> {
   if (!wallet.publicKey || !wallet.signTransaction) {
     throw new Error('Wallet not connected');
   }
 
+  if (reference && reference.length !== 16) {
+    throw new Error('Withdrawal reference must be exactly 16 bytes');
+  }
+
+  if (useShadowwire && !shadowwireProof) {
+    throw new Error('ShadowWire proof is required when useShadowwire is enabled');
+  }
+
   if (!vaultTokenAccount || !employeeTokenAccount) {
     throw new Error('Confidential token accounts are required. The program requires use_confidential_tokens to be enabled.');
   }
@@ -486,13 +520,30 @@ export async function requestWithdrawal(
   const encryptedAmount = await encryptForInco(amountLamports);
 
   // Build instruction data: discriminator + encrypted_amount (Vec<u8>) + use_shadowwire (bool)
+  //   + shadowwire_proof (Option<{ commitment: Vec<u8>, range_proof: Vec<u8> }>)
+  //   + reference (Option<[u8; 16]>) + input_type (u8, 0 = ciphertext)
+  //   + encrypted_withheld (Option<Vec<u8>>, None: no tax withholding)
   // Vec<u8> format: length (u32 LE) + data
   const encLen = Buffer.alloc(4);
   encLen.writeUInt32LE(encryptedAmount.length);
   const shadowwireBuf = Buffer.alloc(1);
   shadowwireBuf.writeUInt8(useShadowwire ? 1 : 0);
 
-  const data = Buffer.concat([DISCRIMINATORS.request_withdrawal, encLen, encryptedAmount, shadowwireBuf]);
+  const vecU8 = (bytes: Buffer) => {
+    const len = Buffer.alloc(4);
+    len.writeUInt32LE(bytes.length);
+    return Buffer.concat([len, bytes]);
+  };
+  const proofBuf = useShadowwire && shadowwireProof
+    ? Buffer.concat([Buffer.from([1]), vecU8(shadowwireProof.commitment), vecU8(shadowwireProof.rangeProof)])
+    : Buffer.from([0]);
+  // reference: Option<[u8; 16]>
+  const referenceBuf = reference ? Buffer.concat([Buffer.from([1]), reference]) : Buffer.from([0]);
+
+  const data = Buffer.concat([
+    DISCRIMINATORS.request_withdrawal, encLen, encryptedAmount, shadowwireBuf, proofBuf, referenceBuf,
+    Buffer.from([0]), Buffer.from([0]),
+  ]);
 
   // Build instruction keys - program expects specific order
   const INCO_TOKEN_ID = incoTokenProgram ||
@@ -509,6 +560,8 @@ export async function requestWithdrawal(
     { pubkey: INCO_TOKEN_ID, isSigner: false, isWritable: false }, // inco_token_program
     { pubkey: vaultTokenAccount, isSigner: false, isWritable: true }, // master_vault_token_account
     { pubkey: employeeTokenAccount, isSigner: false, isWritable: true }, // employee_token_account
+    { pubkey: BAGEL_PROGRAM_ID, isSigner: false, isWritable: false }, // withholding_config (None)
+    { pubkey: BAGEL_PROGRAM_ID, isSigner: false, isWritable: false }, // tax_token_account (None)
     { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }, // system_program
   ];
   
@@ -540,12 +593,15 @@ export async function requestWithdrawal(
 
 /**
  * EmployeeEntry layout (Bagel program): discriminator 8, business_entry 32, employee_index 8,
- * encrypted_employee_id 16, encrypted_salary 16, encrypted_accrued 16, last_action 8, is_active 1, bump 1, padding.
+ * encrypted_employee_id 16, encrypted_salary 16, encrypted_accrued 16, last_action 8, is_active 1, bump 1,
+ * encrypted_total_withdrawn 16, padding.
  */
 export interface EmployeeEntrySalaryData {
   lastAction: number;
   encryptedSalaryHex: string;
   encryptedAccruedHex: string;
+  /** Lifetime payouts handle (all zeros on entries created before it was added) */
+  encryptedTotalWithdrawnHex: string;
   isActive: boolean;
 }
 
@@ -564,7 +620,8 @@ export async function getEmployeeEntrySalaryData(
   const isActive = data[104] === 1;
   const encryptedSalaryHex = data.slice(64, 80).toString('hex');
   const encryptedAccruedHex = data.slice(80, 96).toString('hex');
-  return { lastAction, encryptedSalaryHex, encryptedAccruedHex, isActive };
+  const encryptedTotalWithdrawnHex = data.slice(106, 122).toString('hex');
+  return { lastAction, encryptedSalaryHex, encryptedAccruedHex, encryptedTotalWithdrawnHex, isActive };
 }
 
 // Utility functions
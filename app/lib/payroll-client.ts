@@ -290,6 +290,7 @@ export interface EmployeeAccount {
   isActive: boolean;
   isDelegated: boolean;
   bump: number;
+  payoutWallet: PublicKey;
 }
 
 export async function getEmployeeAccount(
@@ -315,6 +316,7 @@ export async function getEmployeeAccount(
   // 152: is_active (1)
   // 153: is_delegated (1)
   // 154: bump (1)
+  // 155-187: payout_wallet (32, zero = auto_payment disabled)
   const data = accountInfo.data;
 
   return {
@@ -328,6 +330,7 @@ export async function getEmployeeAccount(
     isActive: data[152] === 1,
     isDelegated: data[153] === 1,
     bump: data[154],
+    payoutWallet: new PublicKey(data.slice(155, 187)),
   };
 }
 
@@ -583,10 +586,10 @@ export async function deposit(
   const amountLamports = BigInt(Math.floor(amountUSDBagel * 1_000_000_000));
   const encryptedAmount = await encryptForInco(amountLamports);
 
-  // Build instruction data: discriminator + encrypted_amount (Vec<u8>)
+  // Build instruction data: discriminator + encrypted_amount (Vec<u8>) + input_type (u8, 0 = ciphertext)
   const lengthBytes = Buffer.alloc(4);
   lengthBytes.writeUInt32LE(encryptedAmount.length);
-  const data = Buffer.concat([DISCRIMINATORS.deposit, lengthBytes, encryptedAmount]);
+  const data = Buffer.concat([DISCRIMINATORS.deposit, lengthBytes, encryptedAmount, Buffer.from([0])]);
 
   const instruction = new TransactionInstruction({
     keys: [
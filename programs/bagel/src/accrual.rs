@@ -0,0 +1,600 @@
+//! Accrual time math
+//!
+//! Pure helpers for turning `Clock` readings into accrual windows.
+//! Kept free of account types so they can be unit tested directly.
+
+use anchor_lang::prelude::*;
+
+use crate::oracle::SalaryDenom;
+use crate::{
+    BagelError, MAX_ACCRUAL_ELAPSED, MAX_RATE_SCALE, MAX_SALARY_TOKENS_PER_SECOND,
+    MAX_SALARY_USD_PER_SECOND, MAX_TOKEN_DECIMALS,
+};
+
+/// Seconds elapsed since `last_action`
+///
+/// Returns `None` when `last_action` is ahead of `now` (clock skew or
+/// state replayed from the TEE). Callers should treat that as "no time
+/// elapsed" and reset `last_action` rather than fail forever.
+pub fn elapsed_since(last_action: i64, now: i64) -> Result<Option<i64>> {
+    let elapsed = now
+        .checked_sub(last_action)
+        .ok_or(BagelError::InvalidTimestamp)?;
+
+    Ok((elapsed >= 0).then_some(elapsed))
+}
+
+/// Earliest timestamp a manual withdrawal is allowed after `last_action`
+pub fn next_withdraw_at(last_action: i64, interval: i64) -> Result<i64> {
+    last_action
+        .checked_add(interval)
+        .ok_or(BagelError::Overflow.into())
+}
+
+/// Whether a delegation started at `delegated_at` may be committed back
+///
+/// Entries delegated before `delegated_at` was recorded read 0 and are
+/// always mature. A clock behind `delegated_at` counts as no time passed.
+pub fn delegation_matured(delegated_at: i64, now: i64, min_duration: i64) -> bool {
+    now.saturating_sub(delegated_at) >= min_duration
+}
+
+/// Latest second a fixed-term entry may accrue to
+///
+/// `end_timestamp = 0` is an open-ended stream and leaves `now` as is;
+/// otherwise earnings stop exactly at the end of the term.
+pub fn accrual_until(now: i64, end_timestamp: i64) -> i64 {
+    if end_timestamp > 0 {
+        now.min(end_timestamp)
+    } else {
+        now
+    }
+}
+
+/// Slots left in a fixed term that started accruing at `last_action`
+///
+/// Slot-basis counterpart of `accrual_until`, converted at
+/// `MS_PER_SLOT`. `u64::MAX` for open-ended streams.
+pub fn term_slot_cap(last_action: i64, end_timestamp: i64) -> u64 {
+    if end_timestamp == 0 {
+        return u64::MAX;
+    }
+    let seconds = end_timestamp.saturating_sub(last_action).max(0) as u64;
+    seconds.saturating_mul(MS_PER_SECOND as u64) / MS_PER_SLOT
+}
+
+/// Clamp an accrual window to `MAX_ACCRUAL_ELAPSED`
+///
+/// Bounds the scalar passed to the encrypted multiply so a single call
+/// can never produce a runaway accrual.
+pub fn clamp_elapsed(elapsed: u64) -> u64 {
+    if elapsed > MAX_ACCRUAL_ELAPSED {
+        msg!("⚠️  Elapsed {}s clamped to {}s", elapsed, MAX_ACCRUAL_ELAPSED);
+    }
+    elapsed.min(MAX_ACCRUAL_ELAPSED)
+}
+
+/// Accrual window to settle and the timestamp to advance to
+///
+/// Returns `None` when there is nothing to settle (no time elapsed, or
+/// `last_action` is ahead of `now`). The new timestamp only moves by the
+/// clamped window, so a gap longer than `MAX_ACCRUAL_ELAPSED` is not lost.
+pub fn settlement_window(last_action: i64, now: i64) -> Result<Option<(u64, i64)>> {
+    let elapsed = match elapsed_since(last_action, now)? {
+        Some(elapsed) if elapsed > 0 => elapsed as u64,
+        _ => return Ok(None),
+    };
+
+    let window = clamp_elapsed(elapsed);
+    let settled_to = last_action
+        .checked_add(window as i64)
+        .ok_or(BagelError::InvalidTimestamp)?;
+
+    Ok(Some((window, settled_to)))
+}
+
+/// `accrual_basis`: elapsed time is `Clock::unix_timestamp` seconds
+pub const ACCRUAL_BASIS_SECONDS: u8 = 0;
+/// `accrual_basis`: elapsed time is `Clock::slot` deltas
+pub const ACCRUAL_BASIS_SLOTS: u8 = 1;
+
+/// Nominal slot duration used to scale a per-second rate to slots
+pub const MS_PER_SLOT: u64 = 400;
+const MS_PER_SECOND: u128 = 1_000;
+
+/// Slot-basis counterpart of `MAX_ACCRUAL_ELAPSED`
+pub const MAX_ACCRUAL_SLOTS: u64 = MAX_ACCRUAL_ELAPSED * 1_000 / MS_PER_SLOT;
+
+pub fn validate_accrual_basis(accrual_basis: u8) -> Result<()> {
+    require!(
+        accrual_basis == ACCRUAL_BASIS_SECONDS || accrual_basis == ACCRUAL_BASIS_SLOTS,
+        BagelError::InvalidState
+    );
+    Ok(())
+}
+
+/// Slot-basis accrual window and the slot to advance to
+///
+/// Mirrors `settlement_window`: `None` when no slot has passed or
+/// `last_action_slot` is ahead of `slot`, and the window is clamped to
+/// `MAX_ACCRUAL_SLOTS` so a longer gap carries over.
+pub fn slot_settlement_window(last_action_slot: u64, slot: u64) -> Option<(u64, u64)> {
+    let elapsed = slot.checked_sub(last_action_slot).filter(|e| *e > 0)?;
+    let window = elapsed.min(MAX_ACCRUAL_SLOTS);
+    Some((window, last_action_slot + window))
+}
+
+/// `(multiplier, divisor)` applied to a per-second rate for `window`
+///
+/// Rates stay quoted per second on both bases; a slot window is scaled
+/// by `MS_PER_SLOT / 1000` so switching basis does not change pay.
+pub fn basis_scalars(accrual_basis: u8, window: u64) -> Result<(u128, u128)> {
+    validate_accrual_basis(accrual_basis)?;
+    if accrual_basis == ACCRUAL_BASIS_SLOTS {
+        Ok((window as u128 * MS_PER_SLOT as u128, MS_PER_SECOND))
+    } else {
+        Ok((window as u128, 1))
+    }
+}
+
+/// Elapsed time still owed after a settlement, in seconds
+///
+/// Non-zero only when the window was clamped (e.g. after a long TEE
+/// outage). Later settlements drain it `MAX_ACCRUAL_ELAPSED` at a time;
+/// `settled_to`/`settled_slot` already sit at the start of the backlog,
+/// so this is a read-only view of it. Slot backlogs are converted at
+/// `MS_PER_SLOT`.
+pub fn pending_catchup_seconds(
+    accrual_basis: u8,
+    settled_to: i64,
+    now: i64,
+    settled_slot: u64,
+    slot: u64,
+) -> u64 {
+    if accrual_basis == ACCRUAL_BASIS_SLOTS {
+        slot.saturating_sub(settled_slot) * MS_PER_SLOT / 1_000
+    } else {
+        now.saturating_sub(settled_to).max(0) as u64
+    }
+}
+
+/// Clocks that leave `catchup_seconds` of unsettled time behind now
+///
+/// For realigning both clocks (an accrual basis switch) without dropping
+/// a clamped window that is still owed; inverse of
+/// `pending_catchup_seconds` on either basis.
+pub fn catchup_clocks(now: i64, slot: u64, catchup_seconds: u64) -> (i64, u64) {
+    let behind = i64::try_from(catchup_seconds).unwrap_or(i64::MAX);
+    let behind_slots = (catchup_seconds as u128 * MS_PER_SECOND / MS_PER_SLOT as u128)
+        .min(u64::MAX as u128) as u64;
+    (now.saturating_sub(behind), slot.saturating_sub(behind_slots))
+}
+
+/// `10^rate_scale`, the divisor applied after the `elapsed` multiply
+pub fn rate_divisor(rate_scale: u8) -> Result<u128> {
+    require!(rate_scale <= MAX_RATE_SCALE, BagelError::Overflow);
+    10u128
+        .checked_pow(rate_scale as u32)
+        .ok_or(BagelError::Overflow.into())
+}
+
+/// Highest encrypted salary `add_employee` accepts, in stored rate units
+///
+/// `MAX_SALARY_TOKENS_PER_SECOND` whole tokens (or
+/// `MAX_SALARY_USD_PER_SECOND` whole USD) in base units (`10^decimals`),
+/// times `10^rate_scale` for fixed-point rates.
+pub fn max_salary_rate(decimals: u8, rate_scale: u8, denom: SalaryDenom) -> Result<u128> {
+    require!(decimals <= MAX_TOKEN_DECIMALS, BagelError::Overflow);
+    let whole_units = match denom {
+        SalaryDenom::Native => MAX_SALARY_TOKENS_PER_SECOND,
+        SalaryDenom::Usd => MAX_SALARY_USD_PER_SECOND,
+    };
+    let base_units = 10u128
+        .checked_pow(decimals as u32)
+        .and_then(|unit| unit.checked_mul(whole_units))
+        .ok_or(BagelError::Overflow)?;
+    base_units
+        .checked_mul(rate_divisor(rate_scale)?)
+        .ok_or(BagelError::Overflow.into())
+}
+
+/// Lamports earned over `window` seconds at a fixed-point rate
+///
+/// Plaintext mirror of the encrypted settle path:
+/// `rate_scaled * window / 10^rate_scale`, floored.
+pub fn scaled_earnings(rate_scaled: u128, window: u64, rate_scale: u8) -> Result<u128> {
+    let product = rate_scaled
+        .checked_mul(window as u128)
+        .ok_or(BagelError::Overflow)?;
+    Ok(product / rate_divisor(rate_scale)?)
+}
+
+/// Lamports earned over `window` units of `accrual_basis`
+///
+/// Plaintext mirror of `settle_accrual`:
+/// `rate_scaled * multiplier / (10^rate_scale * divisor)`, floored.
+pub fn basis_earnings(
+    rate_scaled: u128,
+    window: u64,
+    rate_scale: u8,
+    accrual_basis: u8,
+) -> Result<u128> {
+    let (multiplier, divisor) = basis_scalars(accrual_basis, window)?;
+    let product = rate_scaled
+        .checked_mul(multiplier)
+        .ok_or(BagelError::Overflow)?;
+    let divisor = rate_divisor(rate_scale)?
+        .checked_mul(divisor)
+        .ok_or(BagelError::Overflow)?;
+    Ok(product / divisor)
+}
+
+/// Runway reported for a zero salary rate (the stream never stalls)
+pub const RUNWAY_UNBOUNDED: u128 = u64::MAX as u128;
+
+/// Seconds a balance can fund a fixed-point salary rate
+///
+/// Plaintext mirror of `stream_runway`:
+/// `balance * 10^rate_scale / rate_scaled`, floored. A zero rate
+/// returns `RUNWAY_UNBOUNDED` instead of dividing by zero.
+pub fn runway_seconds(balance: u128, rate_scaled: u128, rate_scale: u8) -> Result<u128> {
+    if rate_scaled == 0 {
+        return Ok(RUNWAY_UNBOUNDED);
+    }
+    let numerator = balance
+        .checked_mul(rate_divisor(rate_scale)?)
+        .ok_or(BagelError::Overflow)?;
+    Ok(numerator / rate_scaled)
+}
+
+/// Rounding mode for accrual divisions (vesting fractions, yield splits)
+///
+/// Configured per business and applied to `settle_accrual`'s encrypted
+/// divisions through `rounding_bias`. `Floor` matches plain integer
+/// division and leaves the remainder (dust) in the vault. `Ceil` and
+/// `Nearest` pay the employee up to one base unit more per division, so
+/// the vault - not the employee - absorbs the rounding; across many
+/// divisions this can exceed what a strictly floored ledger would allow
+/// for.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RoundingMode {
+    #[default]
+    Floor,
+    Ceil,
+    /// Round half up
+    Nearest,
+}
+
+/// `numerator / denominator` rounded according to `mode`
+pub fn div_round(numerator: u128, denominator: u128, mode: RoundingMode) -> Result<u128> {
+    require!(denominator != 0, BagelError::Overflow);
+
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+
+    let round_up = match mode {
+        RoundingMode::Floor => false,
+        RoundingMode::Ceil => remainder != 0,
+        RoundingMode::Nearest => remainder >= denominator - remainder,
+    };
+
+    if round_up {
+        quotient.checked_add(1).ok_or(BagelError::Overflow.into())
+    } else {
+        Ok(quotient)
+    }
+}
+
+/// Amount added to a numerator so flooring division rounds by `mode`
+///
+/// The encrypted path has only a flooring `e_div`, so `settle_accrual`
+/// adds this bias first: `(n + rounding_bias(d, mode)) / d` equals
+/// `div_round(n, d, mode)`.
+pub fn rounding_bias(denominator: u128, mode: RoundingMode) -> u128 {
+    match mode {
+        RoundingMode::Floor => 0,
+        RoundingMode::Ceil => denominator.saturating_sub(1),
+        RoundingMode::Nearest => denominator / 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elapsed_since_past_action() {
+        assert_eq!(elapsed_since(1_000, 1_060).unwrap(), Some(60));
+    }
+
+    #[test]
+    fn test_elapsed_since_same_second() {
+        assert_eq!(elapsed_since(1_000, 1_000).unwrap(), Some(0));
+    }
+
+    #[test]
+    fn test_future_last_action_is_skew() {
+        // last_action injected 1 hour ahead of the clock
+        assert_eq!(elapsed_since(1_000 + 3600, 1_000).unwrap(), None);
+    }
+
+    #[test]
+    fn test_elapsed_since_overflow() {
+        assert!(elapsed_since(i64::MIN, i64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_next_withdraw_at() {
+        assert_eq!(next_withdraw_at(1_000, 60).unwrap(), 1_060);
+        // Eligible exactly when the withdrawal path's elapsed check passes
+        let next = next_withdraw_at(1_000, 60).unwrap();
+        assert!(elapsed_since(1_000, next).unwrap().unwrap() >= 60);
+        assert!(elapsed_since(1_000, next - 1).unwrap().unwrap() < 60);
+        assert!(next_withdraw_at(i64::MAX, 60).is_err());
+    }
+
+    #[test]
+    fn test_delegation_matured_boundary() {
+        assert!(!delegation_matured(1_000, 1_000 + 299, 300));
+        assert!(delegation_matured(1_000, 1_000 + 300, 300));
+        // No minimum configured
+        assert!(delegation_matured(1_000, 1_000, 0));
+        // Clock behind the stamp
+        assert!(!delegation_matured(1_000, 900, 1));
+        // Never stamped (delegated before the field existed)
+        assert!(delegation_matured(0, 1_000, 300));
+    }
+
+    #[test]
+    fn test_clamp_elapsed_within_window() {
+        assert_eq!(clamp_elapsed(3600), 3600);
+        assert_eq!(clamp_elapsed(MAX_ACCRUAL_ELAPSED), MAX_ACCRUAL_ELAPSED);
+    }
+
+    #[test]
+    fn test_clamp_elapsed_caps_long_gap() {
+        // 10 years delegated without an accrual
+        assert_eq!(clamp_elapsed(10 * MAX_ACCRUAL_ELAPSED), MAX_ACCRUAL_ELAPSED);
+    }
+
+    #[test]
+    fn test_settlement_window_up_to_the_second() {
+        // Salary 5/s, delegated for 90s: accrued grows by 450, settled to now
+        let (window, settled_to) = settlement_window(1_000, 1_090).unwrap().unwrap();
+        assert_eq!(window, 90);
+        assert_eq!(settled_to, 1_090);
+        assert_eq!(basis_earnings(5, window, 0, ACCRUAL_BASIS_SECONDS).unwrap(), 450);
+    }
+
+    #[test]
+    fn test_settlement_window_nothing_to_settle() {
+        assert_eq!(settlement_window(1_000, 1_000).unwrap(), None);
+        assert_eq!(settlement_window(2_000, 1_000).unwrap(), None);
+    }
+
+    #[test]
+    fn test_fixed_term_stops_at_end() {
+        // Open-ended streams are untouched
+        assert_eq!(accrual_until(5_000, 0), 5_000);
+        assert_eq!(term_slot_cap(1_000, 0), u64::MAX);
+
+        // Final settlement ends exactly at term, later ones earn nothing
+        let end = 1_090;
+        let (window, settled_to) = settlement_window(1_000, accrual_until(2_000, end))
+            .unwrap()
+            .unwrap();
+        assert_eq!((window, settled_to), (90, end));
+        assert_eq!(settlement_window(end, accrual_until(3_000, end)).unwrap(), None);
+        assert_eq!(accrual_until(1_050, end), 1_050);
+
+        // 90 seconds left = 225 slots at 400ms, none once the term is over
+        assert_eq!(term_slot_cap(1_000, end), 225);
+        assert_eq!(term_slot_cap(end + 10, end), 0);
+    }
+
+    #[test]
+    fn test_settlement_window_long_gap_carries_over() {
+        let last = 1_000;
+        let now = last + 2 * MAX_ACCRUAL_ELAPSED as i64;
+        let (window, settled_to) = settlement_window(last, now).unwrap().unwrap();
+        assert_eq!(window, MAX_ACCRUAL_ELAPSED);
+        assert_eq!(settled_to, last + MAX_ACCRUAL_ELAPSED as i64);
+    }
+
+    #[test]
+    fn test_seconds_basis_earnings() {
+        // Salary 5/s over 90s
+        assert_eq!(basis_earnings(5, 90, 0, ACCRUAL_BASIS_SECONDS).unwrap(), 450);
+        assert_eq!(
+            basis_earnings(5, 90, 0, ACCRUAL_BASIS_SECONDS).unwrap(),
+            scaled_earnings(5, 90, 0).unwrap()
+        );
+        assert_eq!(basis_earnings(500, 3, 3, ACCRUAL_BASIS_SECONDS).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_slots_basis_earnings() {
+        // 225 slots at 400ms = 90s, same pay as the seconds basis
+        let (window, settled_slot) = slot_settlement_window(10_000, 10_225).unwrap();
+        assert_eq!((window, settled_slot), (225, 10_225));
+        assert_eq!(basis_earnings(5, window, 0, ACCRUAL_BASIS_SLOTS).unwrap(), 450);
+        // One slot at 5/s floors to 2
+        assert_eq!(basis_earnings(5, 1, 0, ACCRUAL_BASIS_SLOTS).unwrap(), 2);
+        // 0.5/s with rate_scale 3 over 10 slots (4s)
+        assert_eq!(basis_earnings(500, 10, 3, ACCRUAL_BASIS_SLOTS).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_slot_settlement_window_edges() {
+        assert_eq!(slot_settlement_window(10, 10), None);
+        assert_eq!(slot_settlement_window(20, 10), None);
+        let (window, settled_slot) = slot_settlement_window(0, 2 * MAX_ACCRUAL_SLOTS).unwrap();
+        assert_eq!(window, MAX_ACCRUAL_SLOTS);
+        assert_eq!(settled_slot, MAX_ACCRUAL_SLOTS);
+    }
+
+    #[test]
+    fn test_invalid_accrual_basis() {
+        assert!(validate_accrual_basis(2).is_err());
+        assert!(basis_scalars(2, 1).is_err());
+    }
+
+    #[test]
+    fn test_runway_seconds() {
+        // 1_000 lamports at 5/s lasts 200s
+        assert_eq!(runway_seconds(1_000, 5, 0).unwrap(), 200);
+        // 0.5/s (rate_scale 3) doubles it
+        assert_eq!(runway_seconds(1_000, 500, 3).unwrap(), 2_000);
+        assert_eq!(runway_seconds(4, 5, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_runway_seconds_zero_salary() {
+        assert_eq!(runway_seconds(1_000, 0, 0).unwrap(), RUNWAY_UNBOUNDED);
+        assert_eq!(runway_seconds(0, 0, 3).unwrap(), RUNWAY_UNBOUNDED);
+    }
+
+    #[test]
+    fn test_long_outage_catchup_pays_full_amount() {
+        // TEE down for 3.5 clamp windows, salary 5/s
+        let rate = 5u128;
+        let start = 1_000i64;
+        let outage = 3 * MAX_ACCRUAL_ELAPSED + MAX_ACCRUAL_ELAPSED / 2;
+        let now = start + outage as i64;
+
+        let mut last_action = start;
+        let mut paid = 0u128;
+        let mut calls = 0;
+        let mut pending = outage;
+        while let Some((window, settled_to)) = settlement_window(last_action, now).unwrap() {
+            paid += scaled_earnings(rate, window, 0).unwrap();
+            last_action = settled_to;
+            calls += 1;
+
+            let remaining = pending_catchup_seconds(ACCRUAL_BASIS_SECONDS, settled_to, now, 0, 0);
+            assert!(remaining < pending);
+            pending = remaining;
+        }
+
+        assert_eq!(calls, 4);
+        assert_eq!(pending, 0);
+        assert_eq!(last_action, now);
+        assert_eq!(paid, rate * outage as u128);
+    }
+
+    #[test]
+    fn test_catchup_clocks_round_trip() {
+        assert_eq!(catchup_clocks(1_000, 500, 0), (1_000, 500));
+        // 100s behind = 250 slots behind
+        let (last_action, last_action_slot) = catchup_clocks(1_000, 500, 100);
+        assert_eq!((last_action, last_action_slot), (900, 250));
+        assert_eq!(pending_catchup_seconds(ACCRUAL_BASIS_SECONDS, last_action, 1_000, 0, 0), 100);
+        assert_eq!(pending_catchup_seconds(ACCRUAL_BASIS_SLOTS, 0, 0, last_action_slot, 500), 100);
+        // Never wraps below zero
+        assert_eq!(catchup_clocks(10, 5, 100), (-90, 0));
+    }
+
+    #[test]
+    fn test_pending_catchup_slots() {
+        assert_eq!(pending_catchup_seconds(ACCRUAL_BASIS_SLOTS, 0, 0, 100, 100), 0);
+        // 250 slots behind = 100s
+        assert_eq!(pending_catchup_seconds(ACCRUAL_BASIS_SLOTS, 0, 0, 100, 350), 100);
+        let (_, settled_slot) = slot_settlement_window(0, MAX_ACCRUAL_SLOTS + 5).unwrap();
+        assert_eq!(
+            pending_catchup_seconds(ACCRUAL_BASIS_SLOTS, 0, 0, settled_slot, MAX_ACCRUAL_SLOTS + 5),
+            2
+        );
+    }
+
+    #[test]
+    fn test_div_round_floor() {
+        assert_eq!(div_round(7, 2, RoundingMode::Floor).unwrap(), 3);
+        assert_eq!(div_round(9, 10, RoundingMode::Floor).unwrap(), 0);
+        assert_eq!(div_round(8, 2, RoundingMode::Floor).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_div_round_ceil() {
+        assert_eq!(div_round(7, 2, RoundingMode::Ceil).unwrap(), 4);
+        assert_eq!(div_round(1, 10, RoundingMode::Ceil).unwrap(), 1);
+        assert_eq!(div_round(8, 2, RoundingMode::Ceil).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_div_round_nearest() {
+        // Exactly half rounds up
+        assert_eq!(div_round(7, 2, RoundingMode::Nearest).unwrap(), 4);
+        assert_eq!(div_round(14, 10, RoundingMode::Nearest).unwrap(), 1);
+        assert_eq!(div_round(15, 10, RoundingMode::Nearest).unwrap(), 2);
+        assert_eq!(div_round(16, 10, RoundingMode::Nearest).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_rounding_bias_matches_div_round() {
+        for mode in [RoundingMode::Floor, RoundingMode::Ceil, RoundingMode::Nearest] {
+            for denominator in [1u128, 2, 3, 10, 1_000] {
+                for numerator in 0u128..50 {
+                    assert_eq!(
+                        (numerator + rounding_bias(denominator, mode)) / denominator,
+                        div_round(numerator, denominator, mode).unwrap()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_div_round_zero_denominator() {
+        assert!(div_round(1, 0, RoundingMode::Floor).is_err());
+    }
+
+    #[test]
+    fn test_div_round_default_is_floor() {
+        assert_eq!(RoundingMode::default(), RoundingMode::Floor);
+    }
+
+    #[test]
+    fn test_rate_divisor() {
+        assert_eq!(rate_divisor(0).unwrap(), 1);
+        assert_eq!(rate_divisor(3).unwrap(), 1_000);
+        assert_eq!(rate_divisor(MAX_RATE_SCALE).unwrap(), 10u128.pow(MAX_RATE_SCALE as u32));
+        assert!(rate_divisor(MAX_RATE_SCALE + 1).is_err());
+    }
+
+    #[test]
+    fn test_max_salary_rate_scales_with_decimals() {
+        // 50 SOL/sec in lamports, 50 USDC/sec in micro-USDC
+        let native = SalaryDenom::Native;
+        assert_eq!(max_salary_rate(9, 0, native).unwrap(), 50_000_000_000);
+        assert_eq!(max_salary_rate(6, 0, native).unwrap(), 50_000_000);
+        // Fixed-point rates carry the extra scale
+        assert_eq!(max_salary_rate(6, 3, native).unwrap(), 50_000_000_000);
+        assert!(max_salary_rate(MAX_TOKEN_DECIMALS, MAX_RATE_SCALE, native).is_ok());
+        assert!(max_salary_rate(MAX_TOKEN_DECIMALS + 1, 0, native).is_err());
+    }
+
+    #[test]
+    fn test_max_salary_rate_uses_usd_ceiling() {
+        // 10 USD/sec at 6 decimals of precision
+        assert_eq!(max_salary_rate(6, 0, SalaryDenom::Usd).unwrap(), 10_000_000);
+        assert!(
+            max_salary_rate(6, 0, SalaryDenom::Usd).unwrap()
+                < max_salary_rate(6, 0, SalaryDenom::Native).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sub_lamport_rate_over_an_hour() {
+        // 0.5 lamports/sec: rounds to 0 as an integer rate
+        assert_eq!(scaled_earnings(0, 3600, 0).unwrap(), 0);
+        // Stored as 500 milli-lamports/sec at scale 3
+        assert_eq!(scaled_earnings(500, 3600, 3).unwrap(), 1_800);
+        // 0.0015 lamports/sec at scale 4 floors once per window
+        assert_eq!(scaled_earnings(15, 3600, 4).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_scaled_earnings_unscaled_matches_plain_multiply() {
+        assert_eq!(scaled_earnings(5, 90, 0).unwrap(), 450);
+    }
+}
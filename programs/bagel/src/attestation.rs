@@ -0,0 +1,146 @@
+//! Ed25519 verification for TEE-attested updates
+//!
+//! An attested update carries a signature from the TEE validator over a
+//! message the instruction rebuilds on-chain. Bagel does not verify the
+//! signature itself: the client adds an Ed25519 program instruction to
+//! the same transaction, the runtime precompile verifies it, and this
+//! module reads the instructions sysvar to confirm that instruction
+//! covered the expected signer, message and signature.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+
+use crate::BagelError;
+
+/// Confirm `signer` signed `message` with `signature` in this transaction
+///
+/// Scans the instructions before the current one for an Ed25519
+/// program instruction matching all three, with the data stored inline.
+/// Fails with `IdentityVerificationFailed` when none does.
+pub fn verify_tee_signature(
+    instruction_sysvar: &AccountInfo,
+    message: &[u8],
+    signature: &[u8; 64],
+    signer: &Pubkey,
+) -> Result<()> {
+    let current = load_current_index_checked(instruction_sysvar)?;
+    for index in 0..current {
+        let ix = load_instruction_at_checked(index as usize, instruction_sysvar)?;
+        if ix.program_id == anchor_lang::solana_program::ed25519_program::ID
+            && ed25519_data_matches(&ix.data, signer, message, signature)
+        {
+            return Ok(());
+        }
+    }
+    err!(BagelError::IdentityVerificationFailed)
+}
+
+/// Match Ed25519 program instruction data against an expected signature
+///
+/// Layout: num_signatures(u8) + padding(u8), then per signature seven
+/// u16 offsets (signature, its ix index, pubkey, its ix index, message,
+/// message size, its ix index). Only inline data (ix index `u16::MAX`)
+/// is accepted.
+pub fn ed25519_data_matches(
+    data: &[u8],
+    signer: &Pubkey,
+    message: &[u8],
+    signature: &[u8; 64],
+) -> bool {
+    const HEADER: usize = 2;
+    const OFFSETS_LEN: usize = 14;
+
+    let read_u16 = |at: usize| -> Option<usize> {
+        data.get(at..at + 2).map(|b| u16::from_le_bytes([b[0], b[1]]) as usize)
+    };
+
+    let count = match data.first() {
+        Some(count) => *count as usize,
+        None => return false,
+    };
+
+    (0..count).any(|i| {
+        let base = HEADER + i * OFFSETS_LEN;
+        let fields: Option<[usize; 7]> = (|| {
+            Some([
+                read_u16(base)?,
+                read_u16(base + 2)?,
+                read_u16(base + 4)?,
+                read_u16(base + 6)?,
+                read_u16(base + 8)?,
+                read_u16(base + 10)?,
+                read_u16(base + 12)?,
+            ])
+        })();
+        let [sig_at, sig_ix, key_at, key_ix, msg_at, msg_len, msg_ix] = match fields {
+            Some(fields) => fields,
+            None => return false,
+        };
+
+        let inline = u16::MAX as usize;
+        sig_ix == inline
+            && key_ix == inline
+            && msg_ix == inline
+            && data.get(sig_at..sig_at + 64) == Some(&signature[..])
+            && data.get(key_at..key_at + 32) == Some(signer.as_ref())
+            && data.get(msg_at..msg_at + msg_len) == Some(message)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ed25519 program data with one inline signature
+    fn ed25519_data(signer: &Pubkey, message: &[u8], signature: &[u8; 64]) -> Vec<u8> {
+        let key_at = 2 + 14;
+        let sig_at = key_at + 32;
+        let msg_at = sig_at + 64;
+        let mut data = vec![1u8, 0];
+        for field in [
+            sig_at as u16,
+            u16::MAX,
+            key_at as u16,
+            u16::MAX,
+            msg_at as u16,
+            message.len() as u16,
+            u16::MAX,
+        ] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(signature);
+        data.extend_from_slice(message);
+        data
+    }
+
+    #[test]
+    fn test_ed25519_data_matches() {
+        let tee = Pubkey::new_unique();
+        let message = b"bagel:tee_accrued example".to_vec();
+        let signature = [9u8; 64];
+        let data = ed25519_data(&tee, &message, &signature);
+
+        assert!(ed25519_data_matches(&data, &tee, &message, &signature));
+        // Wrong signer, message or signature
+        assert!(!ed25519_data_matches(&data, &Pubkey::new_unique(), &message, &signature));
+        assert!(!ed25519_data_matches(&data, &tee, &message[1..], &signature));
+        assert!(!ed25519_data_matches(&data, &tee, &message, &[8u8; 64]));
+        // Truncated or empty data
+        assert!(!ed25519_data_matches(&data[..40], &tee, &message, &signature));
+        assert!(!ed25519_data_matches(&[], &tee, &message, &signature));
+    }
+
+    #[test]
+    fn test_ed25519_data_rejects_cross_instruction_refs() {
+        let tee = Pubkey::new_unique();
+        let message = b"msg".to_vec();
+        let signature = [9u8; 64];
+        let mut data = ed25519_data(&tee, &message, &signature);
+        // Point the public key at instruction 0 instead of inline data
+        data[2 + 6..2 + 8].copy_from_slice(&0u16.to_le_bytes());
+        assert!(!ed25519_data_matches(&data, &tee, &message, &signature));
+    }
+}
@@ -13,6 +13,11 @@ pub const MIN_WITHDRAW_INTERVAL: i64 = 60; // 1 minute
 /// Documentation: https://docs.inco.org/svm/home
 pub const INCO_PROGRAM_ID: &str = "5sjEbPiqgZrYwR31ahR6Uk9wf5awoX61YGg7jExQSwaj";
 
+/// Inco Confidential Token Program - Devnet
+/// Program ID: 4cyJHzecVWuU2xux6bCAPAhALKQT8woBh4Vx3AGEGe5N
+/// Source: https://github.com/Inco-fhevm/lightning-rod-solana
+pub const INCO_TOKEN_PROGRAM_ID: &str = "4cyJHzecVWuU2xux6bCAPAhALKQT8woBh4Vx3AGEGe5N";
+
 /// MagicBlock PER (Real-Time Streaming) - Devnet
 /// Delegation Program: DELeGGvXpWV2fqJUhqcF5ZSYMS4JTLjteaAMARRSaeSh
 /// Permission Program: ACLseoPoyC3cBqoUtkbjZ4aDrkurZW86v19pXz2XQnp1
@@ -33,6 +38,17 @@ pub const MAGICBLOCK_VALIDATOR_US: &str = "MUS3hc9TCw4cGC12vHNoYcCGzJG1txjgQLZWV
 /// Documentation: https://github.com/Radrdotfun/ShadowWire
 pub const SHADOWWIRE_PROGRAM_ID: &str = "GQBqwwoikYh7p6KEUHDUu5r9dHHXx9tMGskAPubmFPzD";
 
+/// Pyth Solana Receiver (PriceUpdateV2 accounts) - Devnet & Mainnet
+/// Program ID: rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ
+/// Documentation: https://docs.pyth.network/price-feeds/use-real-time-data/solana
+pub const PYTH_RECEIVER_PROGRAM_ID: &str = "rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ";
+
+/// Oldest oracle price accepted for USD accrual conversion
+pub const MAX_PRICE_AGE_SECONDS: i64 = 60;
+
+/// Widest oracle confidence interval accepted, in basis points of price
+pub const MAX_PRICE_CONF_BPS: u64 = 100; // 1%
+
 /// Privacy Provider Program IDs
 pub mod program_ids {
     use anchor_lang::prelude::*;
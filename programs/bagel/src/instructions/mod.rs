@@ -6,12 +6,55 @@
 
 // All instructions are currently defined in lib.rs:
 // - initialize_vault
+// - initialize_global_state
+// - transfer_admin / accept_admin
 // - register_business
-// - deposit
+// - set_label
+// - set_rounding_mode / set_withdrawal_delay / set_soft_cap
+// - set_rent_from_treasury
+// - freeze_business / unfreeze_business
+// - set_rate_scale
+// - set_salary_denom
+// - set_salary_band
+// - deposit / close_deposit_guard
+// - deposit_multi
+// - prove_balance_above
+// - reveal_employee_count
+// - stream_runway
+// - employee_contribute
 // - add_employee
+// - list_active
+// - export_business / import_business / import_employees
 // - request_withdrawal
+// - auto_payment
+// - withdraw_split / set_split_destinations
+// - initiate_withdrawal / finalize_withdrawal / cancel_withdrawal / close_pending
+// - withdraw_eligibility
+// - accrued_snapshot
+// - config
+// - healthcheck
 // - configure_confidential_mint
+// - set_confidential_tokens
+// - set_provider_flags
+// - set_min_delegation_duration
+// - set_token_decimals
+// - rotate_confidential_mint
 // - close_vault
+// - close_employee
+// - drain_business
 // - migrate_vault
+// - grow_account
 // - delegate_to_tee
-// - commit_from_tee
+// - commit_from_tee / commit_from_tee_batch
+// - submit_tee_accrued
+// - settle_on_undelegate
+// - force_recover_delegation
+// - suspend_employee / resume_employee
+// - set_accrual_basis
+// - set_employee_budget
+// - extend_term
+// - set_immediate_first_withdrawal
+// - set_withdraw_authority
+// - fund_employee
+// - set_withholding
+// - delegate_vault_to_tee / commit_vault_from_tee
@@ -30,9 +30,19 @@
 
 use anchor_lang::prelude::*;
 use inco_lightning::cpi::accounts::Operation;
-use inco_lightning::cpi::{e_add, e_sub, new_euint128};
-use inco_lightning::types::Euint128;
+use inco_lightning::cpi::{as_euint128, e_add, e_div, e_ge, e_mul, e_select, e_sub, new_euint128};
+use inco_lightning::types::{Ebool, Euint128};
 use inco_lightning::ID as INCO_LIGHTNING_ID;
+use crate::accrual::RoundingMode;
+use crate::attestation::verify_tee_signature;
+use crate::oracle::SalaryDenom;
+use crate::privacy::inco::{
+    allow_instruction, allowance_pda, require_transfer_authority, validate_input_type,
+    validate_payout_input_type,
+    IncoTokenAccountView,
+};
+use crate::privacy::shadowwire::{ErrorCode as ShadowWireError, ShadowWireProof, ShadowWireTransfer};
+use crate::privacy::ProviderFlags;
 
 // Inco Confidential Token SDK
 use inco_token::cpi::accounts::IncoTransfer;
@@ -58,6 +68,20 @@ use ephemeral_rollups_sdk::access_control::structs::{
 
 declare_id!("AEd52vEEAdXWUjKut1aQyLLJQnwMWqYMb4hSaHpxd8Hj");
 
+/// Log the remaining compute budget at a labelled phase
+///
+/// Compiled out unless the `debug-cu` feature is on, so release builds
+/// pay nothing. Consecutive checkpoints bracket the CUs a phase used.
+macro_rules! cu_checkpoint {
+    ($label:expr) => {
+        #[cfg(feature = "debug-cu")]
+        {
+            msg!("⏱️ CU checkpoint: {}", $label);
+            anchor_lang::solana_program::log::sol_log_compute_units();
+        }
+    };
+}
+
 // ============================================================
 // Seeds for PDA derivation (PRIVACY-PRESERVING)
 // ============================================================
@@ -65,12 +89,47 @@ declare_id!("AEd52vEEAdXWUjKut1aQyLLJQnwMWqYMb4hSaHpxd8Hj");
 /// Seed for MasterVault (global, only 1)
 pub const MASTER_VAULT_SEED: &[u8] = b"master_vault";
 
+/// Seed for GlobalState (protocol-wide admin controls, only 1)
+pub const GLOBAL_STATE_SEED: &[u8] = b"global_state";
+
 /// Seed for BusinessEntry (INDEX-BASED - no employer pubkey!)
 pub const BUSINESS_ENTRY_SEED: &[u8] = b"entry";
 
 /// Seed for EmployeeEntry (INDEX-BASED - no employee pubkey!)
 pub const EMPLOYEE_ENTRY_SEED: &[u8] = b"employee";
 
+/// Seed for DepositReceipt (opt-in, per business)
+/// Seeds: ["receipt", business_entry, receipt_index]
+pub const DEPOSIT_RECEIPT_SEED: &[u8] = b"receipt";
+
+/// Seed for PendingWithdrawal (one per employee)
+/// Seeds: ["pending_withdrawal", employee_entry]
+pub const PENDING_WITHDRAWAL_SEED: &[u8] = b"pending_withdrawal";
+
+/// Seed for SalaryBand (optional, one per business)
+/// Seeds: ["salary_band", business_entry]
+pub const SALARY_BAND_SEED: &[u8] = b"salary_band";
+
+/// Seed for BusinessLabel (optional plaintext metadata, one per business)
+/// Seeds: ["label", business_entry]
+pub const BUSINESS_LABEL_SEED: &[u8] = b"label";
+
+/// Seed for DepositGuard (optional idempotency key, per business)
+/// Seeds: ["deposit_guard", business_entry, idempotency_key]
+pub const DEPOSIT_GUARD_SEED: &[u8] = b"deposit_guard";
+
+/// Seed for PriceFeedConfig (optional, one per USD-denominated business)
+/// Seeds: ["price_feed", business_entry]
+pub const PRICE_FEED_SEED: &[u8] = b"price_feed";
+
+/// Seed for WithholdingConfig (optional, one per business that withholds tax)
+/// Seeds: ["withholding", business_entry]
+pub const WITHHOLDING_SEED: &[u8] = b"withholding";
+
+/// Seed for SplitDestinations (optional, one per employee using withdraw_split)
+/// Seeds: ["split_destinations", employee_entry]
+pub const SPLIT_DESTINATIONS_SEED: &[u8] = b"split_destinations";
+
 /// Seed for user token account PDA (deterministic derivation)
 /// Seeds: ["user_token", wallet_pubkey, mint] -> token account PDA
 pub const USER_TOKEN_SEED: &[u8] = b"user_token";
@@ -78,9 +137,56 @@ pub const USER_TOKEN_SEED: &[u8] = b"user_token";
 /// Minimum time between withdrawals (60 seconds)
 pub const MIN_WITHDRAW_INTERVAL: i64 = 60;
 
+/// Maximum ciphertext size stored on a PendingWithdrawal
+pub const MAX_PENDING_CIPHERTEXT_LEN: usize = 256;
+
+/// Maximum size of any client-supplied ciphertext (an Inco euint128
+/// ciphertext is well under this)
+pub const MAX_CIPHERTEXT_LEN: usize = 128;
+
+/// Maximum businesses funded by a single `deposit_multi` call
+pub const MAX_DEPOSIT_ALLOCATIONS: usize = 8;
+
+/// Maximum destinations paid by a single `withdraw_split` call
+pub const MAX_SPLIT_RECIPIENTS: usize = 4;
+
+/// Maximum employee entries scanned by a single `list_active` call
+/// 30 entries plus the fixed accounts fit one legacy transaction (1232
+/// bytes) without lookup tables
+pub const MAX_LIST_ACTIVE: u64 = 30;
+
+/// Maximum employee entries bundled by a single `export_business` call
+/// 2 entries keep a signed `import_business` transaction (bundle, Ed25519
+/// instruction and one account per employee) under the 1232-byte limit
+pub const MAX_EXPORT_EMPLOYEES: usize = 2;
+
+/// `BusinessExport` layout version written by `export_business`
+pub const EXPORT_VERSION: u8 = 4;
+
+/// Maximum seconds a single accrual may cover (1 year)
+/// Longer gaps (e.g. TEE outage) are caught up over multiple calls
+pub const MAX_ACCRUAL_ELAPSED: u64 = 365 * 24 * 60 * 60;
+
+/// Maximum fixed-point decimals for salary rates (see `set_rate_scale`)
+pub const MAX_RATE_SCALE: u8 = 18;
+
+/// Salary ceiling enforced by `add_employee`, in whole tokens per second
+/// Scaled to base units by the vault's `token_decimals`
+pub const MAX_SALARY_TOKENS_PER_SECOND: u128 = 50;
+
+/// Salary ceiling for `SalaryDenom::Usd` businesses, in whole USD per second
+/// USD rates share the token's base-unit precision (1 USD = `10^decimals`)
+pub const MAX_SALARY_USD_PER_SECOND: u128 = 10;
+
+/// Maximum token decimals accepted by `set_token_decimals`
+pub const MAX_TOKEN_DECIMALS: u8 = 18;
+
 /// MagicBlock TEE Validator (Devnet)
 pub const TEE_VALIDATOR: &str = "FnE6VJT5QNZdedZPnCoLsARgBwoE6DeJNjBs2H1gySXA";
 
+/// TEE validators accounts may be delegated to
+pub const KNOWN_TEE_VALIDATORS: &[&str] = &[TEE_VALIDATOR];
+
 /// Permission PDA seed for EmployeeEntry
 pub const PERMISSION_SEED: &[u8] = b"permission";
 
@@ -93,6 +199,15 @@ pub mod constants;
 // Error module
 pub mod error;
 
+// Accrual time math
+pub mod accrual;
+
+// External price oracle for USD-denominated salaries
+pub mod oracle;
+
+// Ed25519 verification for TEE-attested updates
+pub mod attestation;
+
 // Account structs for permission operations (defined before bagel module)
 // These are used for CPI contexts when calling Permission Program
 #[derive(Accounts)]
@@ -163,6 +278,9 @@ pub mod bagel {
         vault.bump = ctx.bumps.master_vault;
         vault.confidential_mint = Pubkey::default(); // Will be set when confidential mint is deployed
         vault.use_confidential_tokens = true; // Enable confidential tokens by default
+        vault.provider_flags = ProviderFlags::new(ProviderFlags::DEFAULT_BITS)?;
+        vault.min_delegation_duration = 0;
+        vault.token_decimals = None;
 
         // Initialize encrypted counts to zero
         let zero_ciphertext = vec![0u8; 16];
@@ -182,7 +300,16 @@ pub mod bagel {
             ctx.accounts.inco_lightning_program.to_account_info(),
             cpi_accounts2,
         );
-        vault.encrypted_employee_count = new_euint128(cpi_ctx2, zero_ciphertext, 0)?;
+        vault.encrypted_employee_count = new_euint128(cpi_ctx2, zero_ciphertext.clone(), 0)?;
+
+        let cpi_ctx3 = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        vault.encrypted_total_balance = new_euint128(cpi_ctx3, zero_ciphertext, 0)?;
+        vault.tracks_encrypted_total = true;
 
         msg!("🏦 Master Vault initialized (Maximum Privacy Mode)");
         msg!("   Business count: ENCRYPTED");
@@ -196,6 +323,86 @@ pub mod bagel {
         Ok(())
     }
 
+    // ============================================================
+    // Global State Instructions
+    // ============================================================
+
+    /// Initialize the global state (one-time setup)
+    ///
+    /// Creates the protocol-wide admin account used for pause controls
+    /// and aggregate stats. The signer becomes the admin.
+    pub fn initialize_global_state(ctx: Context<InitializeGlobalState>) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+
+        global_state.admin = ctx.accounts.admin.key();
+        global_state.pending_admin = Pubkey::default();
+        global_state.is_paused = false;
+        global_state.total_payrolls = 0;
+        global_state.total_withdrawals = 0;
+        global_state.bump = ctx.bumps.global_state;
+
+        msg!("🌐 Global state initialized");
+        msg!("   Admin: {}", global_state.admin);
+
+        emit!(GlobalStateInitialized {
+            admin: global_state.admin,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Propose a new admin (step 1 of 2)
+    ///
+    /// The current admin nominates `new_admin`. Nothing changes until the
+    /// nominee calls `accept_admin`, so a mistyped key can't brick the
+    /// global controls. Passing `Pubkey::default()` cancels a pending transfer.
+    pub fn transfer_admin(ctx: Context<TransferAdmin>, new_admin: Pubkey) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+
+        require!(
+            ctx.accounts.admin.key() == global_state.admin,
+            BagelError::Unauthorized
+        );
+
+        global_state.pending_admin = new_admin;
+
+        msg!("👑 Admin transfer proposed");
+        msg!("   Current admin: {}", global_state.admin);
+        msg!("   Pending admin: {}", new_admin);
+
+        Ok(())
+    }
+
+    /// Accept a pending admin transfer (step 2 of 2)
+    ///
+    /// Must be signed by the key nominated in `transfer_admin`.
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+
+        require!(
+            global_state.pending_admin != Pubkey::default()
+                && ctx.accounts.pending_admin.key() == global_state.pending_admin,
+            BagelError::Unauthorized
+        );
+
+        let previous_admin = global_state.admin;
+        global_state.admin = global_state.pending_admin;
+        global_state.pending_admin = Pubkey::default();
+
+        msg!("👑 Admin transferred");
+        msg!("   Previous admin: {}", previous_admin);
+        msg!("   New admin: {}", global_state.admin);
+
+        emit!(AdminTransferred {
+            previous_admin,
+            new_admin: global_state.admin,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
     // ============================================================
     // Business Entry Instructions (INDEX-BASED)
     // ============================================================
@@ -205,11 +412,31 @@ pub mod bagel {
     /// The PDA is derived from entry_index, NOT employer pubkey.
     /// Employer identity is stored as encrypted hash - observer
     /// cannot link this entry to any specific employer.
+    ///
+    /// `encryption_context` records which Inco encryption context (key
+    /// id) the business's ciphertexts are made under, so clients know
+    /// which one to encrypt and decrypt with. It must be set.
+    ///
+    /// `refund_commitment` is `refund_commitment(account, salt)` for the
+    /// employer's confidential token account that `drain_business` may
+    /// pay out to; salting keeps the account unlinkable until a drain.
+    /// Zeros register none, and the business can then never be drained.
     pub fn register_business(
         ctx: Context<RegisterBusiness>,
         encrypted_employer_id: Vec<u8>, // Hash of employer pubkey, encrypted
+        label: Option<[u8; 32]>,        // Optional PLAINTEXT tag, e.g. "US-PAYROLL"
+        encryption_context: Pubkey,
+        refund_commitment: [u8; 32],
     ) -> Result<()> {
-        require!(!encrypted_employer_id.is_empty(), BagelError::InvalidCiphertext);
+        validate_ciphertext(&encrypted_employer_id)?;
+        require!(
+            encryption_context != Pubkey::default(),
+            BagelError::InvalidEncryptionContext
+        );
+        require!(
+            label.is_some() == ctx.accounts.business_label.is_some(),
+            BagelError::InvalidState
+        );
 
         let vault = &mut ctx.accounts.master_vault;
         let entry = &mut ctx.accounts.business_entry;
@@ -221,9 +448,28 @@ pub mod bagel {
         entry.master_vault = vault.key();
         entry.entry_index = entry_index;
         entry.next_employee_index = 0;
+        entry.next_receipt_index = 0;
+        entry.rounding_mode = RoundingMode::Floor;
+        entry.withdrawal_delay = 0;
+        entry.has_salary_band = false;
+        entry.rate_scale = 0;
+        entry.salary_denom = SalaryDenom::Native;
+        entry.soft_cap = 0;
+        entry.is_funded = false;
+        entry.rent_from_treasury = false;
+        entry.encryption_context = encryption_context;
+        entry.is_frozen = false;
+        entry.refund_commitment = refund_commitment;
         entry.is_active = true;
         entry.bump = ctx.bumps.business_entry;
 
+        // Optional plaintext label (privacy-maximal deployments omit it)
+        if let (Some(label), Some(business_label)) = (label, ctx.accounts.business_label.as_mut()) {
+            business_label.business_entry = entry.key();
+            business_label.label = label;
+            business_label.bump = ctx.bumps.business_label.unwrap_or_default();
+        }
+
         // Store encrypted employer ID (hash of pubkey, encrypted)
         let cpi_accounts = Operation {
             signer: ctx.accounts.employer.to_account_info(),
@@ -287,6 +533,7 @@ pub mod bagel {
 
         emit!(BusinessRegistered {
             entry_index,
+            encryption_context,
             timestamp: Clock::get()?.unix_timestamp,
             // NOTE: No employer pubkey in event for privacy
         });
@@ -294,17 +541,367 @@ pub mod bagel {
         Ok(())
     }
 
+    /// Set or clear a business's plaintext label (vault authority only)
+    ///
+    /// The label is non-sensitive operator metadata for indexers; pass
+    /// all zeros to blank it.
+    pub fn set_label(ctx: Context<SetLabel>, label: [u8; 32]) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let business_label = &mut ctx.accounts.business_label;
+        business_label.business_entry = ctx.accounts.business_entry.key();
+        business_label.label = label;
+        business_label.bump = ctx.bumps.business_label;
+
+        msg!("🏷️  Business label updated");
+        msg!("   Entry Index: {}", ctx.accounts.business_entry.entry_index);
+
+        emit!(BusinessLabelUpdated {
+            entry_index: ctx.accounts.business_entry.entry_index,
+            label,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set the rounding mode used by a business's accrual divisions
+    ///
+    /// Defaults to Floor (current behavior). Only the vault authority
+    /// can change it, since non-floor modes draw rounding from the vault.
+    pub fn set_rounding_mode(
+        ctx: Context<SetRoundingMode>,
+        rounding_mode: RoundingMode,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let entry = &mut ctx.accounts.business_entry;
+        entry.rounding_mode = rounding_mode;
+
+        msg!("⚙️ Rounding mode updated");
+        msg!("   Entry Index: {}", entry.entry_index);
+        msg!("   Mode: {:?}", rounding_mode);
+
+        emit!(RoundingModeUpdated {
+            entry_index: entry.entry_index,
+            rounding_mode,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set the fixed-point scale of a business's salary rates
+    ///
+    /// With `rate_scale = 3` an encrypted salary of 500 means 0.5 lamports
+    /// per second. Accrual divides `salary * elapsed` by `10^rate_scale`,
+    /// so sub-lamport rates still pay out over a settlement window. Each
+    /// settlement floors its own result; the sub-lamport remainder of a
+    /// window is not carried over. Only settable before the first
+    /// employee is added, since it reinterprets every stored salary.
+    pub fn set_rate_scale(
+        ctx: Context<SetRateScale>,
+        rate_scale: u8,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+        require!(rate_scale <= MAX_RATE_SCALE, BagelError::Overflow);
+
+        let entry = &mut ctx.accounts.business_entry;
+        require!(entry.next_employee_index == 0, BagelError::InvalidState);
+        entry.rate_scale = rate_scale;
+
+        msg!("⚙️ Rate scale updated");
+        msg!("   Entry Index: {}", entry.entry_index);
+        msg!("   Scale: {}", rate_scale);
+
+        emit!(RateScaleUpdated {
+            entry_index: entry.entry_index,
+            rate_scale,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set the currency a business's salary rates are denominated in
+    ///
+    /// With `Usd`, settled accrual (`settle_on_undelegate`,
+    /// `suspend_employee`) is converted into token units using the Pyth
+    /// feed `feed_id`, so the encrypted accrued balance that withdrawals
+    /// draw from is always in tokens. Like the rate scale, it reinterprets
+    /// every stored salary and is only settable before the first employee.
+    pub fn set_salary_denom(
+        ctx: Context<SetSalaryDenom>,
+        salary_denom: SalaryDenom,
+        feed_id: [u8; 32],
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let entry = &mut ctx.accounts.business_entry;
+        require!(entry.next_employee_index == 0, BagelError::InvalidState);
+
+        let config = &mut ctx.accounts.price_feed_config;
+        config.business_entry = entry.key();
+        config.feed_id = feed_id;
+        config.bump = ctx.bumps.price_feed_config;
+        entry.salary_denom = salary_denom;
+
+        msg!("💱 Salary denomination updated");
+        msg!("   Entry Index: {}", entry.entry_index);
+        msg!("   Denom: {:?}", salary_denom);
+
+        emit!(SalaryDenomUpdated {
+            entry_index: entry.entry_index,
+            salary_denom,
+            feed_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set the cooling-off delay for two-phase withdrawals
+    ///
+    /// `initiate_withdrawal` locks a payout until `now + withdrawal_delay`.
+    /// 0 (default) lets a pending withdrawal finalize immediately. A
+    /// nonzero delay also closes the single-step paths
+    /// (`request_withdrawal`, `withdraw_split`), so
+    /// every manual payout waits out the cooling-off window.
+    pub fn set_withdrawal_delay(
+        ctx: Context<SetWithdrawalDelay>,
+        withdrawal_delay: i64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+        require!(withdrawal_delay >= 0, BagelError::InvalidTimestamp);
+
+        let entry = &mut ctx.accounts.business_entry;
+        entry.withdrawal_delay = withdrawal_delay;
+
+        msg!("⚙️ Withdrawal delay updated");
+        msg!("   Entry Index: {}", entry.entry_index);
+        msg!("   Delay: {} seconds", withdrawal_delay);
+
+        emit!(WithdrawalDelayUpdated {
+            entry_index: entry.entry_index,
+            withdrawal_delay,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set the employee-count soft cap of a business
+    ///
+    /// `add_employee` emits `EmployeeSoftCapReached` when the count hits
+    /// `soft_cap`, without blocking. Meant for off-chain license-tier
+    /// alerts. 0 disables it.
+    pub fn set_soft_cap(
+        ctx: Context<SetSoftCap>,
+        soft_cap: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let entry = &mut ctx.accounts.business_entry;
+        entry.soft_cap = soft_cap;
+
+        msg!("⚙️ Employee soft cap updated");
+        msg!("   Entry Index: {}", entry.entry_index);
+        msg!("   Soft Cap: {}", soft_cap);
+
+        emit!(SoftCapUpdated {
+            entry_index: entry.entry_index,
+            soft_cap,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Have the business treasury pay EmployeeEntry rent
+    ///
+    /// When enabled, `add_employee` refunds the employer the new entry's
+    /// rent out of the BusinessEntry PDA's own lamports, so a delegated HR
+    /// operator is not paying onboarding from a personal wallet. Top the
+    /// treasury up with a plain SOL transfer to the BusinessEntry PDA.
+    /// `add_employee` fails with `InsufficientFunds` rather than take the
+    /// entry below its own rent-exempt minimum.
+    pub fn set_rent_from_treasury(
+        ctx: Context<SetRentFromTreasury>,
+        enabled: bool,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let entry = &mut ctx.accounts.business_entry;
+        entry.rent_from_treasury = enabled;
+
+        msg!("⚙️ Employee rent payer updated");
+        msg!("   Entry Index: {}", entry.entry_index);
+        msg!("   From Treasury: {}", enabled);
+
+        emit!(RentPayerUpdated {
+            entry_index: entry.entry_index,
+            rent_from_treasury: enabled,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Freeze one business (protocol authority only, not the employer)
+    ///
+    /// A surgical alternative to pausing the protocol: while frozen, the
+    /// business takes no deposits, employee contributions or new employees,
+    /// earmarks no prefunding and pays out no withdrawals. Every other
+    /// business keeps running. Admin settings
+    /// and TEE settlement are unaffected, so accrued state stays correct.
+    pub fn freeze_business(ctx: Context<FreezeBusiness>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let entry = &mut ctx.accounts.business_entry;
+        entry.is_frozen = true;
+
+        msg!("🧊 Business frozen");
+        msg!("   Entry Index: {}", entry.entry_index);
+
+        emit!(BusinessFrozen {
+            entry_index: entry.entry_index,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Lift a `freeze_business` freeze (protocol authority only)
+    pub fn unfreeze_business(ctx: Context<FreezeBusiness>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let entry = &mut ctx.accounts.business_entry;
+        entry.is_frozen = false;
+
+        msg!("🔥 Business unfrozen");
+        msg!("   Entry Index: {}", entry.entry_index);
+
+        emit!(BusinessUnfrozen {
+            entry_index: entry.entry_index,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set an encrypted salary band for a business (vault authority only)
+    ///
+    /// Once set, `add_employee` must pass the SalaryBand PDA and every new
+    /// salary is clamped into [min, max] with e_ge/e_select. The bounds and
+    /// comparison results stay encrypted, so instead of aborting (which
+    /// would require decrypting the comparison) an out-of-band salary is
+    /// pinned to the nearest bound. `SalaryChanged` carries the encrypted
+    /// `above_band_min`/`below_band_max` flags so the employer can see
+    /// (after decrypting) that a salary was clamped and re-issue it.
+    pub fn set_salary_band(
+        ctx: Context<SetSalaryBand>,
+        encrypted_min: Vec<u8>,
+        encrypted_max: Vec<u8>,
+    ) -> Result<()> {
+        validate_ciphertext(&encrypted_min)?;
+        validate_ciphertext(&encrypted_max)?;
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let entry = &mut ctx.accounts.business_entry;
+        let band = &mut ctx.accounts.salary_band;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        band.encrypted_min = new_euint128(cpi_ctx, encrypted_min, 0)?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        band.encrypted_max = new_euint128(cpi_ctx, encrypted_max, 0)?;
+
+        band.business_entry = entry.key();
+        band.bump = ctx.bumps.salary_band;
+        entry.has_salary_band = true;
+
+        msg!("📏 Salary band set (ENCRYPTED)");
+        msg!("   Entry Index: {}", entry.entry_index);
+
+        emit!(SalaryBandUpdated {
+            entry_index: entry.entry_index,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
     /// Deposit funds to master vault
     ///
     /// Funds go to the single master vault. Business allocation
     /// is tracked via encrypted balance in BusinessEntry.
     ///
     /// PRIVACY: Only encrypted_amount is used - no plaintext amount parameter
+    ///
+    /// If `deposit_receipt` is provided, a DepositReceipt PDA is created
+    /// holding the encrypted amount handle, giving the depositor an
+    /// addressable record. Omit it to keep the minimal on-chain footprint.
+    ///
+    /// If `idempotency_key` is provided, a DepositGuard PDA seeded by it is
+    /// created; a retried deposit with the same key fails the `init`, so
+    /// the balance is credited exactly once. Reclaim the guard's rent with
+    /// `close_deposit_guard` after confirmation.
+    ///
+    /// `input_type` is the Inco encoding of `encrypted_amount` (0 = ciphertext).
     pub fn deposit(
         ctx: Context<Deposit>,
         encrypted_amount: Vec<u8>,
+        idempotency_key: Option<[u8; 16]>,
+        input_type: u8,
     ) -> Result<()> {
-        require!(!encrypted_amount.is_empty(), BagelError::InvalidCiphertext);
+        require!(!ctx.accounts.business_entry.is_frozen, BagelError::BusinessIsFrozen);
+        require_entry_in_vault(&ctx.accounts.business_entry, &ctx.accounts.master_vault.key())?;
+        validate_ciphertext(&encrypted_amount)?;
+        validate_input_type(input_type)?;
+        require!(
+            idempotency_key.is_some() == ctx.accounts.deposit_guard.is_some(),
+            BagelError::InvalidState
+        );
 
         let vault = &mut ctx.accounts.master_vault;
         let entry = &mut ctx.accounts.business_entry;
@@ -330,6 +927,7 @@ pub mod bagel {
         let depositor_token = ctx.accounts.depositor_token_account.as_ref().unwrap();
         let vault_token = ctx.accounts.master_vault_token_account.as_ref().unwrap();
         let inco_token_program = ctx.accounts.inco_token_program.as_ref().unwrap();
+        require_vault_token_account(vault_token, &vault.key(), &vault.confidential_mint)?;
 
         // Build CPI context for confidential token transfer
         let depositor_info = ctx.accounts.depositor.to_account_info();
@@ -345,8 +943,10 @@ pub mod bagel {
             cpi_accounts,
         );
 
-        // Transfer with encrypted amount (input_type = 0 for hex-encoded ciphertext)
-        transfer(cpi_ctx, encrypted_amount.clone(), 0)?;
+        require_transfer_authority(&cpi_ctx.accounts.source, cpi_ctx.accounts.authority.key)?;
+
+        // Transfer with encrypted amount in the caller's encoding
+        transfer(cpi_ctx, encrypted_amount.clone(), input_type)?;
 
         msg!("✅ Confidential deposit completed");
 
@@ -358,7 +958,7 @@ pub mod bagel {
             ctx.accounts.inco_lightning_program.to_account_info(),
             cpi_accounts1,
         );
-        let encrypted_deposit = new_euint128(cpi_ctx1, encrypted_amount, 0)?;
+        let encrypted_deposit = new_euint128(cpi_ctx1, encrypted_amount, input_type)?;
         
         let cpi_accounts2 = Operation {
             signer: ctx.accounts.depositor.to_account_info(),
@@ -370,23 +970,489 @@ pub mod bagel {
         entry.encrypted_balance = e_add(
             cpi_ctx2,
             entry.encrypted_balance.clone(),
-            encrypted_deposit,
+            encrypted_deposit.clone(),
             0,
         )?;
+        entry.is_funded = true;
+        track_vault_total(
+            &mut ctx.accounts.master_vault,
+            encrypted_deposit.clone(),
+            true,
+            &ctx.accounts.inco_lightning_program.to_account_info(),
+            &ctx.accounts.depositor.to_account_info(),
+        )?;
 
         msg!("💰 Deposit received (PRIVATE)");
         msg!("   Entry: {}", entry.entry_index);
         msg!("   Amount: ENCRYPTED");
 
-        emit!(FundsDeposited {
-            entry_index: entry.entry_index,
-            timestamp: Clock::get()?.unix_timestamp,
+        // Optional receipt (opt-in)
+        if let Some(receipt) = ctx.accounts.deposit_receipt.as_mut() {
+            let receipt_index = entry.next_receipt_index;
+            entry.next_receipt_index += 1;
+
+            receipt.business_entry = entry.key();
+            receipt.receipt_index = receipt_index;
+            receipt.depositor = ctx.accounts.depositor.key();
+            receipt.encrypted_amount = encrypted_deposit;
+            receipt.timestamp = Clock::get()?.unix_timestamp;
+            receipt.bump = ctx.bumps.deposit_receipt.unwrap_or_default();
+
+            msg!("🧾 Deposit receipt created");
+            msg!("   Receipt Index: {}", receipt_index);
+
+            emit!(DepositReceiptCreated {
+                entry_index: entry.entry_index,
+                receipt_index,
+                timestamp: receipt.timestamp,
+            });
+        }
+
+        // Optional idempotency guard (its init already rejected any replay)
+        if let (Some(key), Some(guard)) = (idempotency_key, ctx.accounts.deposit_guard.as_mut()) {
+            guard.business_entry = entry.key();
+            guard.depositor = ctx.accounts.depositor.key();
+            guard.idempotency_key = key;
+            guard.created_at = Clock::get()?.unix_timestamp;
+            guard.bump = ctx.bumps.deposit_guard.unwrap_or_default();
+        }
+
+        emit!(FundsDeposited {
+            entry_index: entry.entry_index,
+            timestamp: Clock::get()?.unix_timestamp,
             // Amount NOT included for privacy
         });
 
         Ok(())
     }
 
+    /// Deposit to several businesses in one instruction
+    ///
+    /// `remaining_accounts` holds the BusinessEntry PDAs, one per
+    /// allocation and in the same order. Each allocation is transferred
+    /// and `e_add`ed into its business balance inside this instruction, so
+    /// the ledger credit always equals the tokens moved - an encrypted
+    /// total cannot be checked against a separately transferred sum.
+    ///
+    /// PRIVACY: Only encrypted amounts are used - no plaintext amounts
+    pub fn deposit_multi<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DepositMulti<'info>>,
+        allocations: Vec<DepositAllocation>,
+    ) -> Result<()> {
+        require!(
+            !allocations.is_empty() && allocations.len() <= MAX_DEPOSIT_ALLOCATIONS,
+            BagelError::InvalidAmount
+        );
+        validate_batch_accounts(ctx.remaining_accounts, allocations.len())?;
+
+        let vault = &ctx.accounts.master_vault;
+        require!(
+            vault.use_confidential_tokens && vault.confidential_mint != Pubkey::default(),
+            BagelError::InvalidState
+        );
+        require_vault_token_account(
+            &ctx.accounts.master_vault_token_account,
+            &vault.key(),
+            &vault.confidential_mint,
+        )?;
+
+        let mut total_handle: Option<Euint128> = None;
+
+        for (allocation, entry_info) in allocations.into_iter().zip(ctx.remaining_accounts.iter()) {
+            validate_ciphertext(&allocation.encrypted_amount)?;
+
+            let mut entry: Account<BusinessEntry> = Account::try_from(entry_info)?;
+            let (expected, _) = Pubkey::find_program_address(
+                &[BUSINESS_ENTRY_SEED, vault.key().as_ref(), &allocation.entry_index.to_le_bytes()],
+                &crate::ID,
+            );
+            require!(
+                entry_info.key() == expected && entry.entry_index == allocation.entry_index,
+                BagelError::InvalidState
+            );
+            require!(entry_info.is_writable, BagelError::InvalidState);
+            require!(!entry.is_frozen, BagelError::BusinessIsFrozen);
+
+            let cpi_accounts = IncoTransfer {
+                source: ctx.accounts.depositor_token_account.to_account_info(),
+                destination: ctx.accounts.master_vault_token_account.to_account_info(),
+                authority: ctx.accounts.depositor.to_account_info(),
+                inco_lightning_program: ctx.accounts.inco_lightning_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_token_program.to_account_info(),
+                cpi_accounts,
+            );
+            require_transfer_authority(&cpi_ctx.accounts.source, cpi_ctx.accounts.authority.key)?;
+            transfer(cpi_ctx, allocation.encrypted_amount.clone(), 0)?;
+
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                Operation {
+                    signer: ctx.accounts.depositor.to_account_info(),
+                },
+            );
+            let encrypted_allocation = new_euint128(cpi_ctx, allocation.encrypted_amount, 0)?;
+
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                Operation {
+                    signer: ctx.accounts.depositor.to_account_info(),
+                },
+            );
+            entry.encrypted_balance = e_add(
+                cpi_ctx,
+                entry.encrypted_balance.clone(),
+                encrypted_allocation.clone(),
+                0,
+            )?;
+            entry.is_funded = true;
+            entry.exit(&crate::ID)?;
+
+            total_handle = Some(match total_handle {
+                None => encrypted_allocation,
+                Some(total) => {
+                    let cpi_ctx = CpiContext::new(
+                        ctx.accounts.inco_lightning_program.to_account_info(),
+                        Operation {
+                            signer: ctx.accounts.depositor.to_account_info(),
+                        },
+                    );
+                    e_add(cpi_ctx, total, encrypted_allocation, 0)?
+                }
+            });
+        }
+
+        if let Some(total) = total_handle.clone() {
+            track_vault_total(
+                &mut ctx.accounts.master_vault,
+                total,
+                true,
+                &ctx.accounts.inco_lightning_program.to_account_info(),
+                &ctx.accounts.depositor.to_account_info(),
+            )?;
+        }
+
+        let count = ctx.remaining_accounts.len() as u64;
+
+        msg!("💰 Multi-deposit received (PRIVATE)");
+        msg!("   Businesses: {}", count);
+        msg!("   Amounts: ENCRYPTED");
+
+        emit!(MultiDeposit {
+            total_handle: total_handle.unwrap_or_default(),
+            count,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Prove a business balance is at least a threshold (encrypted)
+    ///
+    /// Compares `encrypted_balance >= threshold` with Inco `e_ge` and
+    /// returns the resulting encrypted bool handle via return data. The
+    /// balance is never revealed; a verifier with decrypt access to the
+    /// handle learns only the pass/fail result.
+    ///
+    /// Vault authority only (acting for the employer, whose id is
+    /// encrypted), so third parties cannot binary-search the balance with
+    /// repeated thresholds.
+    pub fn prove_balance_above(
+        ctx: Context<ProveBalanceAbove>,
+        threshold_ciphertext: Vec<u8>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+        validate_ciphertext(&threshold_ciphertext)?;
+
+        let entry = &ctx.accounts.business_entry;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        let threshold = new_euint128(cpi_ctx, threshold_ciphertext, 0)?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        let result = e_ge(cpi_ctx, entry.encrypted_balance.clone(), threshold, 0)?;
+
+        let mut data = Vec::new();
+        result.serialize(&mut data).map_err(|_| BagelError::InvalidState)?;
+        anchor_lang::solana_program::program::set_return_data(&data);
+
+        msg!("🔏 Proof of funds computed (ENCRYPTED)");
+        msg!("   Entry Index: {}", entry.entry_index);
+
+        Ok(())
+    }
+
+    /// Return a business's encrypted employee-count handle to the employer
+    ///
+    /// Vault authority only (acting for the employer; the employer id is
+    /// itself encrypted, so there is no plaintext identity to match).
+    /// Grants the authority decrypt access through an Inco `allow` CPI
+    /// when its allowance PDA does not exist yet, then writes the 16-byte
+    /// handle via return data for off-chain decryption. Observers still
+    /// see only the handle.
+    pub fn reveal_employee_count(ctx: Context<RevealEmployeeCount>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let entry = &ctx.accounts.business_entry;
+        let mut data = Vec::new();
+        entry
+            .encrypted_employee_count
+            .serialize(&mut data)
+            .map_err(|_| BagelError::InvalidState)?;
+        let handle: [u8; 16] = data.as_slice().try_into().map_err(|_| BagelError::InvalidState)?;
+
+        let authority = ctx.accounts.authority.key();
+        require!(
+            ctx.accounts.allowance_account.key()
+                == allowance_pda(&handle, &authority, &INCO_LIGHTNING_ID),
+            BagelError::InvalidState
+        );
+
+        if ctx.accounts.allowance_account.data_is_empty() {
+            let ix = allow_instruction(&INCO_LIGHTNING_ID, &handle, &authority, &authority);
+            anchor_lang::solana_program::program::invoke(
+                &ix,
+                &[
+                    ctx.accounts.allowance_account.to_account_info(),
+                    ctx.accounts.authority.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                    ctx.accounts.inco_lightning_program.to_account_info(),
+                ],
+            )?;
+            msg!("🔑 Decrypt access granted to employer");
+        }
+
+        anchor_lang::solana_program::program::set_return_data(&data);
+
+        msg!("👥 Employee count handle returned (ENCRYPTED)");
+        msg!("   Entry Index: {}", entry.entry_index);
+
+        Ok(())
+    }
+
+    /// Estimate how many seconds a business balance can fund one stream
+    ///
+    /// Vault authority only. Computes `balance * 10^rate_scale / salary`
+    /// as an encrypted quotient and returns the handle via return data
+    /// for the employer to decrypt. A zero salary yields
+    /// `accrual::RUNWAY_UNBOUNDED` rather than an Inco divide-by-zero: the
+    /// divisor is swapped for 1 with `e_select` and the result replaced.
+    /// Rates are taken as stored, so for USD-denominated businesses the
+    /// estimate assumes a 1:1 price.
+    pub fn stream_runway(ctx: Context<StreamRunway>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let business = &ctx.accounts.business_entry;
+        let employee = &ctx.accounts.employee_entry;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        let zero = as_euint128(cpi_ctx, 0)?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        let is_zero_salary = e_ge(cpi_ctx, zero, employee.encrypted_salary.clone(), 0)?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        let one = as_euint128(cpi_ctx, 1)?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        let divisor = e_select(
+            cpi_ctx,
+            is_zero_salary.clone(),
+            one,
+            employee.encrypted_salary.clone(),
+            0,
+        )?;
+
+        let mut numerator = business.encrypted_balance.clone();
+        if business.rate_scale > 0 {
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                Operation {
+                    signer: ctx.accounts.authority.to_account_info(),
+                },
+            );
+            let scale = as_euint128(cpi_ctx, accrual::rate_divisor(business.rate_scale)?)?;
+
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                Operation {
+                    signer: ctx.accounts.authority.to_account_info(),
+                },
+            );
+            numerator = e_mul(cpi_ctx, numerator, scale, 0)?;
+        }
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        let quotient = e_div(cpi_ctx, numerator, divisor, 0)?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        let unbounded = as_euint128(cpi_ctx, accrual::RUNWAY_UNBOUNDED)?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        let runway = e_select(cpi_ctx, is_zero_salary, unbounded, quotient, 0)?;
+
+        let mut data = Vec::new();
+        runway.serialize(&mut data).map_err(|_| BagelError::InvalidState)?;
+        anchor_lang::solana_program::program::set_return_data(&data);
+
+        msg!("⏳ Stream runway computed (ENCRYPTED)");
+        msg!("   Business Index: {}", business.entry_index);
+        msg!("   Employee Index: {}", employee.employee_index);
+
+        Ok(())
+    }
+
+    /// Close a DepositGuard and return its rent to the depositor
+    ///
+    /// Only once the deposit is confirmed - after closing, the same
+    /// idempotency key is accepted again.
+    pub fn close_deposit_guard(_ctx: Context<CloseDepositGuard>) -> Result<()> {
+        msg!("🗑️  Deposit guard closed");
+        Ok(())
+    }
+
+    /// Contribute funds directly to an employee (tips/reimbursements)
+    ///
+    /// Anyone can call this. Funds go to the master vault like a deposit,
+    /// but are credited to the employee's `encrypted_accrued` instead of
+    /// the business balance, so they are withdrawable right away.
+    ///
+    /// PRIVACY: Only encrypted_amount is used - no plaintext amount parameter
+    pub fn employee_contribute(
+        ctx: Context<EmployeeContribute>,
+        encrypted_amount: Vec<u8>,
+    ) -> Result<()> {
+        require!(!ctx.accounts.business_entry.is_frozen, BagelError::BusinessIsFrozen);
+        validate_ciphertext(&encrypted_amount)?;
+
+        let vault = &ctx.accounts.master_vault;
+        let employee = &mut ctx.accounts.employee_entry;
+
+        require!(employee.is_active, BagelError::PayrollInactive);
+        require!(
+            vault.use_confidential_tokens && vault.confidential_mint != Pubkey::default(),
+            BagelError::InvalidState
+        );
+        require_vault_token_account(
+            &ctx.accounts.master_vault_token_account,
+            &vault.key(),
+            &vault.confidential_mint,
+        )?;
+
+        // Confidential transfer contributor -> vault
+        let cpi_accounts = IncoTransfer {
+            source: ctx.accounts.contributor_token_account.to_account_info(),
+            destination: ctx.accounts.master_vault_token_account.to_account_info(),
+            authority: ctx.accounts.contributor.to_account_info(),
+            inco_lightning_program: ctx.accounts.inco_lightning_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_token_program.to_account_info(),
+            cpi_accounts,
+        );
+        require_transfer_authority(&cpi_ctx.accounts.source, cpi_ctx.accounts.authority.key)?;
+        transfer(cpi_ctx, encrypted_amount.clone(), 0)?;
+
+        // Credit the employee's accrued balance
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.contributor.to_account_info(),
+            },
+        );
+        let encrypted_contribution = new_euint128(cpi_ctx, encrypted_amount, 0)?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.contributor.to_account_info(),
+            },
+        );
+        employee.encrypted_accrued = e_add(
+            cpi_ctx,
+            employee.encrypted_accrued.clone(),
+            encrypted_contribution.clone(),
+            0,
+        )?;
+        track_vault_total(
+            &mut ctx.accounts.master_vault,
+            encrypted_contribution.clone(),
+            true,
+            &ctx.accounts.inco_lightning_program.to_account_info(),
+            &ctx.accounts.contributor.to_account_info(),
+        )?;
+
+        msg!("🎁 Contribution received (PRIVATE)");
+        msg!("   Employee Index: {}", employee.employee_index);
+        msg!("   Amount: ENCRYPTED");
+
+        emit!(ContributionReceived {
+            business_index: ctx.accounts.business_entry.entry_index,
+            employee_index: employee.employee_index,
+            amount_handle: encrypted_contribution,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
     // ============================================================
     // Employee Entry Instructions (INDEX-BASED)
     // ============================================================
@@ -399,15 +1465,32 @@ pub mod bagel {
         ctx: Context<AddEmployee>,
         encrypted_employee_id: Vec<u8>, // Hash of employee pubkey, encrypted
         encrypted_salary: Vec<u8>,       // Salary rate, encrypted
+        end_timestamp: i64,              // Fixed-term end (0 = open-ended)
     ) -> Result<()> {
-        require!(!encrypted_employee_id.is_empty(), BagelError::InvalidCiphertext);
-        require!(!encrypted_salary.is_empty(), BagelError::InvalidCiphertext);
+        require!(!ctx.accounts.business_entry.is_frozen, BagelError::BusinessIsFrozen);
+        require_entry_in_vault(&ctx.accounts.business_entry, &ctx.accounts.master_vault.key())?;
+        validate_ciphertext(&encrypted_employee_id)?;
+        validate_ciphertext(&encrypted_salary)?;
 
         let vault = &mut ctx.accounts.master_vault;
         let business = &mut ctx.accounts.business_entry;
         let employee = &mut ctx.accounts.employee_entry;
         let clock = Clock::get()?;
 
+        // Treasury-paid onboarding: refund the employer the rent `init` took
+        if business.rent_from_treasury {
+            let rent = Rent::get()?;
+            let amount = rent.minimum_balance(EmployeeEntry::LEN);
+            let business_info = business.to_account_info();
+            require!(
+                amount <= treasury_spendable(&rent, business_info.lamports(), business_info.data_len()),
+                BagelError::InsufficientFunds
+            );
+            **business_info.try_borrow_mut_lamports()? -= amount;
+            **ctx.accounts.employer.to_account_info().try_borrow_mut_lamports()? += amount;
+            msg!("🏦 Employee rent paid by business treasury: {} lamports", amount);
+        }
+
         // Use next available index
         let employee_index = business.next_employee_index;
         business.next_employee_index += 1;
@@ -415,7 +1498,17 @@ pub mod bagel {
         employee.business_entry = business.key();
         employee.employee_index = employee_index;
         employee.last_action = clock.unix_timestamp;
+        employee.last_action_slot = clock.slot;
+        employee.pending_catchup_seconds = 0;
+        employee.last_withdrawal_at = clock.unix_timestamp;
         employee.is_active = true;
+        require!(
+            end_timestamp == 0 || end_timestamp > clock.unix_timestamp,
+            BagelError::InvalidTimestamp
+        );
+        employee.end_timestamp = end_timestamp;
+        cu_checkpoint!("add_employee: start");
+
         employee.bump = ctx.bumps.employee_entry;
 
         // Store encrypted employee ID
@@ -438,6 +1531,125 @@ pub mod bagel {
         );
         employee.encrypted_salary = new_euint128(cpi_ctx2, encrypted_salary, 0)?;
 
+        cu_checkpoint!("add_employee: id and salary stored");
+
+        // Enforce the business salary band (clamp, comparisons stay encrypted)
+        let mut band_flags = (None, None);
+        if business.has_salary_band {
+            let band = ctx.accounts.salary_band.as_ref().ok_or(BagelError::InvalidState)?;
+
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                Operation {
+                    signer: ctx.accounts.employer.to_account_info(),
+                },
+            );
+            let above_min = e_ge(
+                cpi_ctx,
+                employee.encrypted_salary.clone(),
+                band.encrypted_min.clone(),
+                0,
+            )?;
+
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                Operation {
+                    signer: ctx.accounts.employer.to_account_info(),
+                },
+            );
+            let floored = e_select(
+                cpi_ctx,
+                above_min.clone(),
+                employee.encrypted_salary.clone(),
+                band.encrypted_min.clone(),
+                0,
+            )?;
+
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                Operation {
+                    signer: ctx.accounts.employer.to_account_info(),
+                },
+            );
+            let below_max = e_ge(cpi_ctx, band.encrypted_max.clone(), floored.clone(), 0)?;
+
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                Operation {
+                    signer: ctx.accounts.employer.to_account_info(),
+                },
+            );
+            employee.encrypted_salary = e_select(
+                cpi_ctx,
+                below_max.clone(),
+                floored,
+                band.encrypted_max.clone(),
+                0,
+            )?;
+            band_flags = (Some(above_min), Some(below_max));
+
+            msg!("   Salary band: ENFORCED");
+        }
+
+        // Clamp to the protocol ceiling for the vault token's decimals
+        let mut within_ceiling_flag = None;
+        if let Some(decimals) = vault.token_decimals {
+            let ceiling =
+                accrual::max_salary_rate(decimals, business.rate_scale, business.salary_denom)?;
+
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                Operation {
+                    signer: ctx.accounts.employer.to_account_info(),
+                },
+            );
+            let encrypted_ceiling = as_euint128(cpi_ctx, ceiling)?;
+
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                Operation {
+                    signer: ctx.accounts.employer.to_account_info(),
+                },
+            );
+            let within_ceiling = e_ge(
+                cpi_ctx,
+                encrypted_ceiling.clone(),
+                employee.encrypted_salary.clone(),
+                0,
+            )?;
+
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                Operation {
+                    signer: ctx.accounts.employer.to_account_info(),
+                },
+            );
+            employee.encrypted_salary = e_select(
+                cpi_ctx,
+                within_ceiling.clone(),
+                employee.encrypted_salary.clone(),
+                encrypted_ceiling,
+                0,
+            )?;
+            within_ceiling_flag = Some(within_ceiling);
+
+            msg!("   Salary ceiling: ENFORCED ({} decimals)", decimals);
+        }
+
+        // Audit trail: initial rate (no previous salary)
+        emit!(SalaryChanged {
+            business_index: business.entry_index,
+            employee_index,
+            old_salary_handle: Euint128::default(),
+            new_salary_handle: employee.encrypted_salary.clone(),
+            effective_at: clock.unix_timestamp,
+            above_band_min: band_flags.0,
+            below_band_max: band_flags.1,
+            within_ceiling: within_ceiling_flag,
+        });
+
+        cu_checkpoint!("add_employee: salary band");
+
         // Initialize encrypted accrued to zero
         let zero_ciphertext = vec![0u8; 16];
         let cpi_accounts3 = Operation {
@@ -447,7 +1659,19 @@ pub mod bagel {
             ctx.accounts.inco_lightning_program.to_account_info(),
             cpi_accounts3,
         );
-        employee.encrypted_accrued = new_euint128(cpi_ctx3, zero_ciphertext, 0)?;
+        employee.encrypted_accrued = new_euint128(cpi_ctx3, zero_ciphertext.clone(), 0)?;
+
+        // Initialize encrypted lifetime payouts to zero
+        let cpi_ctx_total = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.employer.to_account_info(),
+            },
+        );
+        employee.encrypted_total_withdrawn = new_euint128(cpi_ctx_total, zero_ciphertext, 0)?;
+        employee.suspended_at = 0;
+
+        cu_checkpoint!("add_employee: balances initialized");
 
         // Increment business and master encrypted employee counts
         let one_ciphertext = vec![1u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
@@ -497,6 +1721,8 @@ pub mod bagel {
             0,
         )?;
 
+        cu_checkpoint!("add_employee: counts updated");
+
         msg!("👷 Employee added (Maximum Privacy)");
         msg!("   Business Entry: {}", business.entry_index);
         msg!("   Employee Index: {} (no pubkey visible)", employee_index);
@@ -510,32 +1736,388 @@ pub mod bagel {
             // NOTE: No pubkeys in event for privacy
         });
 
+        // Soft warning only; never blocks the add
+        if business.soft_cap > 0 && business.next_employee_index == business.soft_cap {
+            msg!("⚠️ Employee soft cap reached: {}", business.soft_cap);
+
+            emit!(EmployeeSoftCapReached {
+                business_index: business.entry_index,
+                count: business.next_employee_index,
+            });
+        }
+
         Ok(())
     }
 
-    /// Request withdrawal (employee proves identity via signature)
-    ///
-    /// Employee signs to prove they own the wallet. Program verifies
-    /// against encrypted_employee_id. Amount comes from encrypted_accrued.
+    /// List which employees in an index range are active
     ///
-    /// PRIVACY: Only encrypted_amount is used - no plaintext amount parameter
-    pub fn request_withdrawal(
-        ctx: Context<RequestWithdrawal>,
-        encrypted_amount: Vec<u8>,
+    /// `remaining_accounts` must hold the EmployeeEntry PDAs for indices
+    /// `start..start + count`, in order. Returns (via return data) a bitmap
+    /// where bit `i` is set if employee `start + i` exists and is active.
+    /// Nothing is decrypted - only the public `is_active` flag is read.
+    pub fn list_active<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ListActive<'info>>,
+        start: u64,
+        count: u64,
+    ) -> Result<()> {
+        require!(count <= MAX_LIST_ACTIVE, BagelError::InvalidAmount);
+        validate_batch_accounts(ctx.remaining_accounts, count as usize)?;
+
+        let business_key = ctx.accounts.business_entry.key();
+        let mut bitmap = vec![0u8; ((count + 7) / 8) as usize];
+
+        for (i, account_info) in ctx.remaining_accounts.iter().enumerate() {
+            let employee_index = start
+                .checked_add(i as u64)
+                .ok_or(BagelError::Overflow)?;
+
+            let index_bytes = employee_index.to_le_bytes();
+
+            // Never created (or closed) - no stored bump, so derive the
+            // canonical address and leave the bit clear
+            if account_info.data_is_empty() {
+                let (expected, _) = Pubkey::find_program_address(
+                    &[EMPLOYEE_ENTRY_SEED, business_key.as_ref(), &index_bytes],
+                    &crate::ID,
+                );
+                require!(account_info.key() == expected, BagelError::InvalidState);
+                continue;
+            }
+
+            // Live entry: re-derive with its stored bump (one hash, no search)
+            let employee: Account<EmployeeEntry> = Account::try_from(account_info)?;
+            let expected = Pubkey::create_program_address(
+                &[EMPLOYEE_ENTRY_SEED, business_key.as_ref(), &index_bytes, &[employee.bump]],
+                &crate::ID,
+            )
+            .map_err(|_| BagelError::InvalidState)?;
+            require!(account_info.key() == expected, BagelError::InvalidState);
+
+            if employee.is_active {
+                bitmap[i / 8] |= 1 << (i % 8);
+            }
+        }
+
+        anchor_lang::solana_program::program::set_return_data(&bitmap);
+
+        Ok(())
+    }
+
+    /// Export a business's encrypted handles for backup (vault authority only)
+    ///
+    /// `remaining_accounts` must hold the EmployeeEntry PDAs for indices
+    /// `start..start + len`, in order, at most `MAX_EXPORT_EMPLOYEES`.
+    /// Returns (via return data) a borsh `BusinessExport` with the
+    /// business handles and settings and one `EmployeeExport` per
+    /// existing entry; indices never created or already closed are
+    /// skipped. Only handles are exported, so the bundle is as private as
+    /// the accounts it was read from. Call repeatedly with a moving
+    /// `start` for larger businesses.
+    ///
+    /// The bundle hash is committed in `BusinessExported`; the authority
+    /// signs `export_bundle_message` over the same bytes for
+    /// `import_business` / `import_employees`.
+    pub fn export_business<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExportBusiness<'info>>,
+        start: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+        require!(
+            ctx.remaining_accounts.len() <= MAX_EXPORT_EMPLOYEES,
+            BagelError::InvalidAmount
+        );
+        validate_batch_accounts(ctx.remaining_accounts, ctx.remaining_accounts.len())?;
+
+        let business = &ctx.accounts.business_entry;
+        let business_key = business.key();
+        let mut employees = Vec::with_capacity(ctx.remaining_accounts.len());
+
+        for (i, account_info) in ctx.remaining_accounts.iter().enumerate() {
+            let employee_index = start
+                .checked_add(i as u64)
+                .ok_or(BagelError::Overflow)?;
+
+            let (expected, _) = Pubkey::find_program_address(
+                &[EMPLOYEE_ENTRY_SEED, business_key.as_ref(), &employee_index.to_le_bytes()],
+                &crate::ID,
+            );
+            require!(account_info.key() == expected, BagelError::InvalidState);
+
+            if account_info.data_is_empty() {
+                continue;
+            }
+
+            let employee: Account<EmployeeEntry> = Account::try_from(account_info)?;
+            employees.push(EmployeeExport {
+                employee_index,
+                is_active: employee.is_active,
+                last_action: employee.last_action,
+                suspended_at: employee.suspended_at,
+                end_timestamp: employee.end_timestamp,
+                accrual_basis: employee.accrual_basis,
+                withholding_bps: employee.withholding_bps,
+                has_budget: employee.has_budget,
+                has_prefunded: employee.has_prefunded,
+                pending_catchup_seconds: employee.pending_catchup_seconds,
+                last_withdrawal_at: employee.last_withdrawal_at,
+                allow_immediate_first_withdrawal: employee.allow_immediate_first_withdrawal,
+                first_withdrawal_done: employee.first_withdrawal_done,
+                withdraw_authority: employee.withdraw_authority,
+                encrypted_employee_id: employee.encrypted_employee_id.clone(),
+                encrypted_salary: employee.encrypted_salary.clone(),
+                encrypted_accrued: employee.encrypted_accrued.clone(),
+                encrypted_total_withdrawn: employee.encrypted_total_withdrawn.clone(),
+                encrypted_budget: employee.encrypted_budget.clone(),
+                encrypted_prefunded: employee.encrypted_prefunded.clone(),
+            });
+        }
+
+        let export = BusinessExport {
+            version: EXPORT_VERSION,
+            entry_index: business.entry_index,
+            next_employee_index: business.next_employee_index,
+            encryption_context: business.encryption_context,
+            encrypted_employer_id: business.encrypted_employer_id.clone(),
+            encrypted_balance: business.encrypted_balance.clone(),
+            encrypted_employee_count: business.encrypted_employee_count.clone(),
+            rounding_mode: business.rounding_mode,
+            withdrawal_delay: business.withdrawal_delay,
+            rate_scale: business.rate_scale,
+            salary_denom: business.salary_denom,
+            soft_cap: business.soft_cap,
+            refund_commitment: business.refund_commitment,
+            encrypted_closed_unpaid: business.encrypted_closed_unpaid.clone(),
+            has_closed_unpaid: business.has_closed_unpaid,
+            start,
+            end: start
+                .checked_add(ctx.remaining_accounts.len() as u64)
+                .ok_or(BagelError::Overflow)?,
+            employees,
+        };
+
+        let mut data = Vec::new();
+        export.serialize(&mut data).map_err(|_| BagelError::InvalidState)?;
+        anchor_lang::solana_program::program::set_return_data(&data);
+
+        emit!(BusinessExported {
+            entry_index: business.entry_index,
+            start,
+            bundle_hash: anchor_lang::solana_program::hash::hash(&data).to_bytes(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Recreate a business from an `export_business` bundle (vault authority only)
+    ///
+    /// Takes the bundle starting at employee index 0 and creates the
+    /// BusinessEntry, which must not exist yet and must land on the same
+    /// index (businesses are imported in order into a deployment whose
+    /// `next_business_index` matches). Remaining bundles go through
+    /// `import_employees`. `remaining_accounts` must hold the
+    /// uninitialized EmployeeEntry PDAs for the bundle's employees, in
+    /// order; they are created with the exported indices, handles and
+    /// settings, so every PDA derives exactly as before.
+    ///
+    /// `signature` is the vault authority's Ed25519 signature over
+    /// `export_bundle_message(bundle)`, verified from a preceding Ed25519
+    /// program instruction. The salary band is not exported: re-apply it
+    /// with `set_salary_band`. Vault counters are not adjusted and no
+    /// tokens move: fund the new vault token account separately.
+    pub fn import_business<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ImportBusiness<'info>>,
+        entry_index: u64,
+        bundle: Vec<u8>,
+        signature: [u8; 64],
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+        let export = verified_export(
+            &bundle,
+            &signature,
+            &ctx.accounts.instructions_sysvar.to_account_info(),
+            &ctx.accounts.master_vault.authority,
+        )?;
+        require!(export.entry_index == entry_index, BagelError::InvalidBundle);
+        require!(export.start == 0, BagelError::InvalidBundle);
+
+        let vault = &mut ctx.accounts.master_vault;
+        require!(entry_index == vault.next_business_index, BagelError::InvalidState);
+        vault.next_business_index += 1;
+
+        let entry = &mut ctx.accounts.business_entry;
+        entry.master_vault = vault.key();
+        entry.entry_index = entry_index;
+        entry.encrypted_employer_id = export.encrypted_employer_id.clone();
+        entry.encrypted_balance = export.encrypted_balance.clone();
+        entry.encrypted_employee_count = export.encrypted_employee_count.clone();
+        entry.next_employee_index = export.next_employee_index;
+        entry.next_receipt_index = 0;
+        entry.rounding_mode = export.rounding_mode;
+        entry.withdrawal_delay = export.withdrawal_delay;
+        entry.has_salary_band = false;
+        entry.rate_scale = export.rate_scale;
+        entry.salary_denom = export.salary_denom;
+        entry.soft_cap = export.soft_cap;
+        entry.refund_commitment = export.refund_commitment;
+        entry.encrypted_closed_unpaid = export.encrypted_closed_unpaid.clone();
+        entry.has_closed_unpaid = export.has_closed_unpaid;
+        entry.is_funded = false;
+        entry.rent_from_treasury = false;
+        entry.encryption_context = export.encryption_context;
+        entry.is_frozen = false;
+        entry.is_active = true;
+        entry.bump = ctx.bumps.business_entry;
+        entry.import_cursor = next_import_cursor(&export);
+
+        let clock = Clock::get()?;
+        create_imported_employees(
+            entry,
+            &export,
+            ctx.remaining_accounts,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &clock,
+        )?;
+
+        msg!("📦 Business imported");
+        msg!("   Entry Index: {}", entry_index);
+        msg!("   Employees: {}", export.employees.len());
+
+        emit!(BusinessImported {
+            entry_index,
+            start: export.start,
+            employee_count: export.employees.len() as u8,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Continue an import with the next `export_business` bundle (vault authority only)
+    ///
+    /// The bundle must start exactly where the previous one ended
+    /// (`import_cursor`) and match the imported business; it is signed
+    /// and verified like `import_business`. Once the last bundle is in,
+    /// the cursor clears and the business takes no further imports.
+    pub fn import_employees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ImportEmployees<'info>>,
+        bundle: Vec<u8>,
+        signature: [u8; 64],
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+        let export = verified_export(
+            &bundle,
+            &signature,
+            &ctx.accounts.instructions_sysvar.to_account_info(),
+            &ctx.accounts.master_vault.authority,
+        )?;
+
+        let entry = &mut ctx.accounts.business_entry;
+        require!(
+            entry.import_cursor != 0 && export.start == entry.import_cursor,
+            BagelError::InvalidBundle
+        );
+        require!(
+            export.entry_index == entry.entry_index
+                && export.encryption_context == entry.encryption_context
+                && export.next_employee_index == entry.next_employee_index,
+            BagelError::InvalidBundle
+        );
+        entry.import_cursor = next_import_cursor(&export);
+
+        let clock = Clock::get()?;
+        create_imported_employees(
+            entry,
+            &export,
+            ctx.remaining_accounts,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &clock,
+        )?;
+
+        msg!("📦 Employees imported");
+        msg!("   Entry Index: {}", entry.entry_index);
+        msg!("   Employees: {}", export.employees.len());
+
+        emit!(BusinessImported {
+            entry_index: entry.entry_index,
+            start: export.start,
+            employee_count: export.employees.len() as u8,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Request withdrawal (employee proves identity via signature)
+    ///
+    /// Only the employee's registered `withdraw_authority` may sign, and
+    /// `employee_token_account` must be that wallet's Inco Token account
+    /// for the vault mint. Amount comes from encrypted_accrued.
+    ///
+    /// PRIVACY: Only encrypted_amount is used - no plaintext amount parameter
+    ///
+    /// When `use_shadowwire` is set, `shadowwire_proof` must carry the
+    /// commitment and range proof; it is size-checked and verified before
+    /// any funds move (verifier is mocked on devnet).
+    ///
+    /// `reference` is an optional invoice/reference id echoed in the event
+    /// for off-chain reconciliation (zeros when omitted). `input_type` is
+    /// the Inco encoding of `encrypted_amount` and must be 0 (ciphertext):
+    /// a plaintext payout would publish the amount.
+    ///
+    /// With `withholding_bps` set, `encrypted_amount` is the employee's net
+    /// share and `encrypted_withheld` (same encoding) goes to the
+    /// `WithholdingConfig` tax account; the employee is debited for both.
+    ///
+    /// Rejects with `WithdrawalDelayActive` when the business sets a
+    /// `withdrawal_delay`; its payouts go through `initiate_withdrawal`.
+    pub fn request_withdrawal(
+        ctx: Context<RequestWithdrawal>,
+        encrypted_amount: Vec<u8>,
         use_shadowwire: bool, // Optional ZK amount hiding (simulated on devnet)
+        shadowwire_proof: Option<ShadowWireProof>,
+        reference: Option<[u8; 16]>,
+        input_type: u8,
+        encrypted_withheld: Option<Vec<u8>>,
     ) -> Result<()> {
-        require!(!encrypted_amount.is_empty(), BagelError::InvalidCiphertext);
+        require!(!ctx.accounts.business_entry.is_frozen, BagelError::BusinessIsFrozen);
+        require!(
+            ctx.accounts.business_entry.withdrawal_delay == 0,
+            BagelError::WithdrawalDelayActive
+        );
+        require_entry_in_vault(&ctx.accounts.business_entry, &ctx.accounts.master_vault.key())?;
+        validate_ciphertext(&encrypted_amount)?;
+        validate_payout_input_type(input_type)?;
 
         let vault = &mut ctx.accounts.master_vault;
         let employee = &mut ctx.accounts.employee_entry;
         let clock = Clock::get()?;
 
+        cu_checkpoint!("request_withdrawal: start");
+
         require!(employee.is_active, BagelError::PayrollInactive);
+        require_withdraw_authority(employee, &ctx.accounts.withdrawer.key())?;
 
-        let time_elapsed = clock.unix_timestamp
-            .checked_sub(employee.last_action)
-            .ok_or(BagelError::InvalidTimestamp)?;
-        require!(time_elapsed >= MIN_WITHDRAW_INTERVAL, BagelError::WithdrawTooSoon);
+        let Some(time_elapsed) = withdraw_elapsed(employee, clock.unix_timestamp)? else {
+            return Ok(());
+        };
+        check_withdraw_interval(
+            employee,
+            &ctx.accounts.business_entry,
+            time_elapsed,
+            clock.unix_timestamp,
+        )?;
 
         // PRIVACY: Only confidential token transfers are allowed
         // This ensures amount is NEVER visible on-chain
@@ -558,6 +2140,32 @@ pub mod bagel {
         let vault_token = ctx.accounts.master_vault_token_account.as_ref().unwrap();
         let employee_token = ctx.accounts.employee_token_account.as_ref().unwrap();
         let inco_token_program = ctx.accounts.inco_token_program.as_ref().unwrap();
+        require_payout_destination(employee_token, employee, &vault.confidential_mint)?;
+
+        // ShadowWire: verify the proof before any funds move
+        if use_shadowwire {
+            // Only the mock verifier is compiled in; refuse rather than
+            // accept a mock proof where the deployment declares ShadowWire real
+            require!(
+                !vault.provider_flags.contains(ProviderFlags::SHADOWWIRE_REAL),
+                BagelError::ProviderUnavailable
+            );
+            let proof = shadowwire_proof.ok_or(ShadowWireError::InvalidBulletproof)?;
+            let shadow_transfer = ShadowWireTransfer::new(
+                ctx.accounts.withdrawer.key(),
+                vault.confidential_mint,
+                proof.commitment,
+                proof.range_proof,
+            )?;
+            shadow_transfer.validate_proof_sizes()?;
+            require!(
+                shadow_transfer.verify_proof()?,
+                ShadowWireError::InvalidBulletproof
+            );
+            shadow_transfer.execute()?;
+        }
+
+        cu_checkpoint!("request_withdrawal: checks");
 
         // Build CPI context for confidential token transfer
         let bump = vault.bump;
@@ -575,8 +2183,52 @@ pub mod bagel {
             seeds,
         );
 
-        // Transfer with encrypted amount (input_type = 0 for hex-encoded ciphertext)
-        transfer(cpi_ctx, encrypted_amount.clone(), 0)?;
+        require_transfer_authority(&cpi_ctx.accounts.source, cpi_ctx.accounts.authority.key)?;
+
+        // Transfer with encrypted amount in the caller's encoding. The
+        // ledger debit below decodes these exact bytes with the same
+        // input_type; Inco Token reports no handle for what it moved,
+        // so re-decoding the one ciphertext is the only way to match it.
+        transfer(cpi_ctx, encrypted_amount.clone(), input_type)?;
+
+        // Tax withholding: the withheld share goes to the business tax account
+        let withheld_ciphertext = if employee.withholding_bps > 0 {
+            let withheld = encrypted_withheld.ok_or(BagelError::InvalidCiphertext)?;
+            validate_ciphertext(&withheld)?;
+            let config = ctx
+                .accounts
+                .withholding_config
+                .as_ref()
+                .ok_or(BagelError::InvalidState)?;
+            let tax_token = ctx
+                .accounts
+                .tax_token_account
+                .as_ref()
+                .ok_or(BagelError::InvalidState)?;
+            require!(
+                tax_token.key() == config.tax_token_account,
+                BagelError::InvalidState
+            );
+
+            let cpi_accounts = IncoTransfer {
+                source: vault_token.to_account_info(),
+                destination: tax_token.to_account_info(),
+                authority: vault.to_account_info(),
+                inco_lightning_program: ctx.accounts.inco_lightning_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                inco_token_program.to_account_info(),
+                cpi_accounts,
+                seeds,
+            );
+            transfer(cpi_ctx, withheld.clone(), input_type)?;
+            Some(withheld)
+        } else {
+            None
+        };
+
+        cu_checkpoint!("request_withdrawal: transfers");
 
         msg!("✅ Confidential withdrawal completed");
 
@@ -588,23 +2240,58 @@ pub mod bagel {
             ctx.accounts.inco_lightning_program.to_account_info(),
             cpi_accounts,
         );
-        let encrypted_withdrawal = new_euint128(cpi_ctx, encrypted_amount, 0)?;
+        // Same ciphertext as the transfer above (see there)
+        let net_handle = new_euint128(cpi_ctx, encrypted_amount, input_type)?;
+
+        let mut withheld_handle = Euint128::default();
+        let mut encrypted_withdrawal = net_handle.clone();
+        if let Some(withheld) = withheld_ciphertext {
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                Operation {
+                    signer: ctx.accounts.withdrawer.to_account_info(),
+                },
+            );
+            withheld_handle = new_euint128(cpi_ctx, withheld, input_type)?;
 
-        let cpi_accounts2 = Operation {
-            signer: ctx.accounts.withdrawer.to_account_info(),
-        };
-        let cpi_ctx2 = CpiContext::new(
-            ctx.accounts.inco_lightning_program.to_account_info(),
-            cpi_accounts2,
-        );
-        employee.encrypted_accrued = e_sub(
-            cpi_ctx2,
-            employee.encrypted_accrued.clone(),
-            encrypted_withdrawal,
-            0,
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                Operation {
+                    signer: ctx.accounts.withdrawer.to_account_info(),
+                },
+            );
+            encrypted_withdrawal = e_add(cpi_ctx, net_handle.clone(), withheld_handle.clone(), 0)?;
+
+            let met = withholding_met(
+                encrypted_withdrawal.clone(),
+                withheld_handle.clone(),
+                employee.withholding_bps,
+                &ctx.accounts.inco_lightning_program.to_account_info(),
+                &ctx.accounts.withdrawer.to_account_info(),
+            )?;
+            emit!(WithholdingApplied {
+                business_index: ctx.accounts.business_entry.entry_index,
+                employee_index: employee.employee_index,
+                withholding_bps: employee.withholding_bps,
+                met,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+
+        cu_checkpoint!("request_withdrawal: withdrawal handles");
+
+        record_payout(
+            employee,
+            &mut ctx.accounts.master_vault,
+            encrypted_withdrawal.clone(),
+            &ctx.accounts.inco_lightning_program.to_account_info(),
+            &ctx.accounts.withdrawer.to_account_info(),
         )?;
 
-        employee.last_action = clock.unix_timestamp;
+        cu_checkpoint!("request_withdrawal: ledger updated");
+
+        // Accrual keeps its own clock; the unsettled window stays owed
+        employee.last_withdrawal_at = clock.unix_timestamp;
 
         msg!("💸 Withdrawal processed (PRIVATE)");
         msg!("   Employee Index: {}", employee.employee_index);
@@ -613,1063 +2300,6641 @@ pub mod bagel {
             msg!("   ShadowWire: ENABLED");
         }
 
+        set_withdrawal_result(&WithdrawalResult {
+            employee_index: employee.employee_index,
+            shadowwire_used: use_shadowwire,
+            paid_handle: net_handle.clone(),
+            new_accrued_handle: employee.encrypted_accrued.clone(),
+            timestamp: clock.unix_timestamp,
+        })?;
+
         emit!(WithdrawalProcessed {
             business_index: ctx.accounts.business_entry.entry_index,
             employee_index: employee.employee_index,
             timestamp: clock.unix_timestamp,
             shadowwire_enabled: use_shadowwire,
+            reference: reference.unwrap_or_default(),
+            encrypted_total_withdrawn: employee.encrypted_total_withdrawn.clone(),
+            net_handle,
+            withheld_handle,
         });
 
         Ok(())
     }
 
-    /// Configure confidential token mint for private transfers
+    /// Auto payment (triggered by the TEE on schedule)
     ///
-    /// Sets the confidential mint address and enables confidential token mode.
-    /// Once enabled, deposits and withdrawals will use encrypted token transfers.
-    pub fn configure_confidential_mint(
-        ctx: Context<ConfigureConfidentialMint>,
-        mint: Pubkey,
-        enable: bool,
+    /// Same confidential payout as `request_withdrawal`, but signed by the
+    /// TEE validator instead of the employee and exempt from
+    /// `MIN_WITHDRAW_INTERVAL`, so the rollup can stream sub-interval
+    /// payouts. Manual withdrawals stay rate-limited.
+    pub fn auto_payment(
+        ctx: Context<AutoPayment>,
+        encrypted_amount: Vec<u8>,
     ) -> Result<()> {
-        let vault = &mut ctx.accounts.master_vault;
-        
+        require!(!ctx.accounts.business_entry.is_frozen, BagelError::BusinessIsFrozen);
+        validate_ciphertext(&encrypted_amount)?;
+
+        let vault = &ctx.accounts.master_vault;
+        let employee = &mut ctx.accounts.employee_entry;
+        let clock = Clock::get()?;
+
+        require!(employee.is_active, BagelError::PayrollInactive);
+        // Single-transfer path: withholding needs request_withdrawal's split
+        require!(employee.withholding_bps == 0, BagelError::WithholdingRequired);
         require!(
-            ctx.accounts.authority.key() == vault.authority,
-            BagelError::Unauthorized
+            vault.use_confidential_tokens && vault.confidential_mint != Pubkey::default(),
+            BagelError::InvalidState
         );
 
-        vault.confidential_mint = mint;
-        vault.use_confidential_tokens = enable;
+        msg!("⚡ Processing auto payment from TEE...");
 
-        msg!("🔒 Confidential token mint configured");
-        msg!("   Mint: {}", mint);
-        msg!("   Enabled: {}", enable);
+        let bump = vault.bump;
+        let seeds: &[&[&[u8]]] = &[&[MASTER_VAULT_SEED, &[bump]]];
+        let cpi_accounts = IncoTransfer {
+            source: ctx.accounts.master_vault_token_account.to_account_info(),
+            destination: ctx.accounts.employee_token_account.to_account_info(),
+            authority: vault.to_account_info(),
+            inco_lightning_program: ctx.accounts.inco_lightning_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.inco_token_program.to_account_info(),
+            cpi_accounts,
+            seeds,
+        );
+        require_transfer_authority(&cpi_ctx.accounts.source, cpi_ctx.accounts.authority.key)?;
+        transfer(cpi_ctx, encrypted_amount.clone(), 0)?;
 
-        emit!(ConfidentialMintConfigured {
-            mint,
-            enabled: enable,
-            timestamp: Clock::get()?.unix_timestamp,
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.tee_signer.to_account_info(),
+            },
+        );
+        let encrypted_payment = new_euint128(cpi_ctx, encrypted_amount, 0)?;
+
+        record_payout(
+            employee,
+            &mut ctx.accounts.master_vault,
+            encrypted_payment.clone(),
+            &ctx.accounts.inco_lightning_program.to_account_info(),
+            &ctx.accounts.tee_signer.to_account_info(),
+        )?;
+
+        employee.last_withdrawal_at = clock.unix_timestamp;
+
+        msg!("✅ Auto payment completed (PRIVATE)");
+        msg!("   Employee Index: {}", employee.employee_index);
+
+        set_withdrawal_result(&WithdrawalResult {
+            employee_index: employee.employee_index,
+            shadowwire_used: false,
+            paid_handle: encrypted_payment.clone(),
+            new_accrued_handle: employee.encrypted_accrued.clone(),
+            timestamp: clock.unix_timestamp,
+        })?;
+
+        emit!(WithdrawalProcessed {
+            business_index: ctx.accounts.business_entry.entry_index,
+            employee_index: employee.employee_index,
+            timestamp: clock.unix_timestamp,
+            shadowwire_enabled: false,
+            reference: [0u8; 16],
+            encrypted_total_withdrawn: employee.encrypted_total_withdrawn.clone(),
+            net_handle: encrypted_payment,
+            withheld_handle: Euint128::default(),
         });
 
         Ok(())
     }
 
-    /// Close Master Vault account (for migration/testing)
-    /// Transfers remaining lamports to authority
-    pub fn close_vault(ctx: Context<CloseVault>) -> Result<()> {
-        let vault = &ctx.accounts.master_vault;
-        
+    /// Withdraw one payout divided across several destinations
+    ///
+    /// For garnishments and savings splits. `remaining_accounts` holds the
+    /// destination Inco Token accounts, one per split and in the same
+    /// order; each must be for the vault's confidential mint and on the
+    /// employee's `SplitDestinations` allowlist. Shares are public basis
+    /// points summing to 10_000, but Inco transfers take a ciphertext
+    /// rather than a handle, so each split carries the client's
+    /// encryption of its share. The employee is debited for the sum, and
+    /// `SplitWithdrawal.shares_met` holds one encrypted flag per split,
+    /// true when its share covers `bps` of that sum (as for withholding).
+    ///
+    /// Signed by the employee's `withdraw_authority`. Rejects with
+    /// `WithdrawalDelayActive` when the business sets a
+    /// `withdrawal_delay`, like `request_withdrawal`.
+    pub fn withdraw_split<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawSplit<'info>>,
+        splits: Vec<SplitRecipient>,
+    ) -> Result<()> {
+        require!(!ctx.accounts.business_entry.is_frozen, BagelError::BusinessIsFrozen);
         require!(
-            ctx.accounts.authority.key() == vault.authority,
-            BagelError::Unauthorized
+            ctx.accounts.business_entry.withdrawal_delay == 0,
+            BagelError::WithdrawalDelayActive
         );
+        validate_split_bps(&splits.iter().map(|split| split.bps).collect::<Vec<_>>())?;
+        validate_batch_accounts(ctx.remaining_accounts, splits.len())?;
+
+        let vault = &ctx.accounts.master_vault;
+        let employee = &mut ctx.accounts.employee_entry;
+        let clock = Clock::get()?;
 
+        require!(employee.is_active, BagelError::PayrollInactive);
+        require_withdraw_authority(employee, &ctx.accounts.withdrawer.key())?;
+        // Split shares are employee-directed; withholding needs request_withdrawal
+        require!(employee.withholding_bps == 0, BagelError::WithholdingRequired);
         require!(
-            vault.total_balance == 0,
+            vault.use_confidential_tokens && vault.confidential_mint != Pubkey::default(),
             BagelError::InvalidState
         );
 
-        let authority = &mut ctx.accounts.authority;
-        let vault_account = &ctx.accounts.master_vault.to_account_info();
-        
-        **authority.to_account_info().try_borrow_mut_lamports()? += vault_account.lamports();
-        **vault_account.try_borrow_mut_lamports()? = 0;
+        let Some(time_elapsed) = withdraw_elapsed(employee, clock.unix_timestamp)? else {
+            return Ok(());
+        };
+        check_withdraw_interval(
+            employee,
+            &ctx.accounts.business_entry,
+            time_elapsed,
+            clock.unix_timestamp,
+        )?;
 
-        msg!("🗑️  Master Vault closed");
+        let allowlist = &ctx.accounts.split_destinations;
+        let allowed = &allowlist.destinations[..allowlist.count as usize];
+
+        let inco_token_program_id = Pubkey::try_from(crate::constants::INCO_TOKEN_PROGRAM_ID).unwrap();
+        let bump = vault.bump;
+        let seeds: &[&[&[u8]]] = &[&[MASTER_VAULT_SEED, &[bump]]];
+        let mut total_handle: Option<Euint128> = None;
+        let mut shares = Vec::with_capacity(splits.len());
+
+        for (split, destination) in splits.iter().zip(ctx.remaining_accounts.iter()) {
+            validate_ciphertext(&split.encrypted_amount)?;
+            require!(allowed.contains(&split.destination), BagelError::Unauthorized);
+            require!(
+                destination.key() == split.destination && destination.is_writable,
+                BagelError::InvalidState
+            );
+            require!(
+                *destination.owner == inco_token_program_id,
+                BagelError::InvalidState
+            );
+            let view = IncoTokenAccountView::parse(&destination.try_borrow_data()?)?;
+            require!(view.mint == vault.confidential_mint, BagelError::InvalidState);
+
+            let cpi_accounts = IncoTransfer {
+                source: ctx.accounts.master_vault_token_account.to_account_info(),
+                destination: destination.to_account_info(),
+                authority: vault.to_account_info(),
+                inco_lightning_program: ctx.accounts.inco_lightning_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.inco_token_program.to_account_info(),
+                cpi_accounts,
+                seeds,
+            );
+            require_transfer_authority(&cpi_ctx.accounts.source, cpi_ctx.accounts.authority.key)?;
+            transfer(cpi_ctx, split.encrypted_amount.clone(), 0)?;
+
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                Operation {
+                    signer: ctx.accounts.withdrawer.to_account_info(),
+                },
+            );
+            let encrypted_share = new_euint128(cpi_ctx, split.encrypted_amount.clone(), 0)?;
+            shares.push(encrypted_share.clone());
+
+            total_handle = Some(match total_handle {
+                None => encrypted_share,
+                Some(total) => {
+                    let cpi_ctx = CpiContext::new(
+                        ctx.accounts.inco_lightning_program.to_account_info(),
+                        Operation {
+                            signer: ctx.accounts.withdrawer.to_account_info(),
+                        },
+                    );
+                    e_add(cpi_ctx, total, encrypted_share, 0)?
+                }
+            });
+        }
+
+        let encrypted_withdrawal = total_handle.ok_or(BagelError::NoAccountsProvided)?;
+
+        // share * 10_000 >= total * bps, the same check as withholding
+        let mut shares_met = Vec::with_capacity(shares.len());
+        for (split, share) in splits.iter().zip(shares) {
+            shares_met.push(withholding_met(
+                encrypted_withdrawal.clone(),
+                share,
+                split.bps,
+                &ctx.accounts.inco_lightning_program.to_account_info(),
+                &ctx.accounts.withdrawer.to_account_info(),
+            )?);
+        }
+
+        record_payout(
+            employee,
+            &mut ctx.accounts.master_vault,
+            encrypted_withdrawal.clone(),
+            &ctx.accounts.inco_lightning_program.to_account_info(),
+            &ctx.accounts.withdrawer.to_account_info(),
+        )?;
+        employee.last_withdrawal_at = clock.unix_timestamp;
+
+        msg!("🔀 Split withdrawal processed (PRIVATE)");
+        msg!("   Employee Index: {}", employee.employee_index);
+        msg!("   Recipients: {}", splits.len());
+
+        set_withdrawal_result(&WithdrawalResult {
+            employee_index: employee.employee_index,
+            shadowwire_used: false,
+            paid_handle: encrypted_withdrawal,
+            new_accrued_handle: employee.encrypted_accrued.clone(),
+            timestamp: clock.unix_timestamp,
+        })?;
+
+        emit!(SplitWithdrawal {
+            business_index: ctx.accounts.business_entry.entry_index,
+            employee_index: employee.employee_index,
+            recipient_count: splits.len() as u8,
+            shares_met,
+            timestamp: clock.unix_timestamp,
+            encrypted_total_withdrawn: employee.encrypted_total_withdrawn.clone(),
+        });
 
         Ok(())
     }
 
-    /// Migrate MasterVault from old structure to new structure
-    /// 
-    /// Reads old format manually and writes new format with additional fields
-    /// This allows upgrading existing vaults without losing data
-    pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
-        let vault_info = &mut ctx.accounts.master_vault;
-        let authority = &ctx.accounts.authority;
-        
-        // Verify PDA seeds match (this validates the account is the correct vault)
-        let (expected_vault, _bump) = Pubkey::find_program_address(
-            &[MASTER_VAULT_SEED],
-            ctx.program_id,
-        );
+    /// Register the accounts `withdraw_split` may pay for an employee
+    ///
+    /// Only the employee's registered `withdraw_authority` may set it, so
+    /// split destinations are always employee-chosen. Replaces the whole
+    /// list: 1..=`MAX_SPLIT_RECIPIENTS` distinct Inco Token accounts, which
+    /// `withdraw_split` checks for mint and owner when paying them.
+    pub fn set_split_destinations(
+        ctx: Context<SetSplitDestinations>,
+        destinations: Vec<Pubkey>,
+    ) -> Result<()> {
+        let employee = &ctx.accounts.employee_entry;
+        require_withdraw_authority(employee, &ctx.accounts.withdrawer.key())?;
         require!(
-            vault_info.key() == expected_vault,
-            BagelError::InvalidState
+            !destinations.is_empty() && destinations.len() <= MAX_SPLIT_RECIPIENTS,
+            BagelError::InvalidAmount
         );
-        
-        // Read old data and extract all fields before any mutations
-        let old_data_len = vault_info.data_len();
-        let old_data = vault_info.try_borrow_data()?;
-        
-        if old_data.len() < 40 {
-            return Err(BagelError::InvalidState.into());
+        for (i, destination) in destinations.iter().enumerate() {
+            require!(*destination != Pubkey::default(), BagelError::InvalidState);
+            require!(!destinations[..i].contains(destination), BagelError::InvalidState);
         }
-        
-        let old_authority = Pubkey::try_from(&old_data[8..40])
-            .map_err(|_| BagelError::InvalidState)?;
-        
+
+        let allowlist = &mut ctx.accounts.split_destinations;
+        allowlist.employee_entry = employee.key();
+        allowlist.destinations = [Pubkey::default(); MAX_SPLIT_RECIPIENTS];
+        allowlist.destinations[..destinations.len()].copy_from_slice(&destinations);
+        allowlist.count = destinations.len() as u8;
+        allowlist.bump = ctx.bumps.split_destinations;
+
+        msg!("🔀 Split destinations updated");
+        msg!("   Employee Index: {}", employee.employee_index);
+        msg!("   Destinations: {}", destinations.len());
+
+        emit!(SplitDestinationsSet {
+            business_index: ctx.accounts.business_entry.entry_index,
+            employee_index: employee.employee_index,
+            count: allowlist.count,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Initiate a two-phase withdrawal (phase 1)
+    ///
+    /// Records the encrypted amount on a PendingWithdrawal PDA, locked
+    /// until `now + business_entry.withdrawal_delay`. No funds move until
+    /// `finalize_withdrawal`; the vault authority can cancel before unlock.
+    ///
+    /// Only the employee's registered `withdraw_authority` (see
+    /// `set_withdraw_authority`) may initiate; rejects with
+    /// `Unauthorized` while none is registered.
+    pub fn initiate_withdrawal(
+        ctx: Context<InitiateWithdrawal>,
+        encrypted_amount: Vec<u8>,
+    ) -> Result<()> {
+        require!(!ctx.accounts.business_entry.is_frozen, BagelError::BusinessIsFrozen);
+        validate_ciphertext(&encrypted_amount)?;
         require!(
-            authority.key() == old_authority,
-            BagelError::Unauthorized
-        );
-        
-        // Check if already migrated (new structure has 162 bytes)
-        if old_data.len() >= 162 {
-            msg!("✅ Vault already migrated");
-            return Ok(());
-        }
-        
-        // Extract all old data fields into owned values
-        let old_authority_bytes: [u8; 32] = old_data[8..40].try_into()
-            .map_err(|_| BagelError::InvalidState)?;
-        let old_total_balance = u64::from_le_bytes(
-            old_data[40..48].try_into().map_err(|_| BagelError::InvalidState)?
+            encrypted_amount.len() <= MAX_PENDING_CIPHERTEXT_LEN,
+            BagelError::InvalidCiphertext
         );
-        let old_encrypted_business_count: [u8; 16] = old_data[48..64].try_into()
-            .map_err(|_| BagelError::InvalidState)?;
-        let old_encrypted_employee_count: [u8; 16] = old_data[64..80].try_into()
-            .map_err(|_| BagelError::InvalidState)?;
-        let old_next_business_index = u64::from_le_bytes(
-            old_data[80..88].try_into().map_err(|_| BagelError::InvalidState)?
+
+        let vault = &ctx.accounts.master_vault;
+        let business = &ctx.accounts.business_entry;
+        let employee = &mut ctx.accounts.employee_entry;
+        let pending = &mut ctx.accounts.pending_withdrawal;
+        let clock = Clock::get()?;
+
+        require!(employee.is_active, BagelError::PayrollInactive);
+        require_withdraw_authority(employee, &ctx.accounts.withdrawer.key())?;
+        // Single-transfer path: withholding needs request_withdrawal's split
+        require!(employee.withholding_bps == 0, BagelError::WithholdingRequired);
+        require!(
+            vault.use_confidential_tokens && vault.confidential_mint != Pubkey::default(),
+            BagelError::InvalidState
         );
-        let old_is_active = old_data[88];
-        let old_bump = old_data[89];
-        
-        drop(old_data); // Release borrow before realloc
-        
-        // Resize account if needed
-        let new_size = MasterVault::LEN;
-        if old_data_len < new_size {
-            // Realloc with zero-initialization for new space
-            vault_info.realloc(new_size, false)?;
-        }
-        
-        // Write new format
-        let mut new_data = vault_info.try_borrow_mut_data()?;
-        
-        // Discriminator (8 bytes) - keep existing (already set)
-        // Authority (32 bytes) - copy from old
-        new_data[8..40].copy_from_slice(&old_authority_bytes);
-        // Total balance (8 bytes) - copy from old
-        new_data[40..48].copy_from_slice(&old_total_balance.to_le_bytes());
-        // Encrypted business count (16 bytes) - copy from old
-        new_data[48..64].copy_from_slice(&old_encrypted_business_count);
-        // Encrypted employee count (16 bytes) - copy from old
-        new_data[64..80].copy_from_slice(&old_encrypted_employee_count);
-        // Next business index (8 bytes) - copy from old
-        new_data[80..88].copy_from_slice(&old_next_business_index.to_le_bytes());
-        // Is active (1 byte) - copy from old
-        new_data[88] = old_is_active;
-        // Bump (1 byte) - copy from old
-        new_data[89] = old_bump;
-        // Confidential mint (32 bytes) - set to default (Pubkey::default())
-        new_data[90..122].fill(0);
-        // Use confidential tokens (1 byte) - set to false
-        new_data[122] = 0;
-        // Padding (31 bytes) - already zeroed by realloc
-        
-        msg!("✅ Vault migrated successfully");
-        msg!("   Old size: {} bytes", old_data_len);
-        msg!("   New size: {} bytes", new_size);
-        
+
+        let Some(time_elapsed) = withdraw_elapsed(employee, clock.unix_timestamp)? else {
+            // Nothing to lock: hand the just-created record's rent back
+            anchor_lang::common::close(
+                ctx.accounts.pending_withdrawal.to_account_info(),
+                ctx.accounts.withdrawer.to_account_info(),
+            )?;
+            return Ok(());
+        };
+        check_withdraw_interval(
+            employee,
+            &ctx.accounts.business_entry,
+            time_elapsed,
+            clock.unix_timestamp,
+        )?;
+
+        let unlock_at = clock.unix_timestamp
+            .checked_add(business.withdrawal_delay)
+            .ok_or(BagelError::InvalidTimestamp)?;
+
+        pending.employee_entry = employee.key();
+        pending.withdrawer = ctx.accounts.withdrawer.key();
+        pending.encrypted_amount = encrypted_amount;
+        pending.initiated_at = clock.unix_timestamp;
+        pending.unlock_at = unlock_at;
+        pending.bump = ctx.bumps.pending_withdrawal;
+        pending.status = WithdrawalStatus::Pending;
+
+        // Rate-limit from initiation, not finalization
+        employee.last_withdrawal_at = clock.unix_timestamp;
+
+        msg!("⏳ Withdrawal initiated (PRIVATE)");
+        msg!("   Employee Index: {}", employee.employee_index);
+        msg!("   Unlocks at: {}", unlock_at);
+
+        emit!(WithdrawalInitiated {
+            business_index: business.entry_index,
+            employee_index: employee.employee_index,
+            unlock_at,
+            timestamp: clock.unix_timestamp,
+        });
+
         Ok(())
     }
 
-    /// Delegate employee entry to MagicBlock TEE (optional)
-    /// 
-    /// **NEW:** Now includes Permission Program integration:
-    /// 1. Creates permission account if it doesn't exist
-    /// 2. Sets initial members (employer + employee) with appropriate flags
-    /// 3. Delegates both permission AND permissioned_account to TEE
-    /// 
-    /// **Parameters:**
-    /// - `employer`: The employer's public key (for permission account)
-    /// - `employee`: The employee's public key (for permission account)
-    pub fn delegate_to_tee(
-        ctx: Context<DelegateToTee>,
-        employer: Pubkey,
-        employee: Pubkey,
-    ) -> Result<()> {
-        msg!("⚡ Delegating to MagicBlock TEE with Permission Program...");
-        msg!("   Employer: {}", employer);
-        msg!("   Employee: {}", employee);
-        
-        let business_entry_key = ctx.accounts.business_entry.key();
-        let employee_index_bytes = ctx.accounts.employee_entry.employee_index.to_le_bytes();
-        let employee_entry_key = ctx.accounts.employee_entry.key();
-        
-        // Step 1: Create permission account if it doesn't exist
-        let permission_info = ctx.accounts.permission.to_account_info();
-        let permission_exists = permission_info.data_len() > 0;
-        
-        if !permission_exists {
-            msg!("🔐 Creating permission account...");
-            
-            // Create permission account via CPI
-            use crate::privacy::magicblock::create_employee_entry_permission;
-            
-            // Build CPI context for permission creation
-            // We need to create a new CPI context with the Permission Program
-            let create_permission_ctx = CpiContext::new(
-                ctx.accounts.permission_program.to_account_info(),
-                CreateEmployeePermission {
-                    payer: ctx.accounts.payer.clone(),
-                    employee_entry: ctx.accounts.employee_entry.to_account_info(),
-                    permission: ctx.accounts.permission.clone(),
-                    permission_program: ctx.accounts.permission_program.clone(),
-                    system_program: ctx.accounts.system_program.clone(),
-                },
-            );
-            
-            create_employee_entry_permission(
-                &create_permission_ctx,
-                employer,
-                employee,
-            )?;
-        } else {
-            msg!("✅ Permission account already exists");
-        }
-        
-        // Step 2: Delegate both permission and permissioned_account
-        let seeds: &[&[u8]] = &[
-            EMPLOYEE_ENTRY_SEED,
-            business_entry_key.as_ref(),
-            &employee_index_bytes,
-        ];
-        
-        let validator_key = ctx.accounts.validator
-            .as_ref()
-            .map(|v| v.key())
-            .or_else(|| Pubkey::try_from(TEE_VALIDATOR).ok());
-        
-        // Delegate EmployeeEntry (permissioned_account)
-        // The #[delegate] macro on the account context automatically handles delegation
-        // It will delegate the account marked with `del` constraint (employee_entry)
-        // We also need to delegate the permission account
-        ctx.accounts.delegate_employee_entry(
-            &ctx.accounts.payer,
+    /// Finalize a two-phase withdrawal (phase 2)
+    ///
+    /// Transfers the pending encrypted amount once `now >= unlock_at`,
+    /// to the current `withdraw_authority`'s Inco Token account.
+    /// With `auto_close` the PendingWithdrawal is closed in the same tx,
+    /// returning rent to the withdrawer; otherwise it is kept as a
+    /// `Finalized` record until `close_pending` (which must run before
+    /// the next `initiate_withdrawal` for this employee).
+    pub fn finalize_withdrawal(ctx: Context<FinalizeWithdrawal>, auto_close: bool) -> Result<()> {
+        require!(!ctx.accounts.business_entry.is_frozen, BagelError::BusinessIsFrozen);
+        let vault = &ctx.accounts.master_vault;
+        let employee = &mut ctx.accounts.employee_entry;
+        let pending = &mut ctx.accounts.pending_withdrawal;
+        let clock = Clock::get()?;
+
+        require!(employee.is_active, BagelError::PayrollInactive);
+        // The authority may have been rotated since initiation
+        require_withdraw_authority(employee, &ctx.accounts.withdrawer.key())?;
+        require_payout_destination(
+            &ctx.accounts.employee_token_account,
+            employee,
+            &vault.confidential_mint,
+        )?;
+        require!(pending.status == WithdrawalStatus::Pending, BagelError::InvalidState);
+        require!(clock.unix_timestamp >= pending.unlock_at, BagelError::WithdrawalLocked);
+
+        pending.status = WithdrawalStatus::Finalized;
+
+        let encrypted_amount = pending.encrypted_amount.clone();
+
+        // Confidential transfer vault -> employee
+        let bump = vault.bump;
+        let seeds: &[&[&[u8]]] = &[&[MASTER_VAULT_SEED, &[bump]]];
+        let cpi_accounts = IncoTransfer {
+            source: ctx.accounts.master_vault_token_account.to_account_info(),
+            destination: ctx.accounts.employee_token_account.to_account_info(),
+            authority: vault.to_account_info(),
+            inco_lightning_program: ctx.accounts.inco_lightning_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.inco_token_program.to_account_info(),
+            cpi_accounts,
             seeds,
-            DelegateConfig {
-                validator: validator_key,
-                ..Default::default()
+        );
+        require_transfer_authority(&cpi_ctx.accounts.source, cpi_ctx.accounts.authority.key)?;
+        transfer(cpi_ctx, encrypted_amount.clone(), 0)?;
+
+        // Update encrypted accrued and lifetime payouts
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.withdrawer.to_account_info(),
             },
+        );
+        let encrypted_withdrawal = new_euint128(cpi_ctx, encrypted_amount, 0)?;
+
+        record_payout(
+            employee,
+            &mut ctx.accounts.master_vault,
+            encrypted_withdrawal.clone(),
+            &ctx.accounts.inco_lightning_program.to_account_info(),
+            &ctx.accounts.withdrawer.to_account_info(),
         )?;
-        
-        // Delegate Permission account
-        // The permission account also has `del` constraint, so it should be delegated
-        // by the #[delegate] macro. However, we need to ensure both are delegated.
-        // The SDK's delegate macro should handle multiple accounts with `del` constraint.
-        // For now, we rely on the macro to handle both accounts.
 
-        let validator = validator_key.unwrap_or_default();
-        msg!("✅ Delegated to TEE");
-        msg!("   Employee Index: {}", ctx.accounts.employee_entry.employee_index);
-        msg!("   Permission PDA: {}", ctx.accounts.permission.key());
-        msg!("   Validator: {}", validator);
-        msg!("   ✅ Both permission AND permissioned_account delegated");
+        msg!("✅ Withdrawal finalized (PRIVATE)");
+        msg!("   Employee Index: {}", employee.employee_index);
 
-        emit!(DelegatedToTee {
+        set_withdrawal_result(&WithdrawalResult {
+            employee_index: employee.employee_index,
+            shadowwire_used: false,
+            paid_handle: encrypted_withdrawal,
+            new_accrued_handle: employee.encrypted_accrued.clone(),
+            timestamp: clock.unix_timestamp,
+        })?;
+
+        emit!(WithdrawalFinalized {
             business_index: ctx.accounts.business_entry.entry_index,
-            employee_index: ctx.accounts.employee_entry.employee_index,
-            validator,
-            timestamp: Clock::get()?.unix_timestamp,
+            employee_index: employee.employee_index,
+            timestamp: clock.unix_timestamp,
+            encrypted_total_withdrawn: employee.encrypted_total_withdrawn.clone(),
         });
 
+        if auto_close {
+            anchor_lang::common::close(
+                ctx.accounts.pending_withdrawal.to_account_info(),
+                ctx.accounts.withdrawer.to_account_info(),
+            )?;
+        }
+
         Ok(())
     }
 
-    /// Commit TEE state back to L1
-    /// 
-    /// **NEW:** Now includes Permission Program - commits both permission
-    /// and permissioned_account back to L1
-    pub fn commit_from_tee(ctx: Context<CommitFromTee>) -> Result<()> {
-        msg!("⚡ Committing from TEE to L1 (with Permission Program)...");
-
-        let payer_info = ctx.accounts.payer.to_account_info();
-        let employee_info = ctx.accounts.employee_entry.to_account_info();
-        let permission_info = ctx.accounts.permission.to_account_info();
-        let magic_context_info = ctx.accounts.magic_context.to_account_info();
-        let magic_program_info = ctx.accounts.magic_program.to_account_info();
+    /// Cancel a pending withdrawal before it unlocks
+    ///
+    /// Vault authority only (acting for the employer). Closes the
+    /// PendingWithdrawal and returns rent to the original withdrawer.
+    pub fn cancel_withdrawal(ctx: Context<CancelWithdrawal>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
 
-        // Commit both permission and permissioned_account
-        // The SDK's commit_and_undelegate_accounts should handle both
-        commit_and_undelegate_accounts(
-            &payer_info,
-            vec![&employee_info, &permission_info],
-            &magic_context_info,
-            &magic_program_info,
-        )?;
+        let clock = Clock::get()?;
+        require!(
+            ctx.accounts.pending_withdrawal.status == WithdrawalStatus::Pending,
+            BagelError::InvalidState
+        );
+        require!(
+            clock.unix_timestamp < ctx.accounts.pending_withdrawal.unlock_at,
+            BagelError::WithdrawalUnlocked
+        );
 
-        msg!("✅ Committed to L1");
-        msg!("   ✅ Both permission AND permissioned_account undelegated");
+        msg!("🚫 Pending withdrawal cancelled");
+        msg!("   Employee Index: {}", ctx.accounts.employee_entry.employee_index);
 
-        emit!(CommittedFromTee {
+        emit!(WithdrawalCancelled {
             business_index: ctx.accounts.business_entry.entry_index,
             employee_index: ctx.accounts.employee_entry.employee_index,
-            timestamp: Clock::get()?.unix_timestamp,
+            timestamp: clock.unix_timestamp,
         });
 
         Ok(())
     }
 
-    // ============================================================
-    // PDA-based Token Account Instructions
-    // ============================================================
-
-    /// Initialize a user's token account as a PDA
+    /// Close a finalized PendingWithdrawal and reclaim its rent
     ///
-    /// Derives a deterministic token account address from:
-    /// Seeds: ["user_token", wallet_pubkey, mint]
-    ///
-    /// This allows anyone to calculate a user's token account address
-    /// without needing to store it. The Bagel program owns the PDA
-    /// and can sign for token operations.
-    pub fn initialize_user_token_account(
-        ctx: Context<InitializeUserTokenAccount>,
-    ) -> Result<()> {
-        let clock = Clock::get()?;
-        let owner_key = ctx.accounts.owner.key();
-        let mint_key = ctx.accounts.mint.key();
-        let pda_key = ctx.accounts.user_token_account.key();
-        let bump = ctx.bumps.user_token_account;
-
-        let user_token = &mut ctx.accounts.user_token_account;
-        user_token.owner = owner_key;
-        user_token.mint = mint_key;
-        user_token.inco_token_account = Pubkey::default(); // Set by mint API later
-        user_token.balance = Euint128::default();
-        user_token.initialized_at = clock.unix_timestamp;
-        user_token.bump = bump;
-
-        msg!("🔐 User token account initialized (PDA-based)");
-        msg!("   Owner: {}", owner_key);
-        msg!("   Mint: {}", mint_key);
-        msg!("   PDA: {}", pda_key);
+    /// Permissionless: rent always goes back to the original withdrawer.
+    /// Rejects while the withdrawal is still pending.
+    pub fn close_pending(ctx: Context<ClosePending>) -> Result<()> {
+        require!(
+            ctx.accounts.pending_withdrawal.status != WithdrawalStatus::Pending,
+            BagelError::WithdrawalPending
+        );
 
-        emit!(UserTokenAccountInitialized {
-            owner: owner_key,
-            mint: mint_key,
-            token_account_pda: pda_key,
-            timestamp: clock.unix_timestamp,
-        });
+        msg!("🧹 Pending withdrawal record closed");
+        msg!("   Employee Index: {}", ctx.accounts.employee_entry.employee_index);
 
         Ok(())
     }
 
-    /// Get the PDA address for a user's token account (view function helper)
-    /// This is a no-op instruction that just logs the derived address
-    /// Clients should derive this off-chain using:
-    /// findProgramAddress(["user_token", wallet, mint], BAGEL_PROGRAM_ID)
-    pub fn get_user_token_pda(
-        ctx: Context<GetUserTokenPda>,
-    ) -> Result<()> {
-        msg!("📍 User Token PDA: {}", ctx.accounts.user_token_account.key());
-        msg!("   Owner: {}", ctx.accounts.owner.key());
-        msg!("   Mint: {}", ctx.accounts.mint.key());
+    /// Return when an employee may next withdraw
+    ///
+    /// Read-only. Writes a borsh-serialized `WithdrawEligibility` via
+    /// return data, using the business's withdraw interval, so clients do
+    /// not re-derive `last_withdrawal_at + MIN_WITHDRAW_INTERVAL` themselves.
+    /// Covers the rate limit only; two-phase withdrawals add the
+    /// business `withdrawal_delay` on top at initiation.
+    pub fn withdraw_eligibility(ctx: Context<WithdrawEligibility>) -> Result<()> {
+        let last_withdrawal_at = ctx.accounts.employee_entry.last_withdrawal_at;
+        let eligibility = WithdrawEligibilityInfo {
+            last_withdrawal_at,
+            next_eligible_at: accrual::next_withdraw_at(
+                last_withdrawal_at,
+                ctx.accounts.business_entry.withdraw_interval(),
+            )?,
+            now: Clock::get()?.unix_timestamp,
+        };
+
+        let mut data = Vec::new();
+        eligibility.serialize(&mut data).map_err(|_| BagelError::InvalidState)?;
+        anchor_lang::solana_program::program::set_return_data(&data);
+
         Ok(())
     }
 
-    /// Set the Inco Token account reference for a user
-    /// Called by the mint API after creating the Inco Token account
-    /// Only the owner or a trusted authority can call this
-    pub fn set_inco_token_account(
-        ctx: Context<SetIncoTokenAccount>,
-        inco_token_account: Pubkey,
-    ) -> Result<()> {
-        let user_token = &mut ctx.accounts.user_token_account;
+    /// Return the employee's last L1 accrued snapshot
+    ///
+    /// Read-only. Writes a borsh-serialized `AccruedSnapshotInfo` via
+    /// return data: the accrued handle and time recorded by the last
+    /// `commit_from_tee`/`commit_from_tee_batch`. Employees compare the
+    /// balance the TEE reports against this anchor. A time of 0 means the
+    /// entry has not been committed since snapshots were introduced.
+    pub fn accrued_snapshot(ctx: Context<AccruedSnapshot>) -> Result<()> {
+        let employee = &ctx.accounts.employee_entry;
+        let snapshot = AccruedSnapshotInfo {
+            accrued_handle: employee.last_snapshot_accrued,
+            snapshot_time: employee.last_snapshot_time,
+            now: Clock::get()?.unix_timestamp,
+        };
 
-        // Only allow setting if not already set (one-time operation)
-        // or if the caller is the owner
-        require!(
-            user_token.inco_token_account == Pubkey::default() ||
-            ctx.accounts.authority.key() == user_token.owner,
-            BagelError::Unauthorized
-        );
+        let mut data = Vec::new();
+        snapshot.serialize(&mut data).map_err(|_| BagelError::InvalidState)?;
+        anchor_lang::solana_program::program::set_return_data(&data);
 
-        user_token.inco_token_account = inco_token_account;
+        Ok(())
+    }
 
-        msg!("🔗 Inco Token Account linked");
-        msg!("   Owner: {}", user_token.owner);
-        msg!("   Inco Token Account: {}", inco_token_account);
+    /// Return the external program-id config of this deployment
+    ///
+    /// Read-only. Writes a borsh-serialized `ProgramConfig` via return
+    /// data so clients read ids from the deployed program instead of
+    /// hard-coding devnet/mainnet values. Also carries the handle of the
+    /// vault's encrypted running total for authorized decryption.
+    pub fn config(ctx: Context<Config>) -> Result<()> {
+        let config = ProgramConfig {
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            inco_token_program: Pubkey::try_from(crate::constants::INCO_TOKEN_PROGRAM_ID)
+                .map_err(|_| BagelError::InvalidState)?,
+            tee_validator: Pubkey::try_from(TEE_VALIDATOR)
+                .map_err(|_| BagelError::InvalidState)?,
+            confidential_mint: ctx.accounts.master_vault.confidential_mint,
+            use_confidential_tokens: ctx.accounts.master_vault.use_confidential_tokens,
+            provider_flags: ctx.accounts.master_vault.provider_flags.bits,
+            encrypted_total_balance: if ctx.accounts.master_vault.tracks_encrypted_total {
+                ctx.accounts.master_vault.encrypted_total_balance.clone()
+            } else {
+                Euint128::default()
+            },
+        };
 
-        emit!(IncoTokenAccountLinked {
-            owner: user_token.owner,
-            mint: user_token.mint,
-            inco_token_account,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+        let mut data = Vec::new();
+        config.serialize(&mut data).map_err(|_| BagelError::InvalidState)?;
+        anchor_lang::solana_program::program::set_return_data(&data);
 
         Ok(())
     }
 
-    // ============================================================
-    // Permission Program (MagicBlock Access Control)
-    // ============================================================
+    /// Smoke-test the external programs this deployment depends on
+    ///
+    /// Checks that Inco Lightning and the MagicBlock delegation program
+    /// are deployed (executable) at the expected ids, then runs a trivial
+    /// `as_euint128` CPI into Inco. Fails with `IncoUnavailable` or
+    /// `MagicBlockUnavailable` so setup problems show up before
+    /// `register_business`. Touches no Bagel state.
+    pub fn healthcheck(ctx: Context<Healthcheck>) -> Result<()> {
+        let inco = &ctx.accounts.inco_lightning_program;
+        require!(
+            inco.key() == INCO_LIGHTNING_ID && inco.executable,
+            BagelError::IncoUnavailable
+        );
+
+        let delegation = &ctx.accounts.delegation_program;
+        let delegation_id = Pubkey::try_from(crate::constants::MAGICBLOCK_DELEGATION_PROGRAM)
+            .map_err(|_| BagelError::InvalidState)?;
+        require!(
+            delegation.key() == delegation_id && delegation.executable,
+            BagelError::MagicBlockUnavailable
+        );
 
-    /// Create permission account for EmployeeEntry
-    /// 
-    /// This instruction creates a permission account with initial members
-    /// (employer + employee) and appropriate flags for access control.
-    /// 
-    /// Can be called separately before delegation, or will be called
-    /// automatically during delegation if permission doesn't exist.
-    pub fn create_employee_permission(
-        ctx: Context<CreateEmployeePermission>,
-        employer: Pubkey,
-        employee: Pubkey,
-    ) -> Result<()> {
-        use crate::privacy::magicblock::create_employee_entry_permission;
-        
         let cpi_ctx = CpiContext::new(
-            ctx.accounts.permission_program.to_account_info(),
-            CreateEmployeePermission {
-                payer: ctx.accounts.payer.clone(),
-                employee_entry: ctx.accounts.employee_entry.to_account_info(),
-                permission: ctx.accounts.permission.clone(),
-                permission_program: ctx.accounts.permission_program.clone(),
-                system_program: ctx.accounts.system_program.clone(),
+            inco.to_account_info(),
+            Operation {
+                signer: ctx.accounts.payer.to_account_info(),
             },
         );
-        
-        create_employee_entry_permission(
-            &cpi_ctx,
-            employer,
-            employee,
-        )?;
+        as_euint128(cpi_ctx, 0)?;
+
+        msg!("✅ Healthcheck passed");
+        msg!("   Inco Lightning: {}", inco.key());
+        msg!("   MagicBlock Delegation: {}", delegation.key());
 
         Ok(())
     }
 
-    /// Update permission account members/flags
-    /// 
-    /// This allows updating permission members and flags in real-time
-    /// while the account is delegated to PER.
-    /// 
-    /// Note: Setting members to None makes the permission public (temporarily visible)
-    /// This is useful for transitional states during delegation/undelegation
-    pub fn update_employee_permission(
-        ctx: Context<UpdateEmployeePermission>,
-        member_pubkeys: Option<Vec<Pubkey>>,
-        member_flags: Option<Vec<u8>>,
+    /// Configure confidential token mint for private transfers
+    ///
+    /// Sets the confidential mint address, records the vault's Inco Token
+    /// account for it and enables confidential token mode. Once enabled,
+    /// deposits and withdrawals will use encrypted token transfers.
+    ///
+    /// Runs once: rejects with `ConfidentialMintAlreadySet` after a mint
+    /// and token account are recorded. Switch mints with
+    /// `rotate_confidential_mint` (which checks the old balance is gone)
+    /// and toggle transfers with `set_confidential_tokens`. A vault whose
+    /// mint was set before `token_account` existed may run it once more
+    /// with that same mint to record its token account.
+    pub fn configure_confidential_mint(
+        ctx: Context<ConfigureConfidentialMint>,
+        mint: Pubkey,
+        enable: bool,
     ) -> Result<()> {
-        use crate::privacy::magicblock::update_employee_entry_permission;
-        use ephemeral_rollups_sdk::access_control::structs::{Member, MembersArgs};
-        
-        // Convert instruction parameters to SDK Member types
-        let members = if let (Some(pubkeys), Some(flags)) = (member_pubkeys, member_flags) {
-            if pubkeys.len() != flags.len() {
-                return Err(BagelError::InvalidState.into());
-            }
-            Some(
-                pubkeys
-                    .into_iter()
-                    .zip(flags.into_iter())
-                    .map(|(pubkey, flag)| Member {
-                        flags: flag,
-                        pubkey,
-                    })
-                    .collect(),
-            )
-        } else {
-            None // Makes permission public
-        };
+        let vault = &mut ctx.accounts.master_vault;
         
-        let cpi_ctx = CpiContext::new(
-            ctx.accounts.permission_program.to_account_info(),
-            UpdateEmployeePermission {
-                payer: ctx.accounts.payer.clone(),
-                employee_entry: ctx.accounts.employee_entry.to_account_info(),
-                permission: ctx.accounts.permission.clone(),
-                permission_program: ctx.accounts.permission_program.clone(),
-                system_program: ctx.accounts.system_program.clone(),
-            },
+        require!(
+            ctx.accounts.authority.key() == vault.authority,
+            BagelError::Unauthorized
         );
-        
-        update_employee_entry_permission(
-            &cpi_ctx,
-            members,
-        )?;
+        require!(
+            vault.token_account == Pubkey::default()
+                && (vault.confidential_mint == Pubkey::default() || vault.confidential_mint == mint),
+            BagelError::ConfidentialMintAlreadySet
+        );
+        require!(mint != Pubkey::default(), BagelError::InvalidState);
+        require_vault_token_account(&ctx.accounts.vault_token_account, &vault.key(), &mint)?;
+
+        vault.confidential_mint = mint;
+        vault.use_confidential_tokens = enable;
+        vault.token_account = ctx.accounts.vault_token_account.key();
+
+        msg!("🔒 Confidential token mint configured");
+        msg!("   Mint: {}", mint);
+        msg!("   Vault token account: {}", vault.token_account);
+        msg!("   Enabled: {}", enable);
+
+        emit!(ConfidentialMintConfigured {
+            mint,
+            enabled: enable,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
         Ok(())
     }
-}
 
-// ============================================================
-// Account Contexts
-// ============================================================
+    /// Turn confidential token transfers on or off for the configured mint
+    ///
+    /// Leaves the mint and vault token account untouched. Enabling
+    /// requires both to be recorded by `configure_confidential_mint`.
+    pub fn set_confidential_tokens(ctx: Context<SetConfidentialTokens>, enable: bool) -> Result<()> {
+        let vault = &mut ctx.accounts.master_vault;
 
-#[derive(Accounts)]
-pub struct InitializeVault<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+        require!(
+            ctx.accounts.authority.key() == vault.authority,
+            BagelError::Unauthorized
+        );
+        require!(
+            !enable || (vault.confidential_mint != Pubkey::default() && vault.token_account != Pubkey::default()),
+            BagelError::InvalidState
+        );
 
-    #[account(
-        init,
-        payer = authority,
-        space = MasterVault::LEN,
-        seeds = [MASTER_VAULT_SEED],
-        bump
-    )]
-    pub master_vault: Account<'info, MasterVault>,
+        vault.use_confidential_tokens = enable;
 
-    /// CHECK: Inco Lightning program
-    #[account(address = INCO_LIGHTNING_ID)]
-    pub inco_lightning_program: AccountInfo<'info>,
+        msg!("🔒 Confidential tokens {}", if enable { "enabled" } else { "disabled" });
 
-    pub system_program: Program<'info, System>,
-}
+        emit!(ConfidentialMintConfigured {
+            mint: vault.confidential_mint,
+            enabled: enable,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-#[derive(Accounts)]
-pub struct RegisterBusiness<'info> {
-    #[account(mut)]
-    pub employer: Signer<'info>,
+        Ok(())
+    }
 
-    #[account(
-        mut,
-        seeds = [MASTER_VAULT_SEED],
-        bump = master_vault.bump,
-    )]
-    pub master_vault: Account<'info, MasterVault>,
+    /// Declare which privacy providers this deployment runs for real
+    ///
+    /// `bits` is a `ProviderFlags` bitfield (bit0 = Inco real, bit1 =
+    /// ShadowWire real, bit2 = TEE active). Inco real must stay set, since
+    /// every encrypted path calls Inco. Delegation requires TEE active, and
+    /// a ShadowWire withdrawal is refused while ShadowWire is flagged real
+    /// until this build ships a real verifier, so a mock never runs where
+    /// the deployment claims the real thing. Migrated vaults start with
+    /// `ProviderFlags::DEFAULT_BITS`, like fresh ones.
+    pub fn set_provider_flags(ctx: Context<SetProviderFlags>, bits: u8) -> Result<()> {
+        let vault = &mut ctx.accounts.master_vault;
 
-    #[account(
-        init,
-        payer = employer,
-        space = BusinessEntry::LEN,
-        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &master_vault.next_business_index.to_le_bytes()],
-        bump
-    )]
-    pub business_entry: Account<'info, BusinessEntry>,
+        require!(
+            ctx.accounts.authority.key() == vault.authority,
+            BagelError::Unauthorized
+        );
 
-    /// CHECK: Inco Lightning program
-    #[account(address = INCO_LIGHTNING_ID)]
-    pub inco_lightning_program: AccountInfo<'info>,
+        let old_bits = vault.provider_flags.bits;
+        vault.provider_flags = ProviderFlags::new(bits)?;
 
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-#[instruction(encrypted_amount: Vec<u8>)]
-pub struct Deposit<'info> {
-    #[account(mut)]
-    pub depositor: Signer<'info>,
-
-    #[account(
-        mut,
-        seeds = [MASTER_VAULT_SEED],
-        bump = master_vault.bump,
-    )]
-    pub master_vault: Account<'info, MasterVault>,
+        msg!("⚙️ Provider flags updated");
+        msg!("   Flags: {:#06b} -> {:#06b}", old_bits, bits);
 
-    #[account(
-        mut,
-        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
-        bump = business_entry.bump,
-    )]
-    pub business_entry: Account<'info, BusinessEntry>,
+        emit!(ProviderFlagsUpdated {
+            old_bits,
+            new_bits: bits,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-    /// CHECK: Inco Lightning program
-    #[account(address = INCO_LIGHTNING_ID)]
-    pub inco_lightning_program: AccountInfo<'info>,
+        Ok(())
+    }
 
-    /// CHECK: Inco Confidential Token program (optional, for confidential transfers)
-    /// When vault.use_confidential_tokens is true, this is used for encrypted transfers
-    pub inco_token_program: Option<AccountInfo<'info>>,
+    /// Set how long an employee entry must stay delegated to the TEE
+    ///
+    /// `commit_from_tee`, `commit_from_tee_batch` and
+    /// `settle_on_undelegate` fail with `DelegationTooShort` until
+    /// `min_delegation_duration` seconds have passed since
+    /// `delegate_to_tee`, which cuts delegate/undelegate churn.
+    /// `force_recover_delegation` is exempt. 0 disables the minimum.
+    pub fn set_min_delegation_duration(
+        ctx: Context<SetMinDelegationDuration>,
+        min_delegation_duration: i64,
+    ) -> Result<()> {
+        let vault = &mut ctx.accounts.master_vault;
 
-    /// CHECK: Depositor confidential token account (optional)
-    /// Used when confidential tokens are enabled
-    pub depositor_token_account: Option<AccountInfo<'info>>,
+        require!(
+            ctx.accounts.authority.key() == vault.authority,
+            BagelError::Unauthorized
+        );
+        require!(min_delegation_duration >= 0, BagelError::InvalidTimestamp);
 
-    /// CHECK: Master vault confidential token account (optional)
-    /// Used when confidential tokens are enabled
-    pub master_vault_token_account: Option<AccountInfo<'info>>,
+        vault.min_delegation_duration = min_delegation_duration;
 
-    pub system_program: Program<'info, System>,
-}
+        msg!("⚙️ Minimum delegation duration updated");
+        msg!("   Duration: {} seconds", min_delegation_duration);
 
-#[derive(Accounts)]
-pub struct CloseVault<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+        emit!(MinDelegationDurationUpdated {
+            min_delegation_duration,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-    #[account(
-        mut,
-        seeds = [MASTER_VAULT_SEED],
-        bump = master_vault.bump,
-        close = authority, // Close account and send lamports to authority
-    )]
-    pub master_vault: Account<'info, MasterVault>,
-}
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct MigrateVault<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// Record the decimals of the vault's confidential token
+    ///
+    /// `add_employee` clamps new salaries to `MAX_SALARY_TOKENS_PER_SECOND`
+    /// whole tokens (`MAX_SALARY_USD_PER_SECOND` for USD-denominated
+    /// businesses), so the same ceiling holds for a 6-decimal USDC and a
+    /// 9-decimal SOL mint. The encrypted `within_ceiling` flag on
+    /// `SalaryChanged` tells the employer whether a salary was clamped.
+    /// Vaults that never call this (including migrated ones) enforce no
+    /// ceiling.
+    pub fn set_token_decimals(ctx: Context<SetTokenDecimals>, decimals: u8) -> Result<()> {
+        let vault = &mut ctx.accounts.master_vault;
 
-    /// CHECK: Master vault PDA - we'll verify the bump from old data
-    /// CHECK: Master vault PDA - verified manually in instruction
-    #[account(mut)]
-    pub master_vault: AccountInfo<'info>, // Use AccountInfo to avoid deserialization
+        require!(
+            ctx.accounts.authority.key() == vault.authority,
+            BagelError::Unauthorized
+        );
+        require!(decimals <= MAX_TOKEN_DECIMALS, BagelError::Overflow);
 
-    pub system_program: Program<'info, System>,
-}
+        vault.token_decimals = Some(decimals);
 
-#[derive(Accounts)]
-pub struct AddEmployee<'info> {
-    #[account(mut)]
-    pub employer: Signer<'info>,
+        msg!("⚙️ Token decimals updated");
+        msg!("   Decimals: {}", decimals);
 
-    #[account(
-        mut,
-        seeds = [MASTER_VAULT_SEED],
-        bump = master_vault.bump,
-    )]
-    pub master_vault: Account<'info, MasterVault>,
+        emit!(TokenDecimalsUpdated {
+            decimals,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-    #[account(
-        mut,
-        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
-        bump = business_entry.bump,
-    )]
-    pub business_entry: Account<'info, BusinessEntry>,
+        Ok(())
+    }
 
-    #[account(
-        init,
-        payer = employer,
-        space = EmployeeEntry::LEN,
-        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &business_entry.next_employee_index.to_le_bytes()],
-        bump
-    )]
-    pub employee_entry: Account<'info, EmployeeEntry>,
+    /// Rotate the confidential token mint
+    ///
+    /// Refuses to switch while the current vault token account may still
+    /// hold funds. The encrypted balance can't be read on-chain, so the
+    /// old account - which must be the recorded `token_account` - has to
+    /// be a never-funded Inco Token account. The new token account must be
+    /// an Inco Token account for `new_mint` owned by the MasterVault PDA,
+    /// and becomes the recorded `token_account`.
+    pub fn rotate_confidential_mint(
+        ctx: Context<RotateConfidentialMint>,
+        new_mint: Pubkey,
+        new_vault_token_account: Pubkey,
+    ) -> Result<()> {
+        let vault = &mut ctx.accounts.master_vault;
 
-    /// CHECK: Inco Lightning program
-    #[account(address = INCO_LIGHTNING_ID)]
-    pub inco_lightning_program: AccountInfo<'info>,
+        require!(
+            ctx.accounts.authority.key() == vault.authority,
+            BagelError::Unauthorized
+        );
+        require!(
+            new_mint != Pubkey::default() && new_mint != vault.confidential_mint,
+            BagelError::InvalidState
+        );
 
-    pub system_program: Program<'info, System>,
-}
+        // Old balance must be gone before we forget about the old mint
+        let old_mint = vault.confidential_mint;
+        if old_mint != Pubkey::default() {
+            let old_account = ctx.accounts.old_vault_token_account
+                .as_ref()
+                .ok_or(BagelError::InvalidState)?;
+
+            // A closed or substituted account proves nothing about the balance
+            require!(
+                old_account.key() == vault.token_account && !old_account.data_is_empty(),
+                BagelError::InvalidState
+            );
+            require_vault_token_account(old_account, &vault.key(), &old_mint)?;
+            let old_view = IncoTokenAccountView::parse(&old_account.try_borrow_data()?)?;
+            require!(old_view.is_unfunded(), BagelError::ConfidentialBalanceNotEmpty);
+        }
 
-#[derive(Accounts)]
-#[instruction(encrypted_amount: Vec<u8>, use_shadowwire: bool)]
-pub struct RequestWithdrawal<'info> {
-    #[account(mut)]
-    pub withdrawer: Signer<'info>,
+        // New token account must be an Inco Token account for new_mint owned by the vault
+        let new_account = &ctx.accounts.new_vault_token_account;
+        require!(
+            new_account.key() == new_vault_token_account,
+            BagelError::InvalidState
+        );
+        require_vault_token_account(new_account, &vault.key(), &new_mint)?;
 
-    #[account(
-        mut,
-        seeds = [MASTER_VAULT_SEED],
-        bump = master_vault.bump,
-    )]
-    pub master_vault: Account<'info, MasterVault>,
+        vault.confidential_mint = new_mint;
+        vault.use_confidential_tokens = true;
+        vault.token_account = new_vault_token_account;
 
-    #[account(
-        mut,
-        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
-        bump = business_entry.bump,
-    )]
-    pub business_entry: Account<'info, BusinessEntry>,
+        msg!("🔄 Confidential token mint rotated");
+        msg!("   Old mint: {}", old_mint);
+        msg!("   New mint: {}", new_mint);
+        msg!("   Vault token account: {}", new_vault_token_account);
 
-    #[account(
-        mut,
-        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
-        bump = employee_entry.bump,
-    )]
-    pub employee_entry: Account<'info, EmployeeEntry>,
+        emit!(ConfidentialMintRotated {
+            old_mint,
+            new_mint,
+            vault_token_account: new_vault_token_account,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-    /// CHECK: Inco Lightning program
-    #[account(address = INCO_LIGHTNING_ID)]
-    pub inco_lightning_program: AccountInfo<'info>,
+        Ok(())
+    }
 
-    /// CHECK: Inco Confidential Token program (optional, for confidential transfers)
-    /// When vault.use_confidential_tokens is true, this is used for encrypted transfers
-    pub inco_token_program: Option<AccountInfo<'info>>,
+    /// Close Master Vault account (for migration/testing)
+    /// Transfers remaining lamports to `refund_to` (a treasury), or to
+    /// the authority when `Pubkey::default()` is passed
+    pub fn close_vault(ctx: Context<CloseVault>, refund_to: Pubkey) -> Result<()> {
+        let vault = &ctx.accounts.master_vault;
+        
+        require!(
+            ctx.accounts.authority.key() == vault.authority,
+            BagelError::Unauthorized
+        );
 
-    /// CHECK: Master vault confidential token account (optional)
-    /// Used when confidential tokens are enabled
-    pub master_vault_token_account: Option<AccountInfo<'info>>,
+        require!(
+            vault.total_balance == 0,
+            BagelError::InvalidState
+        );
 
-    /// CHECK: Employee confidential token account (optional)
-    /// Used when confidential tokens are enabled
-    pub employee_token_account: Option<AccountInfo<'info>>,
+        let refund_to = if refund_to == Pubkey::default() {
+            ctx.accounts.authority.key()
+        } else {
+            refund_to
+        };
+        require!(
+            ctx.accounts.refund_account.key() == refund_to,
+            BagelError::Unauthorized
+        );
 
-    pub system_program: Program<'info, System>,
-}
+        // Lamports move to refund_account via the `close` constraint
+        let amount = vault.to_account_info().lamports();
 
-#[derive(Accounts)]
-pub struct ConfigureConfidentialMint<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+        msg!("🗑️  Master Vault closed");
+        msg!("   Refund to: {}", refund_to);
 
-    #[account(
-        mut,
-        seeds = [MASTER_VAULT_SEED],
-        bump = master_vault.bump,
-    )]
-    pub master_vault: Account<'info, MasterVault>,
-}
+        emit!(VaultClosed {
+            refund_to,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-#[delegate]
-#[derive(Accounts)]
-pub struct DelegateToTee<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
+        Ok(())
+    }
 
-    #[account(
-        seeds = [MASTER_VAULT_SEED],
-        bump = master_vault.bump,
-    )]
-    pub master_vault: Account<'info, MasterVault>,
+    /// Close an EmployeeEntry (vault authority only)
+    ///
+    /// The employee must be suspended first (`EmployeeActive` otherwise):
+    /// `suspend_employee` settles accrual, so the entry's accrued is
+    /// final. Its prefunded bucket goes back to the business balance, and
+    /// its unpaid accrued is added to `encrypted_closed_unpaid`, which
+    /// `drain_business` holds back, so closing never frees owed earnings
+    /// for the employer to drain. The entry's PendingWithdrawal PDA must
+    /// not exist (`WithdrawalPending` otherwise): cancel or finalize it
+    /// and `close_pending` first, since neither works once the entry is
+    /// gone. The business and vault employee counts are decremented.
+    ///
+    /// Rejects with `StillDelegated` while the entry is owned by the
+    /// delegation program, judged by `account.owner` rather than any
+    /// stored flag. Run `commit_from_tee` (or `settle_on_undelegate`) first
+    /// and close once the undelegation has landed on L1 - it completes
+    /// asynchronously, so it cannot be combined into this instruction.
+    pub fn close_employee(ctx: Context<CloseEmployee>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
 
-    #[account(
-        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
-        bump = business_entry.bump,
-    )]
-    pub business_entry: Account<'info, BusinessEntry>,
+        let employee_info = ctx.accounts.employee_entry.to_account_info();
+        require!(
+            employee_info.owner == &crate::ID,
+            BagelError::StillDelegated
+        );
 
-    #[account(
-        mut,
-        del,
-        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
-        bump = employee_entry.bump,
-    )]
-    pub employee_entry: Account<'info, EmployeeEntry>,
+        let employee = EmployeeEntry::try_deserialize(&mut &employee_info.try_borrow_data()?[..])?;
+        let business_key = ctx.accounts.business_entry.key();
+        let (expected, _) = Pubkey::find_program_address(
+            &[EMPLOYEE_ENTRY_SEED, business_key.as_ref(), &employee.employee_index.to_le_bytes()],
+            &crate::ID,
+        );
+        require!(employee_info.key() == expected, BagelError::InvalidState);
+        require!(!employee.is_active, BagelError::EmployeeActive);
+        require!(
+            ctx.accounts.pending_withdrawal.data_is_empty(),
+            BagelError::WithdrawalPending
+        );
 
-    /// CHECK: Permission account PDA for EmployeeEntry
-    /// Seeds: ["permission", employee_entry.key()]
-    #[account(mut, del)]
-    pub permission: UncheckedAccount<'info>,
+        let business = &mut ctx.accounts.business_entry;
 
-    /// CHECK: Permission Program (ACL)
-    #[account(address = Pubkey::try_from(crate::constants::MAGICBLOCK_PERMISSION_PROGRAM).unwrap())]
-    pub permission_program: UncheckedAccount<'info>,
+        // Earmarked from the business balance by fund_employee; hand it back
+        if employee.has_prefunded {
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                Operation {
+                    signer: ctx.accounts.authority.to_account_info(),
+                },
+            );
+            business.encrypted_balance = e_add(
+                cpi_ctx,
+                business.encrypted_balance.clone(),
+                employee.encrypted_prefunded.clone(),
+                0,
+            )?;
+        }
 
-    /// CHECK: Optional validator
-    pub validator: Option<AccountInfo<'info>>,
+        // Still owed to the employee: reserve it from drain_business
+        if business.has_closed_unpaid {
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                Operation {
+                    signer: ctx.accounts.authority.to_account_info(),
+                },
+            );
+            business.encrypted_closed_unpaid = e_add(
+                cpi_ctx,
+                business.encrypted_closed_unpaid.clone(),
+                employee.encrypted_accrued.clone(),
+                0,
+            )?;
+        } else {
+            business.encrypted_closed_unpaid = employee.encrypted_accrued.clone();
+            business.has_closed_unpaid = true;
+        }
 
-    pub system_program: Program<'info, System>,
-}
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        let encrypted_one = as_euint128(cpi_ctx, 1)?;
 
-#[derive(Accounts)]
-pub struct CommitFromTee<'info> {
-    #[account(mut)]
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        business.encrypted_employee_count = e_sub(
+            cpi_ctx,
+            business.encrypted_employee_count.clone(),
+            encrypted_one.clone(),
+            0,
+        )?;
+
+        let vault = &mut ctx.accounts.master_vault;
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        vault.encrypted_employee_count = e_sub(
+            cpi_ctx,
+            vault.encrypted_employee_count.clone(),
+            encrypted_one,
+            0,
+        )?;
+
+        anchor_lang::common::close(employee_info, ctx.accounts.authority.to_account_info())?;
+
+        msg!("🗑️  Employee entry closed");
+        msg!("   Employee Index: {}", employee.employee_index);
+
+        Ok(())
+    }
+
+    /// Drain a business's residual balance back to the employer (vault authority only)
+    ///
+    /// Transfers `encrypted_amount` from the master vault token account to
+    /// `employer_token_account` and subtracts it from `encrypted_balance`.
+    /// Inco `transfer` takes a ciphertext, not a handle, so the authority
+    /// decrypts the business balance off-chain and passes it re-encrypted;
+    /// the stored balance is then zero. The debit is clamped to the
+    /// balance (`e_ge` + `e_select`), and `BusinessDrained.covered` is the
+    /// encrypted flag auditors decrypt to confirm the amount fit in it.
+    ///
+    /// `employer_token_account` must be the refund account the employer
+    /// committed to at registration, opened with `refund_salt`.
+    ///
+    /// Accrued salaries are encrypted and cannot be checked here, so every
+    /// EmployeeEntry (0..next_employee_index) must be passed in
+    /// `remaining_accounts` and already closed via `close_employee`, and
+    /// the clamp works on the balance minus `encrypted_closed_unpaid`:
+    /// earnings closed employees were never paid are not drainable. The
+    /// business is deactivated afterwards.
+    pub fn drain_business<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DrainBusiness<'info>>,
+        encrypted_amount: Vec<u8>,
+        refund_salt: [u8; 32],
+    ) -> Result<()> {
+        validate_ciphertext(&encrypted_amount)?;
+
+        let vault = &ctx.accounts.master_vault;
+        require!(
+            ctx.accounts.authority.key() == vault.authority,
+            BagelError::Unauthorized
+        );
+        let committed = ctx.accounts.business_entry.refund_commitment;
+        require!(
+            committed != [0u8; 32]
+                && refund_commitment(&ctx.accounts.employer_token_account.key(), &refund_salt)
+                    == committed,
+            BagelError::Unauthorized
+        );
+        require!(
+            vault.use_confidential_tokens && vault.confidential_mint != Pubkey::default(),
+            BagelError::InvalidState
+        );
+
+        let business_key = ctx.accounts.business_entry.key();
+        require!(
+            ctx.remaining_accounts.len() as u64 == ctx.accounts.business_entry.next_employee_index,
+            BagelError::EmployeesRemaining
+        );
+        for (employee_index, account_info) in ctx.remaining_accounts.iter().enumerate() {
+            let (expected, _) = Pubkey::find_program_address(
+                &[EMPLOYEE_ENTRY_SEED, business_key.as_ref(), &(employee_index as u64).to_le_bytes()],
+                &crate::ID,
+            );
+            require!(account_info.key() == expected, BagelError::InvalidState);
+            require!(account_info.data_is_empty(), BagelError::EmployeesRemaining);
+        }
+
+        let employer_view = IncoTokenAccountView::parse(
+            &ctx.accounts.employer_token_account.try_borrow_data()?,
+        )?;
+        require!(
+            employer_view.mint == vault.confidential_mint,
+            BagelError::InvalidState
+        );
+
+        let bump = vault.bump;
+        let seeds: &[&[&[u8]]] = &[&[MASTER_VAULT_SEED, &[bump]]];
+        let cpi_accounts = IncoTransfer {
+            source: ctx.accounts.master_vault_token_account.to_account_info(),
+            destination: ctx.accounts.employer_token_account.to_account_info(),
+            authority: vault.to_account_info(),
+            inco_lightning_program: ctx.accounts.inco_lightning_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.inco_token_program.to_account_info(),
+            cpi_accounts,
+            seeds,
+        );
+        require_transfer_authority(&cpi_ctx.accounts.source, cpi_ctx.accounts.authority.key)?;
+        transfer(cpi_ctx, encrypted_amount.clone(), 0)?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        let encrypted_requested = new_euint128(cpi_ctx, encrypted_amount, 0)?;
+
+        let drainable = drainable_balance(
+            &ctx.accounts.business_entry,
+            &ctx.accounts.inco_lightning_program.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+        )?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        let covered = e_ge(cpi_ctx, drainable.clone(), encrypted_requested.clone(), 0)?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        let encrypted_drained = e_select(cpi_ctx, covered.clone(), encrypted_requested, drainable, 0)?;
+
+        track_vault_total(
+            &mut ctx.accounts.master_vault,
+            encrypted_drained.clone(),
+            false,
+            &ctx.accounts.inco_lightning_program.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+        )?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        let business = &mut ctx.accounts.business_entry;
+        business.encrypted_balance = e_sub(
+            cpi_ctx,
+            business.encrypted_balance.clone(),
+            encrypted_drained,
+            0,
+        )?;
+        business.is_active = false;
+
+        msg!("🚰 Business drained (PRIVATE)");
+        msg!("   Entry Index: {}", business.entry_index);
+        msg!("   Amount: ENCRYPTED");
+
+        emit!(BusinessDrained {
+            entry_index: business.entry_index,
+            covered,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Grow an undersized Bagel account to its current `LEN` (vault authority only)
+    ///
+    /// Once new fields exhaust an account's padding, `LEN` grows and
+    /// accounts created earlier are too small. This reallocs `target` to
+    /// the current `LEN` of `kind`, zero-initializing the new bytes and
+    /// topping up rent from the authority. A no-op if already large enough.
+    pub fn grow_account(ctx: Context<GrowAccount>, kind: GrowTarget) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let target = ctx.accounts.target.to_account_info();
+        require!(target.owner == &crate::ID, BagelError::InvalidState);
+        require!(
+            target.try_borrow_data()?.get(..8) == Some(kind.discriminator()),
+            BagelError::InvalidState
+        );
+
+        let old_len = target.data_len();
+        let grown = grow_to(
+            &target,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            kind.target_len(),
+        )?;
+
+        if grown {
+            msg!("✅ Account grown");
+            msg!("   Old size: {} bytes", old_len);
+            msg!("   New size: {} bytes", kind.target_len());
+        } else {
+            msg!("✅ Account already at current size");
+        }
+
+        Ok(())
+    }
+
+    /// Migrate MasterVault from old structure to new structure
+    /// 
+    /// Reads old format manually and writes new format with additional fields
+    /// This allows upgrading existing vaults without losing data
+    pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+        let vault_info = &mut ctx.accounts.master_vault;
+        let authority = &ctx.accounts.authority;
+        
+        // Verify PDA seeds match (this validates the account is the correct vault)
+        let (expected_vault, _bump) = Pubkey::find_program_address(
+            &[MASTER_VAULT_SEED],
+            ctx.program_id,
+        );
+        require!(
+            vault_info.key() == expected_vault,
+            BagelError::InvalidState
+        );
+        
+        // Read old data and extract all fields before any mutations
+        let old_data_len = vault_info.data_len();
+        let old_data = vault_info.try_borrow_data()?;
+        
+        if old_data.len() < 40 {
+            return Err(BagelError::InvalidState.into());
+        }
+        require_vault_discriminator(&old_data)?;
+        
+        let old_authority = Pubkey::try_from(&old_data[8..40])
+            .map_err(|_| BagelError::InvalidState)?;
+        
+        require!(
+            authority.key() == old_authority,
+            BagelError::Unauthorized
+        );
+        
+        // Build the new layout (None = already migrated)
+        let migrated = migrate_vault_data(&old_data)?;
+        drop(old_data); // Release borrow before realloc
+
+        let new_data = match migrated {
+            Some(new_data) => new_data,
+            None => {
+                msg!("✅ Vault already migrated");
+                return Ok(());
+            }
+        };
+
+        // Resize account if needed (rent topped up by the authority)
+        let new_size = MasterVault::LEN;
+        grow_to(
+            &vault_info.to_account_info(),
+            &authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            new_size,
+        )?;
+
+        vault_info.try_borrow_mut_data()?[..new_size].copy_from_slice(&new_data);
+        
+        msg!("✅ Vault migrated successfully");
+        msg!("   Old size: {} bytes", old_data_len);
+        msg!("   New size: {} bytes", new_size);
+        
+        Ok(())
+    }
+
+    /// Delegate employee entry to MagicBlock TEE (optional)
+    /// 
+    /// **NEW:** Now includes Permission Program integration:
+    /// 1. Creates permission account if it doesn't exist
+    /// 2. Sets initial members (employer + employee) with appropriate flags
+    /// 3. Delegates both permission AND permissioned_account to TEE
+    /// 
+    /// **Parameters:**
+    /// - `employer`: The employer's public key (for permission account)
+    /// - `employee`: The employee's public key (for permission account)
+    ///
+    /// Rejects with `InsufficientFunds` when the business has never been
+    /// funded. The balance itself is encrypted and an `e_gt` result
+    /// cannot abort the transaction, so this only rules out streams from
+    /// a business that has never received a deposit.
+    ///
+    /// Rejects with `AlreadyDelegated` when the entry or its permission
+    /// account is already owned by the delegation program.
+    pub fn delegate_to_tee(
+        ctx: Context<DelegateToTee>,
+        employer: Pubkey,
+        employee: Pubkey,
+    ) -> Result<()> {
+        require!(ctx.accounts.business_entry.is_funded, BagelError::InsufficientFunds);
+        require!(
+            ctx.accounts.master_vault.provider_flags.contains(ProviderFlags::TEE_ACTIVE),
+            BagelError::ProviderDisabled
+        );
+
+        let delegation_program = Pubkey::try_from(crate::constants::MAGICBLOCK_DELEGATION_PROGRAM)
+            .map_err(|_| BagelError::InvalidState)?;
+        require!(
+            ctx.accounts.employee_entry.to_account_info().owner != &delegation_program
+                && ctx.accounts.permission.owner != &delegation_program,
+            BagelError::AlreadyDelegated
+        );
+
+        msg!("⚡ Delegating to MagicBlock TEE with Permission Program...");
+        msg!("   Employer: {}", employer);
+        msg!("   Employee: {}", employee);
+        
+        let business_entry_key = ctx.accounts.business_entry.key();
+        let employee_index_bytes = ctx.accounts.employee_entry.employee_index.to_le_bytes();
+        let employee_entry_key = ctx.accounts.employee_entry.key();
+        
+        // Step 1: Create permission account if it doesn't exist
+        let permission_info = ctx.accounts.permission.to_account_info();
+        let permission_exists = permission_info.data_len() > 0;
+        
+        if !permission_exists {
+            msg!("🔐 Creating permission account...");
+            
+            // Create permission account via CPI
+            use crate::privacy::magicblock::create_employee_entry_permission;
+            
+            // Build CPI context for permission creation
+            // We need to create a new CPI context with the Permission Program
+            let create_permission_ctx = CpiContext::new(
+                ctx.accounts.permission_program.to_account_info(),
+                CreateEmployeePermission {
+                    payer: ctx.accounts.payer.clone(),
+                    employee_entry: ctx.accounts.employee_entry.to_account_info(),
+                    permission: ctx.accounts.permission.clone(),
+                    permission_program: ctx.accounts.permission_program.clone(),
+                    system_program: ctx.accounts.system_program.clone(),
+                },
+            );
+            
+            create_employee_entry_permission(
+                &create_permission_ctx,
+                employer,
+                employee,
+            )?;
+        } else {
+            msg!("✅ Permission account already exists");
+        }
+        
+        // Stamp the delegation time into the account data now: the
+        // delegation program snapshots the data as-is, and Anchor does not
+        // write back an account it no longer owns
+        ctx.accounts.employee_entry.delegated_at = Clock::get()?.unix_timestamp;
+        ctx.accounts.employee_entry.exit(&crate::ID)?;
+
+        // Step 2: Delegate both permission and permissioned_account
+        let seeds: &[&[u8]] = &[
+            EMPLOYEE_ENTRY_SEED,
+            business_entry_key.as_ref(),
+            &employee_index_bytes,
+        ];
+        
+        let validator = resolve_tee_validator(ctx.accounts.validator.as_ref().map(|v| v.key()))?;
+        
+        // Delegate EmployeeEntry (permissioned_account)
+        // The #[delegate] macro on the account context automatically handles delegation
+        // It will delegate the account marked with `del` constraint (employee_entry)
+        // We also need to delegate the permission account
+        ctx.accounts.delegate_employee_entry(
+            &ctx.accounts.payer,
+            seeds,
+            DelegateConfig {
+                validator: Some(validator),
+                ..Default::default()
+            },
+        )?;
+        
+        // Delegate Permission account
+        // The permission account also has `del` constraint, so it should be delegated
+        // by the #[delegate] macro. However, we need to ensure both are delegated.
+        // The SDK's delegate macro should handle multiple accounts with `del` constraint.
+        // For now, we rely on the macro to handle both accounts.
+
+        msg!("✅ Delegated to TEE");
+        msg!("   Employee Index: {}", ctx.accounts.employee_entry.employee_index);
+        msg!("   Permission PDA: {}", ctx.accounts.permission.key());
+        msg!("   Validator: {}", validator);
+        msg!("   ✅ Both permission AND permissioned_account delegated");
+
+        emit!(DelegatedToTee {
+            business_index: ctx.accounts.business_entry.entry_index,
+            employee_index: ctx.accounts.employee_entry.employee_index,
+            validator,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Accept an accrued handle computed and signed by the TEE
+    ///
+    /// The transaction must carry an Ed25519 program instruction in which
+    /// `TEE_VALIDATOR` signs `tee_accrued_message(employee_entry,
+    /// accrued_handle, last_action, accrued_until, accrued_until_slot)`;
+    /// anything else fails with `Unauthorized`. `accrued_until` and
+    /// `accrued_until_slot` are the time and slot the TEE computed the
+    /// handle up to; the accrual clocks advance to them, not to now, so
+    /// the window between the TEE's computation and this submission is
+    /// still settled later. Binding `last_action` makes every signature
+    /// single-use, since acceptance must advance it. `accrued_handle` is
+    /// the 16-byte Euint128 handle, zero-padded to 32 bytes.
+    pub fn submit_tee_accrued(
+        ctx: Context<SubmitTeeAccrued>,
+        accrued_handle: [u8; 32],
+        accrued_until: i64,
+        accrued_until_slot: u64,
+        tee_signature: [u8; 64],
+    ) -> Result<()> {
+        require!(
+            accrued_handle[16..].iter().all(|b| *b == 0),
+            BagelError::InvalidCiphertext
+        );
+        let handle = Euint128::deserialize(&mut &accrued_handle[..16])
+            .map_err(|_| BagelError::InvalidCiphertext)?;
+
+        let tee_validator = Pubkey::try_from(TEE_VALIDATOR).map_err(|_| BagelError::InvalidState)?;
+        let employee_key = ctx.accounts.employee_entry.key();
+        let message = tee_accrued_message(
+            &employee_key,
+            &accrued_handle,
+            ctx.accounts.employee_entry.last_action,
+            accrued_until,
+            accrued_until_slot,
+        );
+        verify_tee_signature(
+            &ctx.accounts.instructions_sysvar.to_account_info(),
+            &message,
+            &tee_signature,
+            &tee_validator,
+        )
+        .map_err(|_| BagelError::Unauthorized)?;
+
+        let clock = Clock::get()?;
+        let employee = &mut ctx.accounts.employee_entry;
+        require!(
+            accrued_until > employee.last_action
+                && accrued_until <= clock.unix_timestamp
+                && accrued_until_slot >= employee.last_action_slot
+                && accrued_until_slot <= clock.slot,
+            BagelError::InvalidTimestamp
+        );
+        employee.encrypted_accrued = handle;
+        employee.last_action = accrued_until;
+        employee.last_action_slot = accrued_until_slot;
+        // The TEE value supersedes any catch-up; the next settlement
+        // re-derives what is left after accrued_until
+        employee.pending_catchup_seconds = 0;
+
+        msg!("🛰️ TEE accrued value accepted (ENCRYPTED)");
+        msg!("   Employee Index: {}", employee.employee_index);
+
+        emit!(TeeAccruedSubmitted {
+            business_index: ctx.accounts.business_entry.entry_index,
+            employee_index: employee.employee_index,
+            accrued_handle: employee.encrypted_accrued.clone(),
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Commit TEE state back to L1
+    /// 
+    /// **NEW:** Now includes Permission Program - commits both permission
+    /// and permissioned_account back to L1
+    pub fn commit_from_tee(ctx: Context<CommitFromTee>) -> Result<()> {
+        require!(
+            accrual::delegation_matured(
+                ctx.accounts.employee_entry.delegated_at,
+                Clock::get()?.unix_timestamp,
+                ctx.accounts.master_vault.min_delegation_duration,
+            ),
+            BagelError::DelegationTooShort
+        );
+
+        msg!("⚡ Committing from TEE to L1 (with Permission Program)...");
+
+        // L1 anchor for the committed balance, written before the commit
+        record_accrued_snapshot(&mut ctx.accounts.employee_entry, Clock::get()?.unix_timestamp)?;
+        ctx.accounts.employee_entry.exit(&crate::ID)?;
+
+        let payer_info = ctx.accounts.payer.to_account_info();
+        let employee_info = ctx.accounts.employee_entry.to_account_info();
+        let permission_info = ctx.accounts.permission.to_account_info();
+        let magic_context_info = ctx.accounts.magic_context.to_account_info();
+        let magic_program_info = ctx.accounts.magic_program.to_account_info();
+
+        // Commit both permission and permissioned_account
+        // The SDK's commit_and_undelegate_accounts should handle both
+        commit_and_undelegate_accounts(
+            &payer_info,
+            vec![&employee_info, &permission_info],
+            &magic_context_info,
+            &magic_program_info,
+        )?;
+
+        msg!("✅ Committed to L1");
+        msg!("   ✅ Both permission AND permissioned_account undelegated");
+
+        emit!(CommittedFromTee {
+            business_index: ctx.accounts.business_entry.entry_index,
+            employee_index: ctx.accounts.employee_entry.employee_index,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Commit several employees' TEE state back to L1 in one call
+    ///
+    /// `remaining_accounts` holds `[employee_entry, permission]` pairs, the
+    /// same two accounts `commit_from_tee` commits per employee, in the
+    /// order of `employee_indices`. Every entry must belong to
+    /// `business_entry`; all of them go through a single
+    /// `commit_and_undelegate_accounts`.
+    pub fn commit_from_tee_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CommitFromTeeBatch<'info>>,
+        employee_indices: Vec<u64>,
+    ) -> Result<()> {
+        // One [employee_entry, permission] pair per declared index
+        validate_batch_accounts(ctx.remaining_accounts, 2 * employee_indices.len())?;
+
+        let business_key = ctx.accounts.business_entry.key();
+        let keys: Vec<Pubkey> = ctx.remaining_accounts.iter().map(|a| a.key()).collect();
+        validate_batch_order(&business_key, &employee_indices, &keys)?;
+
+        msg!("⚡ Batch committing from TEE to L1...");
+
+        let now = Clock::get()?.unix_timestamp;
+        for pair in ctx.remaining_accounts.chunks(2) {
+            let mut employee: Account<EmployeeEntry> = Account::try_from(&pair[0])?;
+            require!(employee.business_entry == business_key, BagelError::InvalidState);
+            require!(
+                accrual::delegation_matured(
+                    employee.delegated_at,
+                    now,
+                    ctx.accounts.master_vault.min_delegation_duration,
+                ),
+                BagelError::DelegationTooShort
+            );
+            record_accrued_snapshot(&mut employee, now)?;
+            employee.exit(&crate::ID)?;
+        }
+
+        let payer_info = ctx.accounts.payer.to_account_info();
+        let magic_context_info = ctx.accounts.magic_context.to_account_info();
+        let magic_program_info = ctx.accounts.magic_program.to_account_info();
+
+        commit_and_undelegate_accounts(
+            &payer_info,
+            ctx.remaining_accounts.iter().collect(),
+            &magic_context_info,
+            &magic_program_info,
+        )?;
+
+        msg!("✅ Committed {} employees", employee_indices.len());
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        for employee_index in employee_indices {
+            emit!(CommittedFromTee {
+                business_index: ctx.accounts.business_entry.entry_index,
+                employee_index,
+                timestamp,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Force a stuck EmployeeEntry back to L1 (vault authority only)
+    ///
+    /// Recovery for a `delegate_to_tee` that landed while the client never
+    /// finished: L1 shows the entry (and possibly its permission account)
+    /// owned by the delegation program, and nothing else can unstick it.
+    /// Send this to the rollup, where the delegated entry is writable by
+    /// Bagel again. An owner still equal to the delegation program means
+    /// it was sent to L1 and fails with `StillDelegated`.
+    ///
+    /// The entry is taken unchecked and located by `employee_index`, so a
+    /// half-written state does not block recovery. Pass
+    /// `include_permission = false` when only the entry was delegated.
+    pub fn force_recover_delegation(
+        ctx: Context<ForceRecoverDelegation>,
+        employee_index: u64,
+        include_permission: bool,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let business_key = ctx.accounts.business_entry.key();
+        let (expected, _) = Pubkey::find_program_address(
+            &[EMPLOYEE_ENTRY_SEED, business_key.as_ref(), &employee_index.to_le_bytes()],
+            &crate::ID,
+        );
+        let employee_info = ctx.accounts.employee_entry.to_account_info();
+        require!(employee_info.key() == expected, BagelError::InvalidState);
+
+        let delegation_program = Pubkey::try_from(crate::constants::MAGICBLOCK_DELEGATION_PROGRAM)
+            .map_err(|_| BagelError::InvalidState)?;
+        msg!("🩺 Recovering delegation...");
+        msg!("   Employee Index: {}", employee_index);
+        msg!("   Owner: {}", employee_info.owner);
+        require!(
+            employee_info.owner != &delegation_program,
+            BagelError::StillDelegated
+        );
+
+        let payer_info = ctx.accounts.authority.to_account_info();
+        let permission_info = ctx.accounts.permission.to_account_info();
+        let magic_context_info = ctx.accounts.magic_context.to_account_info();
+        let magic_program_info = ctx.accounts.magic_program.to_account_info();
+
+        let mut accounts = vec![&employee_info];
+        if include_permission {
+            accounts.push(&permission_info);
+        }
+
+        commit_and_undelegate_accounts(
+            &payer_info,
+            accounts,
+            &magic_context_info,
+            &magic_program_info,
+        )?;
+
+        msg!("✅ Delegation recovered");
+
+        emit!(DelegationRecovered {
+            business_index: ctx.accounts.business_entry.entry_index,
+            employee_index,
+            included_permission: include_permission,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Settle accrual and commit TEE state back to L1
+    ///
+    /// Like `commit_from_tee`, but first folds `salary * elapsed` into
+    /// `encrypted_accrued` (e_mul + e_add) so the committed L1 state
+    /// reflects earnings up to this second.
+    pub fn settle_on_undelegate(ctx: Context<SettleOnUndelegate>) -> Result<()> {
+        let clock = Clock::get()?;
+        let employee = &mut ctx.accounts.employee_entry;
+        require!(
+            accrual::delegation_matured(
+                employee.delegated_at,
+                clock.unix_timestamp,
+                ctx.accounts.master_vault.min_delegation_duration,
+            ),
+            BagelError::DelegationTooShort
+        );
+
+        msg!("⚡ Settling accrual before undelegate...");
+
+        let conversion = accrual_conversion(
+            &ctx.accounts.business_entry,
+            ctx.accounts.price_feed_config.as_deref(),
+            ctx.accounts.price_feed.as_ref().map(|f| f.as_ref()),
+            clock.unix_timestamp,
+        )?;
+
+        settle_accrual(
+            employee,
+            &ctx.accounts.business_entry,
+            conversion,
+            &ctx.accounts.inco_lightning_program.to_account_info(),
+            &ctx.accounts.payer.to_account_info(),
+            clock.unix_timestamp,
+            clock.slot,
+        )?;
+
+        // Serialize the settled state before handing the account to the commit
+        employee.exit(&crate::ID)?;
+
+        let payer_info = ctx.accounts.payer.to_account_info();
+        let employee_info = ctx.accounts.employee_entry.to_account_info();
+        let permission_info = ctx.accounts.permission.to_account_info();
+        let magic_context_info = ctx.accounts.magic_context.to_account_info();
+        let magic_program_info = ctx.accounts.magic_program.to_account_info();
+
+        commit_and_undelegate_accounts(
+            &payer_info,
+            vec![&employee_info, &permission_info],
+            &magic_context_info,
+            &magic_program_info,
+        )?;
+
+        msg!("✅ Settled and committed to L1");
+
+        emit!(CommittedFromTee {
+            business_index: ctx.accounts.business_entry.entry_index,
+            employee_index: ctx.accounts.employee_entry.employee_index,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Suspend an employee and halt TEE accrual (vault authority only)
+    ///
+    /// Runs on the rollup: settles earnings up to now, marks the entry
+    /// inactive and commits+undelegates it so the TEE stops adding salary.
+    /// Withdrawals are blocked by `is_active` until `resume_employee`.
+    pub fn suspend_employee(ctx: Context<SuspendEmployee>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let clock = Clock::get()?;
+        let employee = &mut ctx.accounts.employee_entry;
+        require!(employee.is_active, BagelError::PayrollInactive);
+
+        msg!("⏸️ Suspending employee...");
+
+        let conversion = accrual_conversion(
+            &ctx.accounts.business_entry,
+            ctx.accounts.price_feed_config.as_deref(),
+            ctx.accounts.price_feed.as_ref().map(|f| f.as_ref()),
+            clock.unix_timestamp,
+        )?;
+
+        settle_accrual(
+            employee,
+            &ctx.accounts.business_entry,
+            conversion,
+            &ctx.accounts.inco_lightning_program.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            clock.unix_timestamp,
+            clock.slot,
+        )?;
+        employee.is_active = false;
+        employee.suspended_at = clock.unix_timestamp;
+
+        // Serialize the suspended state before handing the account to the commit
+        employee.exit(&crate::ID)?;
+
+        let payer_info = ctx.accounts.authority.to_account_info();
+        let employee_info = ctx.accounts.employee_entry.to_account_info();
+        let permission_info = ctx.accounts.permission.to_account_info();
+        let magic_context_info = ctx.accounts.magic_context.to_account_info();
+        let magic_program_info = ctx.accounts.magic_program.to_account_info();
+
+        commit_and_undelegate_accounts(
+            &payer_info,
+            vec![&employee_info, &permission_info],
+            &magic_context_info,
+            &magic_program_info,
+        )?;
+
+        msg!("✅ Employee suspended and undelegated");
+        msg!("   Employee Index: {}", ctx.accounts.employee_entry.employee_index);
+
+        emit!(EmployeeSuspended {
+            business_index: ctx.accounts.business_entry.entry_index,
+            employee_index: ctx.accounts.employee_entry.employee_index,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Resume a suspended employee (vault authority only)
+    ///
+    /// Runs on L1 once the suspension commit has landed. Restarts the
+    /// accrual clock at now so the suspended window is never paid.
+    /// Re-delegate with `delegate_to_tee` in the same transaction.
+    pub fn resume_employee(ctx: Context<ResumeEmployee>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let clock = Clock::get()?;
+        let employee = &mut ctx.accounts.employee_entry;
+        require!(
+            !employee.is_active && employee.suspended_at > 0,
+            BagelError::InvalidState
+        );
+
+        let suspended_seconds = clock.unix_timestamp.saturating_sub(employee.suspended_at);
+        employee.is_active = true;
+        employee.last_action = clock.unix_timestamp;
+        employee.last_action_slot = clock.slot;
+        employee.pending_catchup_seconds = 0;
+        employee.suspended_at = 0;
+
+        msg!("▶️ Employee resumed");
+        msg!("   Employee Index: {}", employee.employee_index);
+        msg!("   Suspended for: {} seconds", suspended_seconds);
+
+        emit!(EmployeeResumed {
+            business_index: ctx.accounts.business_entry.entry_index,
+            employee_index: employee.employee_index,
+            suspended_seconds,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Choose the clock an employee's stream accrues on
+    ///
+    /// `0` = `unix_timestamp` seconds (default), `1` = `Clock::slot`
+    /// deltas, which validators cannot skew the way they can nudge the
+    /// timestamp. Rates stay per second; slot windows are scaled by
+    /// `accrual::MS_PER_SLOT`. An active employee is settled on the old
+    /// basis first and both clocks are aligned to now, less any clamped
+    /// catch-up still owed, so no window is paid twice or dropped.
+    pub fn set_accrual_basis(ctx: Context<SetAccrualBasis>, accrual_basis: u8) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+        accrual::validate_accrual_basis(accrual_basis)?;
+
+        let clock = Clock::get()?;
+        let conversion = accrual_conversion(
+            &ctx.accounts.business_entry,
+            ctx.accounts.price_feed_config.as_deref(),
+            ctx.accounts.price_feed.as_ref().map(|f| f.as_ref()),
+            clock.unix_timestamp,
+        )?;
+
+        let employee = &mut ctx.accounts.employee_entry;
+        if employee.is_active {
+            settle_accrual(
+                employee,
+                &ctx.accounts.business_entry,
+                conversion,
+                &ctx.accounts.inco_lightning_program.to_account_info(),
+                &ctx.accounts.authority.to_account_info(),
+                clock.unix_timestamp,
+                clock.slot,
+            )?;
+        }
+        employee.accrual_basis = accrual_basis;
+        let (last_action, last_action_slot) = accrual::catchup_clocks(
+            clock.unix_timestamp,
+            clock.slot,
+            employee.pending_catchup_seconds,
+        );
+        employee.last_action = last_action;
+        employee.last_action_slot = last_action_slot;
+
+        msg!("⚙️ Accrual basis updated");
+        msg!("   Employee Index: {}", employee.employee_index);
+        msg!("   Basis: {}", accrual_basis);
+
+        emit!(AccrualBasisUpdated {
+            business_index: ctx.accounts.business_entry.entry_index,
+            employee_index: employee.employee_index,
+            accrual_basis,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Cap what an employee can ever accrue (their share of the budget)
+    ///
+    /// Vault authority only, acting for the employer. The budget bounds
+    /// lifetime earnings, `accrued + encrypted_total_withdrawn`: settlement
+    /// clamps `encrypted_accrued` to `budget - total_withdrawn` (at least
+    /// zero) with `e_ge` + `e_select`, so the vault never pays out more
+    /// than was allocated across withdrawals. `None` removes the cap.
+    pub fn set_employee_budget(
+        ctx: Context<SetEmployeeBudget>,
+        encrypted_budget: Option<Vec<u8>>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let employee = &mut ctx.accounts.employee_entry;
+        match encrypted_budget {
+            Some(ciphertext) => {
+                validate_ciphertext(&ciphertext)?;
+                let cpi_ctx = CpiContext::new(
+                    ctx.accounts.inco_lightning_program.to_account_info(),
+                    Operation {
+                        signer: ctx.accounts.authority.to_account_info(),
+                    },
+                );
+                employee.encrypted_budget = new_euint128(cpi_ctx, ciphertext, 0)?;
+                employee.has_budget = true;
+            }
+            None => {
+                employee.encrypted_budget = Euint128::default();
+                employee.has_budget = false;
+            }
+        }
+
+        msg!("💰 Employee budget updated");
+        msg!("   Employee Index: {}", employee.employee_index);
+        msg!("   Budget: {}", if employee.has_budget { "ENCRYPTED" } else { "NONE" });
+
+        emit!(EmployeeBudgetSet {
+            business_index: ctx.accounts.business_entry.entry_index,
+            employee_index: employee.employee_index,
+            budget_handle: employee.encrypted_budget.clone(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Renew a fixed-term employee (vault authority only)
+    ///
+    /// `new_end` must be in the future and later than the current end,
+    /// or 0 to make the stream open-ended. Renewing after the term has
+    /// ended does not back-pay the gap: the entry must already be settled
+    /// up to the old end, and accrual restarts from now.
+    pub fn extend_term(ctx: Context<ExtendTerm>, new_end: i64) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let clock = Clock::get()?;
+        let employee = &mut ctx.accounts.employee_entry;
+        let old_end = employee.end_timestamp;
+        require!(
+            new_end == 0 || (new_end > clock.unix_timestamp && (old_end == 0 || new_end > old_end)),
+            BagelError::InvalidTimestamp
+        );
+
+        if old_end > 0 && clock.unix_timestamp >= old_end {
+            require!(employee.last_action >= old_end, BagelError::InvalidState);
+            employee.last_action = clock.unix_timestamp;
+            employee.last_action_slot = clock.slot;
+            employee.pending_catchup_seconds = 0;
+        }
+        employee.end_timestamp = new_end;
+
+        msg!("📆 Employee term extended");
+        msg!("   Employee Index: {}", employee.employee_index);
+        msg!("   End: {} -> {}", old_end, new_end);
+
+        emit!(TermExtended {
+            business_index: ctx.accounts.business_entry.entry_index,
+            employee_index: employee.employee_index,
+            old_end,
+            new_end,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Let an employee's first withdrawal skip the withdraw interval
+    /// (vault authority only)
+    ///
+    /// Meant to be set at onboarding so a sign-on bonus or initial
+    /// reimbursement can be withdrawn right away. Has no effect once the
+    /// employee has been paid; the waiver is single-use.
+    pub fn set_immediate_first_withdrawal(
+        ctx: Context<SetImmediateFirstWithdrawal>,
+        allow: bool,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let employee = &mut ctx.accounts.employee_entry;
+        employee.allow_immediate_first_withdrawal = allow;
+
+        msg!("⚙️ Immediate first withdrawal updated");
+        msg!("   Employee Index: {}", employee.employee_index);
+        msg!("   Allowed: {}", allow);
+        msg!("   Already used: {}", employee.first_withdrawal_done);
+
+        emit!(ImmediateFirstWithdrawalSet {
+            business_index: ctx.accounts.business_entry.entry_index,
+            employee_index: employee.employee_index,
+            allow,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Register the wallet that signs the employee's withdrawals and
+    /// receives them (vault authority only)
+    ///
+    /// The entry otherwise holds only an encrypted employee id, which the
+    /// program cannot compare against a signer. The employer knows who the
+    /// employee is and records their wallet here at onboarding; the event
+    /// omits it. `Pubkey::default()` clears it, closing every
+    /// employee-signed payout path for the entry.
+    pub fn set_withdraw_authority(
+        ctx: Context<SetWithdrawAuthority>,
+        withdraw_authority: Pubkey,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let employee = &mut ctx.accounts.employee_entry;
+        employee.withdraw_authority = withdraw_authority;
+
+        msg!("⚙️ Withdraw authority updated");
+        msg!("   Employee Index: {}", employee.employee_index);
+
+        emit!(WithdrawAuthoritySet {
+            business_index: ctx.accounts.business_entry.entry_index,
+            employee_index: employee.employee_index,
+            registered: withdraw_authority != Pubkey::default(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Earmark part of a business balance for one employee
+    ///
+    /// Vault authority only, acting for the employer. Moves
+    /// `encrypted_amount` from the business `encrypted_balance` into the
+    /// employee's `encrypted_prefunded` bucket; no tokens move, the funds
+    /// stay in the master vault. Withdrawals spend the prefunded bucket
+    /// before accrued earnings, so the payout is guaranteed regardless of
+    /// what happens to the shared pool. The move is clamped to the
+    /// business balance (`e_ge` + `e_select`, as in `drain_business`) so
+    /// the bucket never holds more than was backed; `EmployeeFunded.covered`
+    /// is the encrypted flag showing whether the full amount fit.
+    pub fn fund_employee(ctx: Context<FundEmployee>, encrypted_amount: Vec<u8>) -> Result<()> {
+        require!(!ctx.accounts.business_entry.is_frozen, BagelError::BusinessIsFrozen);
+        validate_ciphertext(&encrypted_amount)?;
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        let requested = new_euint128(cpi_ctx, encrypted_amount, 0)?;
+
+        let business = &mut ctx.accounts.business_entry;
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        let covered = e_ge(cpi_ctx, business.encrypted_balance.clone(), requested.clone(), 0)?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        let amount = e_select(
+            cpi_ctx,
+            covered.clone(),
+            requested,
+            business.encrypted_balance.clone(),
+            0,
+        )?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.authority.to_account_info(),
+            },
+        );
+        business.encrypted_balance = e_sub(
+            cpi_ctx,
+            business.encrypted_balance.clone(),
+            amount.clone(),
+            0,
+        )?;
+
+        let employee = &mut ctx.accounts.employee_entry;
+        if employee.has_prefunded {
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                Operation {
+                    signer: ctx.accounts.authority.to_account_info(),
+                },
+            );
+            employee.encrypted_prefunded = e_add(
+                cpi_ctx,
+                employee.encrypted_prefunded.clone(),
+                amount,
+                0,
+            )?;
+        } else {
+            employee.encrypted_prefunded = amount;
+            employee.has_prefunded = true;
+        }
+
+        msg!("🎯 Employee prefunded (PRIVATE)");
+        msg!("   Employee Index: {}", employee.employee_index);
+        msg!("   Amount: ENCRYPTED");
+
+        emit!(EmployeeFunded {
+            business_index: business.entry_index,
+            employee_index: employee.employee_index,
+            prefunded_handle: employee.encrypted_prefunded.clone(),
+            covered,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Withhold a share of each payout to the business's tax account
+    ///
+    /// Vault authority only, acting for the employer. `withholding_bps`
+    /// is per employee (0 disables it); the tax account is shared by the
+    /// business and must be an Inco Token account for the vault's
+    /// confidential mint. Withholding employees can only be paid through
+    /// `request_withdrawal`, which transfers the two shares separately.
+    pub fn set_withholding(ctx: Context<SetWithholding>, withholding_bps: u16) -> Result<()> {
+        let vault = &ctx.accounts.master_vault;
+        require!(
+            ctx.accounts.authority.key() == vault.authority,
+            BagelError::Unauthorized
+        );
+        require!(withholding_bps <= 10_000, BagelError::InvalidAmount);
+
+        let tax_account = &ctx.accounts.tax_token_account;
+        let inco_token_program_id = Pubkey::try_from(crate::constants::INCO_TOKEN_PROGRAM_ID).unwrap();
+        require!(
+            *tax_account.owner == inco_token_program_id,
+            BagelError::InvalidState
+        );
+        let tax_view = IncoTokenAccountView::parse(&tax_account.try_borrow_data()?)?;
+        require!(
+            tax_view.mint == vault.confidential_mint,
+            BagelError::InvalidState
+        );
+
+        let config = &mut ctx.accounts.withholding_config;
+        config.business_entry = ctx.accounts.business_entry.key();
+        config.tax_token_account = tax_account.key();
+        config.bump = ctx.bumps.withholding_config;
+
+        let employee = &mut ctx.accounts.employee_entry;
+        employee.withholding_bps = withholding_bps;
+
+        msg!("🧾 Withholding updated");
+        msg!("   Employee Index: {}", employee.employee_index);
+        msg!("   Rate: {} bps", withholding_bps);
+
+        emit!(WithholdingUpdated {
+            business_index: ctx.accounts.business_entry.entry_index,
+            employee_index: employee.employee_index,
+            withholding_bps,
+            tax_token_account: config.tax_token_account,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Delegate the MasterVault to MagicBlock TEE (optional)
+    ///
+    /// Lets aggregate computations over the encrypted business/employee
+    /// counts run inside the TEE. The vault is delegated with its own PDA
+    /// seeds: ["master_vault"]. Only the vault authority can delegate.
+    pub fn delegate_vault_to_tee(ctx: Context<DelegateVaultToTee>) -> Result<()> {
+        require!(
+            ctx.accounts.payer.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+        require!(
+            ctx.accounts.master_vault.provider_flags.contains(ProviderFlags::TEE_ACTIVE),
+            BagelError::ProviderDisabled
+        );
+
+        msg!("⚡ Delegating Master Vault to MagicBlock TEE...");
+
+        let seeds: &[&[u8]] = &[MASTER_VAULT_SEED];
+
+        let validator = resolve_tee_validator(ctx.accounts.validator.as_ref().map(|v| v.key()))?;
+
+        ctx.accounts.delegate_master_vault(
+            &ctx.accounts.payer,
+            seeds,
+            DelegateConfig {
+                validator: Some(validator),
+                ..Default::default()
+            },
+        )?;
+
+        msg!("✅ Master Vault delegated to TEE");
+        msg!("   Validator: {}", validator);
+
+        emit!(VaultDelegatedToTee {
+            validator,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Commit MasterVault TEE state back to L1 and undelegate
+    pub fn commit_vault_from_tee(ctx: Context<CommitVaultFromTee>) -> Result<()> {
+        require!(
+            ctx.accounts.payer.key() == ctx.accounts.master_vault.authority,
+            BagelError::Unauthorized
+        );
+
+        msg!("⚡ Committing Master Vault from TEE to L1...");
+
+        let payer_info = ctx.accounts.payer.to_account_info();
+        let vault_info = ctx.accounts.master_vault.to_account_info();
+        let magic_context_info = ctx.accounts.magic_context.to_account_info();
+        let magic_program_info = ctx.accounts.magic_program.to_account_info();
+
+        commit_and_undelegate_accounts(
+            &payer_info,
+            vec![&vault_info],
+            &magic_context_info,
+            &magic_program_info,
+        )?;
+
+        msg!("✅ Master Vault committed to L1");
+
+        emit!(VaultCommittedFromTee {
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ============================================================
+    // PDA-based Token Account Instructions
+    // ============================================================
+
+    /// Initialize a user's token account as a PDA
+    ///
+    /// Derives a deterministic token account address from:
+    /// Seeds: ["user_token", wallet_pubkey, mint]
+    ///
+    /// This allows anyone to calculate a user's token account address
+    /// without needing to store it. The Bagel program owns the PDA
+    /// and can sign for token operations.
+    pub fn initialize_user_token_account(
+        ctx: Context<InitializeUserTokenAccount>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let owner_key = ctx.accounts.owner.key();
+        let mint_key = ctx.accounts.mint.key();
+        let pda_key = ctx.accounts.user_token_account.key();
+        let bump = ctx.bumps.user_token_account;
+
+        let user_token = &mut ctx.accounts.user_token_account;
+        user_token.owner = owner_key;
+        user_token.mint = mint_key;
+        user_token.inco_token_account = Pubkey::default(); // Set by mint API later
+        user_token.balance = Euint128::default();
+        user_token.initialized_at = clock.unix_timestamp;
+        user_token.bump = bump;
+
+        msg!("🔐 User token account initialized (PDA-based)");
+        msg!("   Owner: {}", owner_key);
+        msg!("   Mint: {}", mint_key);
+        msg!("   PDA: {}", pda_key);
+
+        emit!(UserTokenAccountInitialized {
+            owner: owner_key,
+            mint: mint_key,
+            token_account_pda: pda_key,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Get the PDA address for a user's token account (view function helper)
+    /// This is a no-op instruction that just logs the derived address
+    /// Clients should derive this off-chain using:
+    /// findProgramAddress(["user_token", wallet, mint], BAGEL_PROGRAM_ID)
+    pub fn get_user_token_pda(
+        ctx: Context<GetUserTokenPda>,
+    ) -> Result<()> {
+        msg!("📍 User Token PDA: {}", ctx.accounts.user_token_account.key());
+        msg!("   Owner: {}", ctx.accounts.owner.key());
+        msg!("   Mint: {}", ctx.accounts.mint.key());
+        Ok(())
+    }
+
+    /// Set the Inco Token account reference for a user
+    /// Called by the mint API after creating the Inco Token account
+    /// Only the owner or a trusted authority can call this
+    pub fn set_inco_token_account(
+        ctx: Context<SetIncoTokenAccount>,
+        inco_token_account: Pubkey,
+    ) -> Result<()> {
+        let user_token = &mut ctx.accounts.user_token_account;
+
+        // Only allow setting if not already set (one-time operation)
+        // or if the caller is the owner
+        require!(
+            user_token.inco_token_account == Pubkey::default() ||
+            ctx.accounts.authority.key() == user_token.owner,
+            BagelError::Unauthorized
+        );
+
+        user_token.inco_token_account = inco_token_account;
+
+        msg!("🔗 Inco Token Account linked");
+        msg!("   Owner: {}", user_token.owner);
+        msg!("   Inco Token Account: {}", inco_token_account);
+
+        emit!(IncoTokenAccountLinked {
+            owner: user_token.owner,
+            mint: user_token.mint,
+            inco_token_account,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ============================================================
+    // Permission Program (MagicBlock Access Control)
+    // ============================================================
+
+    /// Create permission account for EmployeeEntry
+    /// 
+    /// This instruction creates a permission account with initial members
+    /// (employer + employee) and appropriate flags for access control.
+    /// 
+    /// Can be called separately before delegation, or will be called
+    /// automatically during delegation if permission doesn't exist.
+    pub fn create_employee_permission(
+        ctx: Context<CreateEmployeePermission>,
+        employer: Pubkey,
+        employee: Pubkey,
+    ) -> Result<()> {
+        use crate::privacy::magicblock::create_employee_entry_permission;
+        
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.permission_program.to_account_info(),
+            CreateEmployeePermission {
+                payer: ctx.accounts.payer.clone(),
+                employee_entry: ctx.accounts.employee_entry.to_account_info(),
+                permission: ctx.accounts.permission.clone(),
+                permission_program: ctx.accounts.permission_program.clone(),
+                system_program: ctx.accounts.system_program.clone(),
+            },
+        );
+        
+        create_employee_entry_permission(
+            &cpi_ctx,
+            employer,
+            employee,
+        )?;
+
+        Ok(())
+    }
+
+    /// Update permission account members/flags
+    /// 
+    /// This allows updating permission members and flags in real-time
+    /// while the account is delegated to PER.
+    /// 
+    /// Note: Setting members to None makes the permission public (temporarily visible)
+    /// This is useful for transitional states during delegation/undelegation
+    pub fn update_employee_permission(
+        ctx: Context<UpdateEmployeePermission>,
+        member_pubkeys: Option<Vec<Pubkey>>,
+        member_flags: Option<Vec<u8>>,
+    ) -> Result<()> {
+        use crate::privacy::magicblock::update_employee_entry_permission;
+        use ephemeral_rollups_sdk::access_control::structs::{Member, MembersArgs};
+        
+        // Convert instruction parameters to SDK Member types
+        let members = if let (Some(pubkeys), Some(flags)) = (member_pubkeys, member_flags) {
+            if pubkeys.len() != flags.len() {
+                return Err(BagelError::InvalidState.into());
+            }
+            Some(
+                pubkeys
+                    .into_iter()
+                    .zip(flags.into_iter())
+                    .map(|(pubkey, flag)| Member {
+                        flags: flag,
+                        pubkey,
+                    })
+                    .collect(),
+            )
+        } else {
+            None // Makes permission public
+        };
+        
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.permission_program.to_account_info(),
+            UpdateEmployeePermission {
+                payer: ctx.accounts.payer.clone(),
+                employee_entry: ctx.accounts.employee_entry.to_account_info(),
+                permission: ctx.accounts.permission.clone(),
+                permission_program: ctx.accounts.permission_program.clone(),
+                system_program: ctx.accounts.system_program.clone(),
+            },
+        );
+        
+        update_employee_entry_permission(
+            &cpi_ctx,
+            members,
+        )?;
+
+        Ok(())
+    }
+}
+
+// ============================================================
+// Helpers
+// ============================================================
+
+/// Validate the basis-point shares of a `withdraw_split`
+///
+/// 1..=`MAX_SPLIT_RECIPIENTS` non-zero shares that sum to exactly 10_000.
+fn validate_split_bps(shares: &[u16]) -> Result<()> {
+    require!(
+        !shares.is_empty() && shares.len() <= MAX_SPLIT_RECIPIENTS,
+        BagelError::InvalidAmount
+    );
+    require!(shares.iter().all(|bps| *bps > 0), BagelError::InvalidAmount);
+    let total: u32 = shares.iter().map(|bps| *bps as u32).sum();
+    require!(total == 10_000, BagelError::InvalidAmount);
+    Ok(())
+}
+
+/// Validator to delegate to: the one passed in, else `TEE_VALIDATOR`
+///
+/// Never falls back to the SDK default validator; the result must be in
+/// `KNOWN_TEE_VALIDATORS` or delegation fails with `InvalidValidator`.
+fn resolve_tee_validator(provided: Option<Pubkey>) -> Result<Pubkey> {
+    let validator = match provided {
+        Some(validator) => validator,
+        None => Pubkey::try_from(TEE_VALIDATOR).map_err(|_| BagelError::InvalidValidator)?,
+    };
+    require!(
+        KNOWN_TEE_VALIDATORS
+            .iter()
+            .any(|known| Pubkey::try_from(*known).ok() == Some(validator)),
+        BagelError::InvalidValidator
+    );
+    Ok(validator)
+}
+
+/// Validate the `remaining_accounts` of a batch instruction
+///
+/// Rejects an empty batch (`NoAccountsProvided`) instead of letting it
+/// succeed as a silent no-op, a count that differs from the declared
+/// batch length, and any account passed twice - a duplicate would be
+/// loaded and written back independently, losing all but the last write.
+fn validate_batch_accounts(accounts: &[AccountInfo], expected_len: usize) -> Result<()> {
+    let keys: Vec<Pubkey> = accounts.iter().map(|a| a.key()).collect();
+    validate_batch_keys(&keys, expected_len)
+}
+
+fn validate_batch_keys(keys: &[Pubkey], expected_len: usize) -> Result<()> {
+    require!(!keys.is_empty(), BagelError::NoAccountsProvided);
+    require!(keys.len() == expected_len, BagelError::InvalidState);
+
+    let mut sorted = keys.to_vec();
+    sorted.sort_unstable();
+    require!(
+        sorted.windows(2).all(|w| w[0] != w[1]),
+        BagelError::DuplicateAccount
+    );
+    Ok(())
+}
+
+/// `Euint128` handle zero-padded to 32 bytes, as in `submit_tee_accrued`
+fn padded_handle(handle: &Euint128) -> Result<[u8; 32]> {
+    let mut padded = [0u8; 32];
+    handle
+        .serialize(&mut &mut padded[..])
+        .map_err(|_| BagelError::InvalidState)?;
+    Ok(padded)
+}
+
+/// Record the accrued handle being committed to L1 at `now`
+fn record_accrued_snapshot(employee: &mut EmployeeEntry, now: i64) -> Result<()> {
+    employee.last_snapshot_accrued = padded_handle(&employee.encrypted_accrued)?;
+    employee.last_snapshot_time = now;
+    Ok(())
+}
+
+/// Message the TEE signs for `submit_tee_accrued`
+fn tee_accrued_message(
+    employee_entry: &Pubkey,
+    accrued_handle: &[u8; 32],
+    last_action: i64,
+    accrued_until: i64,
+    accrued_until_slot: u64,
+) -> Vec<u8> {
+    let mut message = Vec::with_capacity(17 + 32 + 32 + 8 + 8 + 8);
+    message.extend_from_slice(b"bagel:tee_accrued");
+    message.extend_from_slice(employee_entry.as_ref());
+    message.extend_from_slice(accrued_handle);
+    message.extend_from_slice(&last_action.to_le_bytes());
+    message.extend_from_slice(&accrued_until.to_le_bytes());
+    message.extend_from_slice(&accrued_until_slot.to_le_bytes());
+    message
+}
+
+/// Message the vault authority signs to authorize importing `bundle`
+///
+/// `bagel:export` followed by the SHA-256 of the exact bundle bytes -
+/// the same hash `export_business` commits in `BusinessExported`.
+fn export_bundle_message(bundle: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(12 + 32);
+    message.extend_from_slice(b"bagel:export");
+    message.extend_from_slice(&anchor_lang::solana_program::hash::hash(bundle).to_bytes());
+    message
+}
+
+/// Commitment to an employer refund token account, stored at registration
+///
+/// SHA-256 of the account key followed by a 32-byte salt the employer
+/// keeps, so the stored value does not reveal which account it is.
+pub fn refund_commitment(account: &Pubkey, salt: &[u8; 32]) -> [u8; 32] {
+    anchor_lang::solana_program::hash::hashv(&[account.as_ref(), salt]).to_bytes()
+}
+
+/// Verify and decode an `export_business` bundle for import
+///
+/// Checks the signer's Ed25519 signature over `export_bundle_message`,
+/// the layout version, and that the records are strictly increasing
+/// indices inside `start..end`, with `end` within the business.
+fn verified_export(
+    bundle: &[u8],
+    signature: &[u8; 64],
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+) -> Result<BusinessExport> {
+    verify_tee_signature(
+        instructions_sysvar,
+        &export_bundle_message(bundle),
+        signature,
+        signer,
+    )
+    .map_err(|_| BagelError::InvalidBundle)?;
+
+    // try_from_slice also rejects trailing bytes
+    let export = BusinessExport::try_from_slice(bundle)
+        .map_err(|_| BagelError::InvalidBundle)?;
+    require!(export.version == EXPORT_VERSION, BagelError::InvalidBundle);
+    require!(
+        export.employees.len() <= MAX_EXPORT_EMPLOYEES,
+        BagelError::InvalidAmount
+    );
+    require!(
+        export.start <= export.end && export.end <= export.next_employee_index,
+        BagelError::InvalidBundle
+    );
+
+    let mut next = export.start;
+    for record in export.employees.iter() {
+        require!(
+            record.employee_index >= next && record.employee_index < export.end,
+            BagelError::InvalidBundle
+        );
+        next = record.employee_index + 1;
+    }
+
+    Ok(export)
+}
+
+/// `import_cursor` after importing `export`: its end, or 0 once complete
+fn next_import_cursor(export: &BusinessExport) -> u64 {
+    if export.end < export.next_employee_index {
+        export.end
+    } else {
+        0
+    }
+}
+
+/// Create the EmployeeEntry PDAs for a verified bundle's records
+///
+/// `accounts` must be the uninitialized PDAs in record order. Each entry
+/// gets the exported handles and settings; its slot clock is rebuilt
+/// from the local slot so slot-based accrual resumes at the exported
+/// `last_action`. Emits `SalaryChanged` per employee (from the zero
+/// handle, as in `add_employee`) so indexers see the imported rate.
+fn create_imported_employees<'info>(
+    business: &Account<'info, BusinessEntry>,
+    export: &BusinessExport,
+    accounts: &'info [AccountInfo<'info>],
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    clock: &Clock,
+) -> Result<()> {
+    if export.employees.is_empty() {
+        return Ok(());
+    }
+    validate_batch_accounts(accounts, export.employees.len())?;
+
+    let business_key = business.key();
+    let rent = Rent::get()?;
+
+    for (record, account_info) in export.employees.iter().zip(accounts.iter()) {
+        let index_bytes = record.employee_index.to_le_bytes();
+        let (expected, bump) = Pubkey::find_program_address(
+            &[EMPLOYEE_ENTRY_SEED, business_key.as_ref(), &index_bytes],
+            &crate::ID,
+        );
+        require!(account_info.key() == expected, BagelError::InvalidState);
+        require!(account_info.data_is_empty(), BagelError::InvalidState);
+
+        anchor_lang::system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                anchor_lang::system_program::CreateAccount {
+                    from: payer.clone(),
+                    to: account_info.clone(),
+                },
+                &[&[EMPLOYEE_ENTRY_SEED, business_key.as_ref(), &index_bytes, &[bump]]],
+            ),
+            rent.minimum_balance(EmployeeEntry::LEN),
+            EmployeeEntry::LEN as u64,
+            &crate::ID,
+        )?;
+        account_info.try_borrow_mut_data()?[..8].copy_from_slice(EmployeeEntry::DISCRIMINATOR);
+
+        let elapsed = clock.unix_timestamp.saturating_sub(record.last_action).max(0) as u64;
+        let (_, last_action_slot) =
+            accrual::catchup_clocks(clock.unix_timestamp, clock.slot, elapsed);
+
+        // Zeroed fields deserialize as their defaults
+        let mut employee: Account<EmployeeEntry> = Account::try_from(account_info)?;
+        employee.business_entry = business_key;
+        employee.employee_index = record.employee_index;
+        employee.encrypted_employee_id = record.encrypted_employee_id.clone();
+        employee.encrypted_salary = record.encrypted_salary.clone();
+        employee.encrypted_accrued = record.encrypted_accrued.clone();
+        employee.encrypted_total_withdrawn = record.encrypted_total_withdrawn.clone();
+        employee.encrypted_budget = record.encrypted_budget.clone();
+        employee.has_budget = record.has_budget;
+        employee.encrypted_prefunded = record.encrypted_prefunded.clone();
+        employee.has_prefunded = record.has_prefunded;
+        employee.last_action = record.last_action;
+        employee.last_action_slot = last_action_slot;
+        employee.pending_catchup_seconds = record.pending_catchup_seconds;
+        employee.last_withdrawal_at = record.last_withdrawal_at;
+        employee.suspended_at = record.suspended_at;
+        employee.end_timestamp = record.end_timestamp;
+        employee.accrual_basis = record.accrual_basis;
+        employee.withholding_bps = record.withholding_bps;
+        employee.allow_immediate_first_withdrawal = record.allow_immediate_first_withdrawal;
+        employee.first_withdrawal_done = record.first_withdrawal_done;
+        employee.withdraw_authority = record.withdraw_authority;
+        employee.is_active = record.is_active;
+        employee.bump = bump;
+        employee.exit(&crate::ID)?;
+
+        emit!(SalaryChanged {
+            business_index: business.entry_index,
+            employee_index: record.employee_index,
+            old_salary_handle: Euint128::default(),
+            new_salary_handle: record.encrypted_salary.clone(),
+            effective_at: clock.unix_timestamp,
+            above_band_min: None,
+            below_band_max: None,
+            within_ceiling: None,
+        });
+    }
+
+    Ok(())
+}
+
+/// Check batch `[employee_entry, permission]` pairs against declared indices
+///
+/// Pair `i` must be the EmployeeEntry PDA for `employee_indices[i]` followed
+/// by its MagicBlock permission PDA. Anything else - shuffled pairs, a
+/// swapped pair, a foreign account - fails with `AccountOrderMismatch`,
+/// so a client cannot commit (or pay) a different employee than declared.
+fn validate_batch_order(
+    business_key: &Pubkey,
+    employee_indices: &[u64],
+    keys: &[Pubkey],
+) -> Result<()> {
+    require!(
+        keys.len() == 2 * employee_indices.len(),
+        BagelError::InvalidState
+    );
+
+    let permission_program = Pubkey::try_from(crate::constants::MAGICBLOCK_PERMISSION_PROGRAM)
+        .map_err(|_| BagelError::InvalidState)?;
+    for (pair, employee_index) in keys.chunks(2).zip(employee_indices) {
+        let (expected_entry, _) = Pubkey::find_program_address(
+            &[EMPLOYEE_ENTRY_SEED, business_key.as_ref(), &employee_index.to_le_bytes()],
+            &crate::ID,
+        );
+        let (expected_permission, _) = Pubkey::find_program_address(
+            &[PERMISSION_SEED, expected_entry.as_ref()],
+            &permission_program,
+        );
+        require!(
+            pair[0] == expected_entry && pair[1] == expected_permission,
+            BagelError::AccountOrderMismatch
+        );
+    }
+    Ok(())
+}
+
+/// USD-to-token conversion for a settlement, if the business needs one
+///
+/// Native businesses return `None` and ignore the optional accounts.
+/// USD businesses must pass their `PriceFeedConfig` and a fresh, tight
+/// Pyth price for the configured feed.
+fn accrual_conversion(
+    business: &BusinessEntry,
+    price_feed_config: Option<&PriceFeedConfig>,
+    price_feed: Option<&AccountInfo>,
+    now: i64,
+) -> Result<Option<(u128, u128)>> {
+    if business.salary_denom == SalaryDenom::Native {
+        return Ok(None);
+    }
+
+    let (config, feed) = match (price_feed_config, price_feed) {
+        (Some(config), Some(feed)) => (config, feed),
+        _ => return err!(BagelError::InvalidPriceFeed),
+    };
+    let price = oracle::load_price(feed, &config.feed_id, now)?;
+    Ok(Some(oracle::usd_conversion(&price)?))
+}
+
+/// Write a `WithdrawalResult` as return data
+///
+/// Call after the payout's last CPI; any later CPI would replace it.
+fn set_withdrawal_result(result: &WithdrawalResult) -> Result<()> {
+    let mut data = Vec::new();
+    result.serialize(&mut data).map_err(|_| BagelError::InvalidState)?;
+    anchor_lang::solana_program::program::set_return_data(&data);
+    Ok(())
+}
+
+/// Reject empty or oversized client ciphertexts
+///
+/// Bounds every encrypted input so a caller cannot bloat the transaction
+/// or the Inco CPI with a multi-kilobyte blob.
+fn validate_ciphertext(ciphertext: &[u8]) -> Result<()> {
+    require!(
+        !ciphertext.is_empty() && ciphertext.len() <= MAX_CIPHERTEXT_LEN,
+        BagelError::InvalidCiphertext
+    );
+    Ok(())
+}
+
+/// Check that a business entry belongs to `vault`
+///
+/// Defense in depth: the seeds already bind the entry to the vault, so
+/// this only fails if a seeds constraint is ever loosened.
+fn require_entry_in_vault(business: &BusinessEntry, vault: &Pubkey) -> Result<()> {
+    require!(business.master_vault == *vault, BagelError::InvalidState);
+    Ok(())
+}
+
+/// Check that `account` is an Inco Token account for `mint` owned by the vault
+///
+/// Used wherever the vault's token account is recorded or credited, so a
+/// look-alike account of another mint or owner is never accepted.
+fn require_vault_token_account(account: &AccountInfo, vault: &Pubkey, mint: &Pubkey) -> Result<()> {
+    let inco_token_program_id = Pubkey::try_from(crate::constants::INCO_TOKEN_PROGRAM_ID)
+        .map_err(|_| BagelError::InvalidState)?;
+    require!(
+        *account.owner == inco_token_program_id,
+        BagelError::InvalidState
+    );
+    let view = IncoTokenAccountView::parse(&account.try_borrow_data()?)?;
+    require!(
+        view.mint == *mint && view.owner == *vault,
+        BagelError::InvalidState
+    );
+    Ok(())
+}
+
+/// Check that `signer` is the employee's registered `withdraw_authority`
+///
+/// Every employee-signed payout path calls this; rejects with
+/// `Unauthorized` while no authority is registered.
+fn require_withdraw_authority(employee: &EmployeeEntry, signer: &Pubkey) -> Result<()> {
+    require!(
+        employee.withdraw_authority != Pubkey::default() && *signer == employee.withdraw_authority,
+        BagelError::Unauthorized
+    );
+    Ok(())
+}
+
+/// Check that a payout lands in the withdraw authority's Inco Token account
+///
+/// Same mint and owner check as the vault's own account, so a signer
+/// cannot redirect an employee's payout to an account they control.
+fn require_payout_destination(
+    account: &AccountInfo,
+    employee: &EmployeeEntry,
+    mint: &Pubkey,
+) -> Result<()> {
+    require_vault_token_account(account, &employee.withdraw_authority, mint)
+}
+
+/// Seconds since the employee's last withdrawal, self-healing a future clock
+///
+/// A `last_withdrawal_at` ahead of `now` (clock skew or replayed TEE state)
+/// would fail every withdrawal with `InvalidTimestamp` forever. It is reset
+/// to `now` and `None` returned instead; the caller returns without paying
+/// and the next withdrawal after the interval proceeds.
+fn withdraw_elapsed(employee: &mut EmployeeEntry, now: i64) -> Result<Option<i64>> {
+    let elapsed = accrual::elapsed_since(employee.last_withdrawal_at, now)?;
+    if elapsed.is_none() {
+        msg!("⚠️  last_withdrawal_at ahead of clock - resetting to now");
+        employee.last_withdrawal_at = now;
+    }
+    Ok(elapsed)
+}
+
+/// Enforce the business withdraw interval on a manual withdrawal
+///
+/// Waived for employees with `allow_immediate_first_withdrawal` until
+/// their first payout (sign-on bonuses paid right after onboarding);
+/// every later withdrawal is rate-limited as usual. The waiver is only
+/// consumed by `record_payout`, so a cancelled timelocked withdrawal
+/// leaves it in place. `FirstWithdrawalWaived` marks each waived check.
+fn check_withdraw_interval(
+    employee: &mut EmployeeEntry,
+    business: &BusinessEntry,
+    time_elapsed: i64,
+    now: i64,
+) -> Result<()> {
+    let waived = employee.allow_immediate_first_withdrawal && !employee.first_withdrawal_done;
+    require!(
+        waived || time_elapsed >= business.withdraw_interval(),
+        BagelError::WithdrawTooSoon
+    );
+
+    if waived {
+        msg!("   Withdraw interval: WAIVED (first withdrawal)");
+        emit!(FirstWithdrawalWaived {
+            business_index: business.entry_index,
+            employee_index: employee.employee_index,
+            timestamp: now,
+        });
+    }
+    Ok(())
+}
+
+/// Fold a confidential vault inflow or outflow into the running total
+///
+/// `e_add` for deposits, `e_sub` for payouts. Vaults created before
+/// `encrypted_total_balance` existed never summed their earlier deposits,
+/// so they are left untracked rather than report a wrong aggregate.
+fn track_vault_total<'info>(
+    vault: &mut MasterVault,
+    amount: Euint128,
+    inflow: bool,
+    inco_lightning_program: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+) -> Result<()> {
+    if !vault.tracks_encrypted_total {
+        return Ok(());
+    }
+
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program.clone(),
+        Operation {
+            signer: signer.clone(),
+        },
+    );
+    let total = vault.encrypted_total_balance.clone();
+    vault.encrypted_total_balance = if inflow {
+        e_add(cpi_ctx, total, amount, 0)?
+    } else {
+        e_sub(cpi_ctx, total, amount, 0)?
+    };
+    Ok(())
+}
+
+/// Take a payout out of the employee's buckets, prefunded first
+///
+/// `min(amount, prefunded)` comes out of `encrypted_prefunded` (via
+/// `e_ge` + `e_select`) and only the remainder out of `encrypted_accrued`,
+/// so an earmarked pool is spent before streamed earnings. Employees
+/// never funded with `fund_employee` take the single `e_sub` path.
+fn debit_employee<'info>(
+    employee: &mut EmployeeEntry,
+    amount: Euint128,
+    inco_lightning_program: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+) -> Result<()> {
+    if !employee.has_prefunded {
+        let cpi_ctx = CpiContext::new(
+            inco_lightning_program.clone(),
+            Operation {
+                signer: signer.clone(),
+            },
+        );
+        employee.encrypted_accrued = e_sub(
+            cpi_ctx,
+            employee.encrypted_accrued.clone(),
+            amount,
+            0,
+        )?;
+        return Ok(());
+    }
+
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program.clone(),
+        Operation {
+            signer: signer.clone(),
+        },
+    );
+    let covered = e_ge(
+        cpi_ctx,
+        employee.encrypted_prefunded.clone(),
+        amount.clone(),
+        0,
+    )?;
+
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program.clone(),
+        Operation {
+            signer: signer.clone(),
+        },
+    );
+    let from_prefunded = e_select(
+        cpi_ctx,
+        covered,
+        amount.clone(),
+        employee.encrypted_prefunded.clone(),
+        0,
+    )?;
+
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program.clone(),
+        Operation {
+            signer: signer.clone(),
+        },
+    );
+    employee.encrypted_prefunded = e_sub(
+        cpi_ctx,
+        employee.encrypted_prefunded.clone(),
+        from_prefunded.clone(),
+        0,
+    )?;
+
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program.clone(),
+        Operation {
+            signer: signer.clone(),
+        },
+    );
+    let from_accrued = e_sub(cpi_ctx, amount, from_prefunded, 0)?;
+
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program.clone(),
+        Operation {
+            signer: signer.clone(),
+        },
+    );
+    employee.encrypted_accrued = e_sub(
+        cpi_ctx,
+        employee.encrypted_accrued.clone(),
+        from_accrued,
+        0,
+    )?;
+
+    Ok(())
+}
+
+/// Ledger side of a payout of `amount` (ENCRYPTED)
+///
+/// Debits the employee (`debit_employee`), takes the amount off the
+/// vault total and adds it to `encrypted_total_withdrawn`. Every paying
+/// path goes through here so the three stay in step, and the first one
+/// consumes the first-withdrawal waiver.
+fn record_payout<'info>(
+    employee: &mut EmployeeEntry,
+    vault: &mut MasterVault,
+    amount: Euint128,
+    inco_lightning_program: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+) -> Result<()> {
+    debit_employee(employee, amount.clone(), inco_lightning_program, signer)?;
+    track_vault_total(vault, amount.clone(), false, inco_lightning_program, signer)?;
+
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program.clone(),
+        Operation {
+            signer: signer.clone(),
+        },
+    );
+    employee.encrypted_total_withdrawn = e_add(
+        cpi_ctx,
+        employee.encrypted_total_withdrawn.clone(),
+        amount,
+        0,
+    )?;
+    employee.first_withdrawal_done = true;
+    Ok(())
+}
+
+/// Business balance `drain_business` may pay out (ENCRYPTED)
+///
+/// `encrypted_balance` minus the unpaid accrued reserved by
+/// `close_employee`, floored at zero with `e_ge` + `e_select` because the
+/// subtraction would otherwise wrap.
+fn drainable_balance<'info>(
+    business: &BusinessEntry,
+    inco_lightning_program: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+) -> Result<Euint128> {
+    if !business.has_closed_unpaid {
+        return Ok(business.encrypted_balance.clone());
+    }
+
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program.clone(),
+        Operation {
+            signer: signer.clone(),
+        },
+    );
+    let reserve_fits = e_ge(
+        cpi_ctx,
+        business.encrypted_balance.clone(),
+        business.encrypted_closed_unpaid.clone(),
+        0,
+    )?;
+
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program.clone(),
+        Operation {
+            signer: signer.clone(),
+        },
+    );
+    let remaining = e_sub(
+        cpi_ctx,
+        business.encrypted_balance.clone(),
+        business.encrypted_closed_unpaid.clone(),
+        0,
+    )?;
+
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program.clone(),
+        Operation {
+            signer: signer.clone(),
+        },
+    );
+    let zero = as_euint128(cpi_ctx, 0)?;
+
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program.clone(),
+        Operation {
+            signer: signer.clone(),
+        },
+    );
+    e_select(cpi_ctx, reserve_fits, remaining, zero, 0)
+}
+
+/// Whether `withheld` covers `withholding_bps` of `gross` (ENCRYPTED)
+///
+/// Both shares are client ciphertexts, since Inco transfers take a
+/// ciphertext rather than a handle, so the split cannot be enforced in
+/// plaintext. Instead `withheld * 10_000 >= gross * bps` is evaluated
+/// homomorphically (no division, so no rounding) and the resulting
+/// `Ebool` is emitted for the employer and auditors to decrypt.
+fn withholding_met<'info>(
+    gross: Euint128,
+    withheld: Euint128,
+    withholding_bps: u16,
+    inco_lightning_program: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+) -> Result<Ebool> {
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program.clone(),
+        Operation {
+            signer: signer.clone(),
+        },
+    );
+    let bps = as_euint128(cpi_ctx, withholding_bps as u128)?;
+
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program.clone(),
+        Operation {
+            signer: signer.clone(),
+        },
+    );
+    let denominator = as_euint128(cpi_ctx, 10_000)?;
+
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program.clone(),
+        Operation {
+            signer: signer.clone(),
+        },
+    );
+    let required = e_mul(cpi_ctx, gross, bps, 0)?;
+
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program.clone(),
+        Operation {
+            signer: signer.clone(),
+        },
+    );
+    let scaled = e_mul(cpi_ctx, withheld, denominator, 0)?;
+
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program.clone(),
+        Operation {
+            signer: signer.clone(),
+        },
+    );
+    e_ge(cpi_ctx, scaled, required, 0)
+}
+
+/// `numerator / divisor` (ENCRYPTED), rounded by the business's `RoundingMode`
+///
+/// `e_div` floors, so `accrual::rounding_bias` is added to the numerator
+/// first; `Floor` skips the extra add.
+fn e_div_rounded<'info>(
+    numerator: Euint128,
+    divisor: u128,
+    mode: RoundingMode,
+    inco_lightning_program: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+) -> Result<Euint128> {
+    let bias = accrual::rounding_bias(divisor, mode);
+    let numerator = if bias > 0 {
+        let cpi_ctx = CpiContext::new(
+            inco_lightning_program.clone(),
+            Operation {
+                signer: signer.clone(),
+            },
+        );
+        let encrypted_bias = as_euint128(cpi_ctx, bias)?;
+
+        let cpi_ctx = CpiContext::new(
+            inco_lightning_program.clone(),
+            Operation {
+                signer: signer.clone(),
+            },
+        );
+        e_add(cpi_ctx, numerator, encrypted_bias, 0)?
+    } else {
+        numerator
+    };
+
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program.clone(),
+        Operation {
+            signer: signer.clone(),
+        },
+    );
+    let encrypted_divisor = as_euint128(cpi_ctx, divisor)?;
+
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program.clone(),
+        Operation {
+            signer: signer.clone(),
+        },
+    );
+    e_div(cpi_ctx, numerator, encrypted_divisor, 0)
+}
+
+/// Fold `salary * elapsed` since `last_action` into `encrypted_accrued`
+///
+/// Shared by every path that has to freeze an employee's earnings at
+/// `now` (settle before undelegate, suspension). Leaves `last_action`
+/// at the settled second. With a non-zero `rate_scale` the salary is a
+/// fixed-point rate and the product is divided by `10^rate_scale`; every
+/// division rounds by the business's `rounding_mode`. `conversion` is
+/// the `(multiplier, divisor)` from `accrual_conversion` for
+/// USD-denominated businesses. On the slots basis the window is
+/// `slot - last_action_slot` and both clocks advance to the present.
+/// With a budget set, accrued plus lifetime payouts is clamped to it and
+/// an `AccrualCapped` event carries the encrypted "cap reached" flag.
+/// Fixed-term entries settle no further than `end_timestamp`.
+fn settle_accrual<'info>(
+    employee: &mut EmployeeEntry,
+    business: &BusinessEntry,
+    conversion: Option<(u128, u128)>,
+    inco_lightning_program: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    now: i64,
+    slot: u64,
+) -> Result<()> {
+    cu_checkpoint!("settle_accrual: start");
+
+    // Fixed-term entries stop earning at end_timestamp
+    let term_slots = accrual::term_slot_cap(employee.last_action, employee.end_timestamp);
+    let now = accrual::accrual_until(now, employee.end_timestamp);
+
+    let settled = if employee.accrual_basis == accrual::ACCRUAL_BASIS_SLOTS {
+        accrual::slot_settlement_window(employee.last_action_slot, slot)
+            .map(|(window, settled_slot)| (window.min(term_slots), now, settled_slot))
+    } else {
+        accrual::settlement_window(employee.last_action, now)?
+            .map(|(window, settled_to)| (window, settled_to, slot))
+    };
+
+    match settled {
+        Some((window, settled_to, settled_slot)) => {
+            let (elapsed_multiplier, elapsed_divisor) =
+                accrual::basis_scalars(employee.accrual_basis, window)?;
+
+            let cpi_ctx = CpiContext::new(
+                inco_lightning_program.clone(),
+                Operation {
+                    signer: signer.clone(),
+                },
+            );
+            let encrypted_elapsed = as_euint128(cpi_ctx, elapsed_multiplier)?;
+
+            let cpi_ctx = CpiContext::new(
+                inco_lightning_program.clone(),
+                Operation {
+                    signer: signer.clone(),
+                },
+            );
+            let mut earned = e_mul(
+                cpi_ctx,
+                employee.encrypted_salary.clone(),
+                encrypted_elapsed,
+                0,
+            )?;
+
+            let divisor = accrual::rate_divisor(business.rate_scale)?
+                .checked_mul(elapsed_divisor)
+                .ok_or(BagelError::Overflow)?;
+            if divisor > 1 {
+                earned = e_div_rounded(
+                    earned,
+                    divisor,
+                    business.rounding_mode,
+                    inco_lightning_program,
+                    signer,
+                )?;
+            }
+
+            if let Some((multiplier, divisor)) = conversion {
+                let cpi_ctx = CpiContext::new(
+                    inco_lightning_program.clone(),
+                    Operation {
+                        signer: signer.clone(),
+                    },
+                );
+                let encrypted_multiplier = as_euint128(cpi_ctx, multiplier)?;
+
+                let cpi_ctx = CpiContext::new(
+                    inco_lightning_program.clone(),
+                    Operation {
+                        signer: signer.clone(),
+                    },
+                );
+                earned = e_mul(cpi_ctx, earned, encrypted_multiplier, 0)?;
+
+                earned = e_div_rounded(
+                    earned,
+                    divisor,
+                    business.rounding_mode,
+                    inco_lightning_program,
+                    signer,
+                )?;
+            }
+
+            let cpi_ctx = CpiContext::new(
+                inco_lightning_program.clone(),
+                Operation {
+                    signer: signer.clone(),
+                },
+            );
+            employee.encrypted_accrued = e_add(
+                cpi_ctx,
+                employee.encrypted_accrued.clone(),
+                earned,
+                0,
+            )?;
+
+            cu_checkpoint!("settle_accrual: earnings folded in");
+
+            if employee.has_budget {
+                // The budget caps lifetime earnings: what is still owed
+                // plus everything already paid out
+                let cpi_ctx = CpiContext::new(
+                    inco_lightning_program.clone(),
+                    Operation {
+                        signer: signer.clone(),
+                    },
+                );
+                let lifetime = e_add(
+                    cpi_ctx,
+                    employee.encrypted_accrued.clone(),
+                    employee.encrypted_total_withdrawn.clone(),
+                    0,
+                )?;
+
+                let cpi_ctx = CpiContext::new(
+                    inco_lightning_program.clone(),
+                    Operation {
+                        signer: signer.clone(),
+                    },
+                );
+                let capped = e_ge(
+                    cpi_ctx,
+                    lifetime,
+                    employee.encrypted_budget.clone(),
+                    0,
+                )?;
+
+                // budget - withdrawn, floored at zero once payouts exceed it
+                let cpi_ctx = CpiContext::new(
+                    inco_lightning_program.clone(),
+                    Operation {
+                        signer: signer.clone(),
+                    },
+                );
+                let within_budget = e_ge(
+                    cpi_ctx,
+                    employee.encrypted_budget.clone(),
+                    employee.encrypted_total_withdrawn.clone(),
+                    0,
+                )?;
+
+                let cpi_ctx = CpiContext::new(
+                    inco_lightning_program.clone(),
+                    Operation {
+                        signer: signer.clone(),
+                    },
+                );
+                let headroom = e_sub(
+                    cpi_ctx,
+                    employee.encrypted_budget.clone(),
+                    employee.encrypted_total_withdrawn.clone(),
+                    0,
+                )?;
+
+                let cpi_ctx = CpiContext::new(
+                    inco_lightning_program.clone(),
+                    Operation {
+                        signer: signer.clone(),
+                    },
+                );
+                let zero = as_euint128(cpi_ctx, 0)?;
+
+                let cpi_ctx = CpiContext::new(
+                    inco_lightning_program.clone(),
+                    Operation {
+                        signer: signer.clone(),
+                    },
+                );
+                let remaining = e_select(cpi_ctx, within_budget, headroom, zero, 0)?;
+
+                let cpi_ctx = CpiContext::new(
+                    inco_lightning_program.clone(),
+                    Operation {
+                        signer: signer.clone(),
+                    },
+                );
+                employee.encrypted_accrued = e_select(
+                    cpi_ctx,
+                    capped.clone(),
+                    remaining,
+                    employee.encrypted_accrued.clone(),
+                    0,
+                )?;
+
+                emit!(AccrualCapped {
+                    business_index: business.entry_index,
+                    employee_index: employee.employee_index,
+                    capped,
+                    timestamp: now,
+                });
+            }
+
+            employee.last_action = settled_to;
+            employee.last_action_slot = settled_slot;
+            employee.pending_catchup_seconds = accrual::pending_catchup_seconds(
+                employee.accrual_basis,
+                settled_to,
+                now,
+                settled_slot,
+                slot,
+            );
+            if employee.pending_catchup_seconds > 0 {
+                msg!("   Catch-up pending: {} seconds", employee.pending_catchup_seconds);
+            }
+
+            if employee.accrual_basis == accrual::ACCRUAL_BASIS_SLOTS {
+                msg!("   Settled: {} slots", window);
+            } else {
+                msg!("   Settled: {} seconds", window);
+            }
+        }
+        None => {
+            if employee.last_action > now {
+                msg!("⚠️  last_action ahead of clock - resetting to now");
+                employee.last_action = now;
+            }
+            if employee.last_action_slot > slot {
+                msg!("⚠️  last_action_slot ahead of clock - resetting to now");
+                employee.last_action_slot = slot;
+            }
+            employee.pending_catchup_seconds = 0;
+        }
+    }
+
+    Ok(())
+}
+
+/// Lamports `account` needs on top of `lamports` to stay rent-exempt at `new_len`
+fn rent_top_up(rent: &Rent, lamports: u64, new_len: usize) -> u64 {
+    rent.minimum_balance(new_len).saturating_sub(lamports)
+}
+
+/// Lamports a program-owned account can give away and stay rent-exempt at `data_len`
+fn treasury_spendable(rent: &Rent, lamports: u64, data_len: usize) -> u64 {
+    lamports.saturating_sub(rent.minimum_balance(data_len))
+}
+
+/// Realloc `account` up to `new_len`, zero-initialized, with `payer` covering rent
+///
+/// Returns `false` (and does nothing) when the account is already large enough.
+fn grow_to<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<bool> {
+    if account.data_len() >= new_len {
+        return Ok(false);
+    }
+
+    let top_up = rent_top_up(&Rent::get()?, account.lamports(), new_len);
+    if top_up > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+
+    account.realloc(new_len, true)?;
+    Ok(true)
+}
+
+/// Reject raw account data that does not start with the MasterVault discriminator
+///
+/// `migrate_vault` reads the vault as bytes, so the seeds check alone does
+/// not prove the account holds a MasterVault.
+fn require_vault_discriminator(data: &[u8]) -> Result<()> {
+    require!(
+        data.len() >= 8 && data[..8] == *MasterVault::DISCRIMINATOR,
+        BagelError::InvalidState
+    );
+    Ok(())
+}
+
+/// Rewrite a legacy (pre confidential-mint) MasterVault into the current layout
+///
+/// Returns `None` when `old_data` is already at least `MasterVault::LEN`.
+/// Every legacy field keeps its offset; `provider_flags` gets
+/// `ProviderFlags::DEFAULT_BITS` as on a fresh vault, and
+/// `confidential_mint`, `use_confidential_tokens` and the padding are
+/// zeroed.
+fn migrate_vault_data(old_data: &[u8]) -> Result<Option<Vec<u8>>> {
+    if old_data.len() >= MasterVault::LEN {
+        return Ok(None);
+    }
+    require!(old_data.len() >= 90, BagelError::InvalidState);
+
+    let mut new_data = vec![0u8; MasterVault::LEN];
+
+    // Discriminator (8 bytes) - keep existing (already set)
+    new_data[0..8].copy_from_slice(&old_data[0..8]);
+    // Authority (32 bytes) - copy from old
+    new_data[8..40].copy_from_slice(&old_data[8..40]);
+    // Total balance (8 bytes) - copy from old
+    new_data[40..48].copy_from_slice(&old_data[40..48]);
+    // Encrypted business count (16 bytes) - copy from old
+    new_data[48..64].copy_from_slice(&old_data[48..64]);
+    // Encrypted employee count (16 bytes) - copy from old
+    new_data[64..80].copy_from_slice(&old_data[64..80]);
+    // Next business index (8 bytes) - copy from old
+    new_data[80..88].copy_from_slice(&old_data[80..88]);
+    // Is active (1 byte) - copy from old
+    new_data[88] = old_data[88];
+    // Bump (1 byte) - copy from old
+    new_data[89] = old_data[89];
+    // Confidential mint (32 bytes) and use confidential tokens (1 byte)
+    // stay zeroed
+    // Provider flags (1 byte) - same defaults as initialize_vault
+    new_data[123] = ProviderFlags::DEFAULT_BITS;
+    // Min delegation duration (8 bytes) and every later field stay zeroed
+
+    Ok(Some(new_data))
+}
+
+// ============================================================
+// Account Contexts
+// ============================================================
+
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = MasterVault::LEN,
+        seeds = [MASTER_VAULT_SEED],
+        bump
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeGlobalState<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = GlobalState::LEN,
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TransferAdmin<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub pending_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterBusiness<'info> {
+    #[account(mut)]
+    pub employer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        init,
+        payer = employer,
+        space = BusinessEntry::LEN,
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &master_vault.next_business_index.to_le_bytes()],
+        bump
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    /// Business label (optional, only when a label is given)
+    #[account(
+        init,
+        payer = employer,
+        space = BusinessLabel::LEN,
+        seeds = [BUSINESS_LABEL_SEED, business_entry.key().as_ref()],
+        bump
+    )]
+    pub business_label: Option<Account<'info, BusinessLabel>>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetLabel<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = BusinessLabel::LEN,
+        seeds = [BUSINESS_LABEL_SEED, business_entry.key().as_ref()],
+        bump
+    )]
+    pub business_label: Account<'info, BusinessLabel>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRoundingMode<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+}
+
+#[derive(Accounts)]
+pub struct SetRateScale<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+}
+
+#[derive(Accounts)]
+pub struct SetRentFromTreasury<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeBusiness<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+}
+
+#[derive(Accounts)]
+pub struct SetSoftCap<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+}
+
+#[derive(Accounts)]
+pub struct SetSalaryDenom<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = PriceFeedConfig::LEN,
+        seeds = [PRICE_FEED_SEED, business_entry.key().as_ref()],
+        bump
+    )]
+    pub price_feed_config: Account<'info, PriceFeedConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetSalaryBand<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = SalaryBand::LEN,
+        seeds = [SALARY_BAND_SEED, business_entry.key().as_ref()],
+        bump
+    )]
+    pub salary_band: Account<'info, SalaryBand>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetWithdrawalDelay<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+}
+
+#[derive(Accounts)]
+#[instruction(encrypted_amount: Vec<u8>, idempotency_key: Option<[u8; 16]>)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    /// CHECK: Inco Confidential Token program (optional, for confidential transfers)
+    /// When vault.use_confidential_tokens is true, this is used for encrypted transfers
+    #[account(address = Pubkey::try_from(crate::constants::INCO_TOKEN_PROGRAM_ID).unwrap() @ BagelError::Unauthorized)]
+    pub inco_token_program: Option<AccountInfo<'info>>,
+
+    /// CHECK: Depositor confidential token account (optional)
+    /// Used when confidential tokens are enabled
+    pub depositor_token_account: Option<AccountInfo<'info>>,
+
+    /// CHECK: Master vault confidential token account (optional)
+    /// Used when confidential tokens are enabled, must be the recorded `token_account`
+    #[account(mut, address = master_vault.token_account @ BagelError::InvalidState)]
+    pub master_vault_token_account: Option<AccountInfo<'info>>,
+
+    /// Deposit receipt (optional, opt-in)
+    #[account(
+        init,
+        payer = depositor,
+        space = DepositReceipt::LEN,
+        seeds = [DEPOSIT_RECEIPT_SEED, business_entry.key().as_ref(), &business_entry.next_receipt_index.to_le_bytes()],
+        bump
+    )]
+    pub deposit_receipt: Option<Account<'info, DepositReceipt>>,
+
+    /// Idempotency guard (optional, required with idempotency_key)
+    #[account(
+        init,
+        payer = depositor,
+        space = DepositGuard::LEN,
+        seeds = [DEPOSIT_GUARD_SEED, business_entry.key().as_ref(), &idempotency_key.unwrap_or_default()],
+        bump
+    )]
+    pub deposit_guard: Option<Account<'info, DepositGuard>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositMulti<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    /// CHECK: Inco Confidential Token program
+    #[account(address = Pubkey::try_from(crate::constants::INCO_TOKEN_PROGRAM_ID).unwrap() @ BagelError::Unauthorized)]
+    pub inco_token_program: AccountInfo<'info>,
+
+    /// CHECK: Depositor confidential token account
+    #[account(mut)]
+    pub depositor_token_account: AccountInfo<'info>,
+
+    /// CHECK: Master vault confidential token account (the recorded `token_account`)
+    #[account(mut, address = master_vault.token_account @ BagelError::InvalidState)]
+    pub master_vault_token_account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProveBalanceAbove<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevealEmployeeCount<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    /// CHECK: Inco allowance PDA for (employee-count handle, authority);
+    /// address verified in handler, created by Inco if missing
+    #[account(mut)]
+    pub allowance_account: UncheckedAccount<'info>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct StreamRunway<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseDepositGuard<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        close = depositor,
+        has_one = depositor,
+        seeds = [DEPOSIT_GUARD_SEED, deposit_guard.business_entry.as_ref(), &deposit_guard.idempotency_key],
+        bump = deposit_guard.bump,
+    )]
+    pub deposit_guard: Account<'info, DepositGuard>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVault<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Receives the vault's lamports; must match `refund_to`
+    /// (verified in handler)
+    #[account(mut)]
+    pub refund_account: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+        close = refund_account, // Close account and send lamports to refund_account
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+}
+
+#[derive(Accounts)]
+pub struct CloseEmployee<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    /// CHECK: EmployeeEntry PDA - left unchecked so a delegated entry
+    /// reports StillDelegated instead of an owner mismatch; verified in handler
+    #[account(mut)]
+    pub employee_entry: UncheckedAccount<'info>,
+
+    /// CHECK: The entry's PendingWithdrawal PDA, which must not exist
+    #[account(
+        seeds = [PENDING_WITHDRAWAL_SEED, employee_entry.key().as_ref()],
+        bump,
+    )]
+    pub pending_withdrawal: UncheckedAccount<'info>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DrainBusiness<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    /// CHECK: Inco Confidential Token program
+    #[account(address = Pubkey::try_from(crate::constants::INCO_TOKEN_PROGRAM_ID).unwrap() @ BagelError::Unauthorized)]
+    pub inco_token_program: AccountInfo<'info>,
+
+    /// CHECK: Master vault confidential token account (the recorded `token_account`)
+    #[account(mut, address = master_vault.token_account @ BagelError::InvalidState)]
+    pub master_vault_token_account: AccountInfo<'info>,
+
+    /// CHECK: Employer confidential token account (refund commitment and
+    /// mint verified in handler)
+    #[account(mut)]
+    pub employer_token_account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GrowAccount<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    /// CHECK: Account to grow - owner and discriminator verified in handler
+    /// (may be the master vault itself)
+    #[account(mut)]
+    pub target: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Master vault PDA - we'll verify the bump from old data
+    /// CHECK: Master vault PDA - verified manually in instruction
+    #[account(mut)]
+    pub master_vault: AccountInfo<'info>, // Use AccountInfo to avoid deserialization
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EmployeeContribute<'info> {
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    /// CHECK: Inco Confidential Token program
+    #[account(address = Pubkey::try_from(crate::constants::INCO_TOKEN_PROGRAM_ID).unwrap() @ BagelError::Unauthorized)]
+    pub inco_token_program: AccountInfo<'info>,
+
+    /// CHECK: Contributor confidential token account
+    #[account(mut)]
+    pub contributor_token_account: AccountInfo<'info>,
+
+    /// CHECK: Master vault confidential token account (the recorded `token_account`)
+    #[account(mut, address = master_vault.token_account @ BagelError::InvalidState)]
+    pub master_vault_token_account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ListActive<'info> {
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+}
+
+#[derive(Accounts)]
+pub struct ExportBusiness<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+}
+
+#[derive(Accounts)]
+#[instruction(entry_index: u64)]
+pub struct ImportBusiness<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = BusinessEntry::LEN,
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &entry_index.to_le_bytes()],
+        bump
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    /// CHECK: Instructions sysvar, read for the Ed25519 verification
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ImportEmployees<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    /// CHECK: Instructions sysvar, read for the Ed25519 verification
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddEmployee<'info> {
+    #[account(mut)]
+    pub employer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        init,
+        payer = employer,
+        space = EmployeeEntry::LEN,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &business_entry.next_employee_index.to_le_bytes()],
+        bump
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    /// Salary band (required once the business has one)
+    #[account(
+        seeds = [SALARY_BAND_SEED, business_entry.key().as_ref()],
+        bump = salary_band.bump,
+    )]
+    pub salary_band: Option<Account<'info, SalaryBand>>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(encrypted_amount: Vec<u8>, use_shadowwire: bool, shadowwire_proof: Option<ShadowWireProof>, reference: Option<[u8; 16]>)]
+pub struct RequestWithdrawal<'info> {
+    #[account(mut)]
+    pub withdrawer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    /// CHECK: Inco Confidential Token program (optional, for confidential transfers)
+    /// When vault.use_confidential_tokens is true, this is used for encrypted transfers
+    #[account(address = Pubkey::try_from(crate::constants::INCO_TOKEN_PROGRAM_ID).unwrap() @ BagelError::Unauthorized)]
+    pub inco_token_program: Option<AccountInfo<'info>>,
+
+    /// CHECK: Master vault confidential token account (optional)
+    /// Used when confidential tokens are enabled, must be the recorded `token_account`
+    #[account(mut, address = master_vault.token_account @ BagelError::InvalidState)]
+    pub master_vault_token_account: Option<AccountInfo<'info>>,
+
+    /// CHECK: Employee confidential token account (optional)
+    /// Used when confidential tokens are enabled
+    pub employee_token_account: Option<AccountInfo<'info>>,
+
+    /// Tax account config (required when the employee has withholding)
+    #[account(
+        seeds = [WITHHOLDING_SEED, business_entry.key().as_ref()],
+        bump = withholding_config.bump,
+    )]
+    pub withholding_config: Option<Account<'info, WithholdingConfig>>,
+
+    /// CHECK: Tax Inco Token account; must match `withholding_config`
+    pub tax_token_account: Option<AccountInfo<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AutoPayment<'info> {
+    /// TEE validator identity - only the rollup may stream payouts
+    #[account(
+        mut,
+        address = Pubkey::try_from(TEE_VALIDATOR).unwrap() @ BagelError::Unauthorized
+    )]
+    pub tee_signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    /// CHECK: Inco Confidential Token program
+    #[account(address = Pubkey::try_from(crate::constants::INCO_TOKEN_PROGRAM_ID).unwrap() @ BagelError::Unauthorized)]
+    pub inco_token_program: AccountInfo<'info>,
+
+    /// CHECK: Master vault confidential token account (the recorded `token_account`)
+    #[account(mut, address = master_vault.token_account @ BagelError::InvalidState)]
+    pub master_vault_token_account: AccountInfo<'info>,
+
+    /// CHECK: Employee confidential token account
+    #[account(mut)]
+    pub employee_token_account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSplit<'info> {
+    #[account(mut)]
+    pub withdrawer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    #[account(
+        seeds = [SPLIT_DESTINATIONS_SEED, employee_entry.key().as_ref()],
+        bump = split_destinations.bump,
+    )]
+    pub split_destinations: Account<'info, SplitDestinations>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    /// CHECK: Inco Confidential Token program
+    #[account(address = Pubkey::try_from(crate::constants::INCO_TOKEN_PROGRAM_ID).unwrap() @ BagelError::Unauthorized)]
+    pub inco_token_program: AccountInfo<'info>,
+
+    /// CHECK: Master vault confidential token account (the recorded `token_account`)
+    #[account(mut, address = master_vault.token_account @ BagelError::InvalidState)]
+    pub master_vault_token_account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetSplitDestinations<'info> {
+    #[account(mut)]
+    pub withdrawer: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    #[account(
+        init_if_needed,
+        payer = withdrawer,
+        space = SplitDestinations::LEN,
+        seeds = [SPLIT_DESTINATIONS_SEED, employee_entry.key().as_ref()],
+        bump
+    )]
+    pub split_destinations: Account<'info, SplitDestinations>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitiateWithdrawal<'info> {
+    #[account(mut)]
+    pub withdrawer: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    #[account(
+        init,
+        payer = withdrawer,
+        space = PendingWithdrawal::LEN,
+        seeds = [PENDING_WITHDRAWAL_SEED, employee_entry.key().as_ref()],
+        bump
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeWithdrawal<'info> {
+    #[account(mut)]
+    pub withdrawer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    #[account(
+        mut,
+        has_one = withdrawer,
+        has_one = employee_entry,
+        seeds = [PENDING_WITHDRAWAL_SEED, employee_entry.key().as_ref()],
+        bump = pending_withdrawal.bump,
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    /// CHECK: Inco Confidential Token program
+    #[account(address = Pubkey::try_from(crate::constants::INCO_TOKEN_PROGRAM_ID).unwrap() @ BagelError::Unauthorized)]
+    pub inco_token_program: AccountInfo<'info>,
+
+    /// CHECK: Master vault confidential token account (the recorded `token_account`)
+    #[account(mut, address = master_vault.token_account @ BagelError::InvalidState)]
+    pub master_vault_token_account: AccountInfo<'info>,
+
+    /// CHECK: Employee confidential token account
+    #[account(mut)]
+    pub employee_token_account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelWithdrawal<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    /// CHECK: Original withdrawer, receives the rent back
+    #[account(mut, address = pending_withdrawal.withdrawer)]
+    pub withdrawer: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = withdrawer,
+        has_one = employee_entry,
+        seeds = [PENDING_WITHDRAWAL_SEED, employee_entry.key().as_ref()],
+        bump = pending_withdrawal.bump,
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePending<'info> {
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    /// CHECK: Original withdrawer, receives the rent back
+    #[account(mut, address = pending_withdrawal.withdrawer)]
+    pub withdrawer: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = withdrawer,
+        has_one = employee_entry,
+        seeds = [PENDING_WITHDRAWAL_SEED, employee_entry.key().as_ref()],
+        bump = pending_withdrawal.bump,
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+}
+
+#[derive(Accounts)]
+pub struct Config<'info> {
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawEligibility<'info> {
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+}
+
+#[derive(Accounts)]
+pub struct AccruedSnapshot<'info> {
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+}
+
+#[derive(Accounts)]
+pub struct Healthcheck<'info> {
+    pub payer: Signer<'info>,
+
+    /// CHECK: Inco Lightning program - id and executable checked in handler
+    pub inco_lightning_program: UncheckedAccount<'info>,
+
+    /// CHECK: MagicBlock delegation program - id and executable checked in handler
+    pub delegation_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureConfidentialMint<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    /// CHECK: Vault Inco Token account for `mint` (validated in instruction)
+    pub vault_token_account: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetConfidentialTokens<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+}
+
+#[derive(Accounts)]
+pub struct SetProviderFlags<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+}
+
+#[derive(Accounts)]
+pub struct SetMinDelegationDuration<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+}
+
+#[derive(Accounts)]
+pub struct SetTokenDecimals<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+}
+
+#[derive(Accounts)]
+pub struct RotateConfidentialMint<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    /// CHECK: Current vault Inco Token account, must be `master_vault.token_account`
+    /// Required when a confidential mint is already configured
+    pub old_vault_token_account: Option<AccountInfo<'info>>,
+
+    /// CHECK: New vault Inco Token account (validated in instruction)
+    pub new_vault_token_account: AccountInfo<'info>,
+}
+
+#[delegate]
+#[derive(Accounts)]
+pub struct DelegateToTee<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        del,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    /// CHECK: Permission account PDA for EmployeeEntry
+    /// Seeds: ["permission", employee_entry.key()]
+    #[account(mut, del)]
+    pub permission: UncheckedAccount<'info>,
+
+    /// CHECK: Permission Program (ACL)
+    #[account(address = Pubkey::try_from(crate::constants::MAGICBLOCK_PERMISSION_PROGRAM).unwrap())]
+    pub permission_program: UncheckedAccount<'info>,
+
+    /// CHECK: Optional validator
+    pub validator: Option<AccountInfo<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitTeeAccrued<'info> {
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    /// CHECK: Instructions sysvar, read for the Ed25519 verification
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CommitFromTee<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    /// CHECK: Permission account PDA for EmployeeEntry
+    /// Seeds: ["permission", employee_entry.key()]
+    #[account(mut)]
+    pub permission: UncheckedAccount<'info>,
+
+    /// CHECK: MagicBlock context
+    #[account(mut)]
+    pub magic_context: AccountInfo<'info>,
+
+    /// CHECK: MagicBlock program
+    pub magic_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitFromTeeBatch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    /// CHECK: MagicBlock context
+    #[account(mut)]
+    pub magic_context: AccountInfo<'info>,
+
+    /// CHECK: MagicBlock program
+    pub magic_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ForceRecoverDelegation<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    /// CHECK: EmployeeEntry PDA - left unchecked so a half-delegated entry
+    /// can still be recovered; verified against employee_index in handler
+    #[account(mut)]
+    pub employee_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Permission account PDA for EmployeeEntry
+    /// Seeds: ["permission", employee_entry.key()]
+    #[account(mut)]
+    pub permission: UncheckedAccount<'info>,
+
+    /// CHECK: MagicBlock context
+    #[account(mut)]
+    pub magic_context: AccountInfo<'info>,
+
+    /// CHECK: MagicBlock program
+    pub magic_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleOnUndelegate<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    /// CHECK: Permission account PDA for EmployeeEntry
+    /// Seeds: ["permission", employee_entry.key()]
+    #[account(mut)]
+    pub permission: UncheckedAccount<'info>,
+
+    /// Oracle config (required when salary_denom is Usd)
+    #[account(
+        seeds = [PRICE_FEED_SEED, business_entry.key().as_ref()],
+        bump = price_feed_config.bump,
+    )]
+    pub price_feed_config: Option<Account<'info, PriceFeedConfig>>,
+
+    /// CHECK: Pyth PriceUpdateV2 account; owner, feed id, staleness and
+    /// confidence are validated in the handler
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    /// CHECK: MagicBlock context
+    #[account(mut)]
+    pub magic_context: AccountInfo<'info>,
+
+    /// CHECK: MagicBlock program
+    pub magic_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SuspendEmployee<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    /// CHECK: Permission account PDA for EmployeeEntry
+    /// Seeds: ["permission", employee_entry.key()]
+    #[account(mut)]
+    pub permission: UncheckedAccount<'info>,
+
+    /// Oracle config (required when salary_denom is Usd)
+    #[account(
+        seeds = [PRICE_FEED_SEED, business_entry.key().as_ref()],
+        bump = price_feed_config.bump,
+    )]
+    pub price_feed_config: Option<Account<'info, PriceFeedConfig>>,
+
+    /// CHECK: Pyth PriceUpdateV2 account; owner, feed id, staleness and
+    /// confidence are validated in the handler
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    /// CHECK: MagicBlock context
+    #[account(mut)]
+    pub magic_context: AccountInfo<'info>,
+
+    /// CHECK: MagicBlock program
+    pub magic_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResumeEmployee<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+}
+
+#[derive(Accounts)]
+pub struct SetAccrualBasis<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    /// Oracle config (required when salary_denom is Usd)
+    #[account(
+        seeds = [PRICE_FEED_SEED, business_entry.key().as_ref()],
+        bump = price_feed_config.bump,
+    )]
+    pub price_feed_config: Option<Account<'info, PriceFeedConfig>>,
+
+    /// CHECK: Pyth PriceUpdateV2 account; owner, feed id, staleness and
+    /// confidence are validated in the handler
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundEmployee<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetWithholding<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = WithholdingConfig::LEN,
+        seeds = [WITHHOLDING_SEED, business_entry.key().as_ref()],
+        bump
+    )]
+    pub withholding_config: Account<'info, WithholdingConfig>,
+
+    /// CHECK: Inco Token account receiving withheld shares; owner and
+    /// mint are validated in the handler
+    pub tax_token_account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendTerm<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+}
+
+#[derive(Accounts)]
+pub struct SetImmediateFirstWithdrawal<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+}
+
+#[derive(Accounts)]
+pub struct SetWithdrawAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+}
+
+#[derive(Accounts)]
+pub struct SetEmployeeBudget<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
+        bump = business_entry.bump,
+    )]
+    pub business_entry: Account<'info, BusinessEntry>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
+        bump = employee_entry.bump,
+    )]
+    pub employee_entry: Account<'info, EmployeeEntry>,
+
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[delegate]
+#[derive(Accounts)]
+pub struct DelegateVaultToTee<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Master vault PDA, delegated with seeds ["master_vault"]
+    #[account(
+        mut,
+        del,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    /// CHECK: Optional validator
+    pub validator: Option<AccountInfo<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitVaultFromTee<'info> {
+    #[account(mut)]
     pub payer: Signer<'info>,
 
-    #[account(
-        seeds = [MASTER_VAULT_SEED],
-        bump = master_vault.bump,
-    )]
-    pub master_vault: Account<'info, MasterVault>,
+    #[account(
+        mut,
+        seeds = [MASTER_VAULT_SEED],
+        bump = master_vault.bump,
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    /// CHECK: MagicBlock context
+    #[account(mut)]
+    pub magic_context: AccountInfo<'info>,
+
+    /// CHECK: MagicBlock program
+    pub magic_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeUserTokenAccount<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: The mint for this token account
+    pub mint: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = UserTokenAccount::LEN,
+        seeds = [USER_TOKEN_SEED, owner.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub user_token_account: Account<'info, UserTokenAccount>,
+
+    /// CHECK: Inco Lightning program for FHE operations
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetUserTokenPda<'info> {
+    /// CHECK: The owner to look up
+    pub owner: AccountInfo<'info>,
+
+    /// CHECK: The mint to look up
+    pub mint: AccountInfo<'info>,
+
+    #[account(
+        seeds = [USER_TOKEN_SEED, owner.key().as_ref(), mint.key().as_ref()],
+        bump = user_token_account.bump,
+    )]
+    pub user_token_account: Account<'info, UserTokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SetIncoTokenAccount<'info> {
+    /// Authority setting the Inco Token account (owner or trusted signer)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: The owner of the user token account
+    pub owner: AccountInfo<'info>,
+
+    /// CHECK: The mint for the token account
+    pub mint: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [USER_TOKEN_SEED, owner.key().as_ref(), mint.key().as_ref()],
+        bump = user_token_account.bump,
+    )]
+    pub user_token_account: Account<'info, UserTokenAccount>,
+}
+
+// ============================================================
+// Account Structures (Maximum Privacy)
+// ============================================================
+
+/// Master Vault - Single account holding all funds
+/// Observer sees ONLY this account's total balance
+#[account]
+pub struct MasterVault {
+    /// Program authority
+    pub authority: Pubkey,
+    
+    /// Total balance (PUBLIC - unavoidable on Solana)
+    pub total_balance: u64,
+    
+    /// ENCRYPTED business count (observer cannot see)
+    pub encrypted_business_count: Euint128,
+    
+    /// ENCRYPTED employee count (observer cannot see)
+    pub encrypted_employee_count: Euint128,
+    
+    /// Next business index (for PDA derivation)
+    pub next_business_index: u64,
+    
+    /// Is active
+    pub is_active: bool,
+    
+    /// Bump seed
+    pub bump: u8,
+    
+    /// Confidential token mint address (for confidential transfers)
+    pub confidential_mint: Pubkey,
+    
+    /// Whether to use confidential tokens for transfers
+    pub use_confidential_tokens: bool,
+
+    /// Privacy providers this deployment runs for real (see `set_provider_flags`)
+    pub provider_flags: ProviderFlags,
+
+    /// Seconds an employee entry must stay delegated before it can be
+    /// committed back (see `set_min_delegation_duration`, 0 = none)
+    pub min_delegation_duration: i64,
+
+    /// Decimals of the confidential token, sizing the salary ceiling
+    /// (see `set_token_decimals`, `None` = no ceiling)
+    pub token_decimals: Option<u8>,
+
+    /// ENCRYPTED sum of confidential deposits minus payouts
+    /// (decryptable by authorized parties, see `track_vault_total`)
+    pub encrypted_total_balance: Euint128,
+
+    /// Whether `encrypted_total_balance` has covered every flow since
+    /// `initialize_vault` (false on migrated vaults)
+    pub tracks_encrypted_total: bool,
+
+    /// The vault's Inco Token account for `confidential_mint`, recorded by
+    /// `configure_confidential_mint` / `rotate_confidential_mint` (zeroed
+    /// on vaults configured before this field until they record it)
+    pub token_account: Pubkey,
+}
+
+impl MasterVault {
+    pub const LEN: usize = 8 +  // discriminator
+        32 +                     // authority
+        8 +                      // total_balance
+        16 +                     // encrypted_business_count
+        16 +                     // encrypted_employee_count
+        8 +                      // next_business_index
+        1 +                      // is_active
+        1 +                      // bump
+        32 +                     // confidential_mint
+        1 +                      // use_confidential_tokens flag
+        1 +                      // provider_flags
+        8 +                      // min_delegation_duration
+        2 +                      // token_decimals (Option<u8>)
+        16 +                     // encrypted_total_balance
+        1 +                      // tracks_encrypted_total
+        32 +                     // token_account
+        3;                       // padding
+}
+
+/// Global State - Protocol-wide admin controls (only 1)
+/// Seeds: ["global_state"]
+#[account]
+pub struct GlobalState {
+    /// Protocol admin (can pause and manage global settings)
+    pub admin: Pubkey,
+
+    /// Admin nominated via transfer_admin, awaiting accept_admin
+    pub pending_admin: Pubkey,
+
+    /// Is the protocol paused
+    pub is_paused: bool,
+
+    /// Total payrolls created (PUBLIC aggregate)
+    pub total_payrolls: u64,
+
+    /// Total withdrawals processed (PUBLIC aggregate)
+    pub total_withdrawals: u64,
+
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl GlobalState {
+    pub const LEN: usize = 8 +  // discriminator
+        32 +                     // admin
+        32 +                     // pending_admin
+        1 +                      // is_paused
+        8 +                      // total_payrolls
+        8 +                      // total_withdrawals
+        1 +                      // bump
+        32;                      // padding
+}
+
+/// Business Entry - INDEX-BASED PDA (no employer pubkey in seeds)
+/// Seeds: ["entry", master_vault, entry_index]
+#[account]
+pub struct BusinessEntry {
+    /// Reference to master vault
+    pub master_vault: Pubkey,
+    
+    /// Entry index (used in PDA, NOT employer pubkey)
+    pub entry_index: u64,
+    
+    /// ENCRYPTED employer ID (hash of pubkey)
+    pub encrypted_employer_id: Euint128,
+    
+    /// ENCRYPTED balance
+    pub encrypted_balance: Euint128,
+    
+    /// ENCRYPTED employee count
+    pub encrypted_employee_count: Euint128,
+    
+    /// Next employee index
+    pub next_employee_index: u64,
+    
+    /// Is active
+    pub is_active: bool,
+    
+    /// Bump seed
+    pub bump: u8,
+    
+    /// Next deposit receipt index
+    pub next_receipt_index: u64,
+    
+    /// Rounding mode for accrual divisions (default Floor)
+    pub rounding_mode: RoundingMode,
+    
+    /// Cooling-off delay for two-phase withdrawals, in seconds
+    pub withdrawal_delay: i64,
+    
+    /// Whether a SalaryBand PDA must be enforced in add_employee
+    pub has_salary_band: bool,
+    
+    /// Fixed-point decimals of salary rates (0 = whole lamports/sec)
+    pub rate_scale: u8,
+    
+    /// Currency salary rates are denominated in (default Native)
+    pub salary_denom: SalaryDenom,
+    
+    /// Employee count that triggers EmployeeSoftCapReached (0 = none)
+    pub soft_cap: u64,
+    
+    /// Whether the business has received at least one deposit
+    pub is_funded: bool,
+
+    /// Reimburse `add_employee` rent from this account's lamports
+    pub rent_from_treasury: bool,
+
+    /// Frozen by the protocol authority (see `freeze_business`)
+    pub is_frozen: bool,
+
+    /// Inco encryption context (key id) for this business's ciphertexts,
+    /// set at registration (entries created before this field need
+    /// `grow_account`)
+    pub encryption_context: Pubkey,
+
+    /// Next employee index `import_employees` expects (0 = not importing;
+    /// entries created before this field need `grow_account`)
+    pub import_cursor: u64,
+
+    /// `refund_commitment` of the account `drain_business` pays out to
+    /// (zeros = none; entries created before this field need `grow_account`)
+    pub refund_commitment: [u8; 32],
+
+    /// Unpaid accrued of employees removed by `close_employee`, held back
+    /// from `drain_business` (ENCRYPTED; entries created before these
+    /// fields need `grow_account`)
+    pub encrypted_closed_unpaid: Euint128,
+    pub has_closed_unpaid: bool,
+}
+
+impl BusinessEntry {
+    pub const LEN: usize = 8 +  // discriminator
+        32 +                     // master_vault
+        8 +                      // entry_index
+        16 +                     // encrypted_employer_id
+        16 +                     // encrypted_balance
+        16 +                     // encrypted_employee_count
+        8 +                      // next_employee_index
+        1 +                      // is_active
+        1 +                      // bump
+        8 +                      // next_receipt_index
+        1 +                      // rounding_mode
+        8 +                      // withdrawal_delay
+        1 +                      // has_salary_band
+        1 +                      // rate_scale
+        1 +                      // salary_denom
+        8 +                      // soft_cap
+        1 +                      // is_funded
+        1 +                      // rent_from_treasury
+        32 +                     // encryption_context
+        1 +                      // is_frozen
+        8 +                      // import_cursor
+        32 +                     // refund_commitment
+        16 +                     // encrypted_closed_unpaid
+        1 +                      // has_closed_unpaid
+        1;                       // padding
+
+    /// Minimum seconds between an employee's manual withdrawals
+    ///
+    /// Every business uses the global `MIN_WITHDRAW_INTERVAL` today;
+    /// withdrawal paths and `withdraw_eligibility` read it through here
+    /// so they stay in step if it becomes configurable per business.
+    pub fn withdraw_interval(&self) -> i64 {
+        MIN_WITHDRAW_INTERVAL
+    }
+}
+
+/// Employee Entry - INDEX-BASED PDA (no employee pubkey in seeds)
+/// Seeds: ["employee", business_entry, employee_index]
+#[account]
+pub struct EmployeeEntry {
+    /// Reference to business entry
+    pub business_entry: Pubkey,
+    
+    /// Employee index (used in PDA, NOT employee pubkey)
+    pub employee_index: u64,
+    
+    /// ENCRYPTED employee ID (hash of pubkey)
+    pub encrypted_employee_id: Euint128,
+    
+    /// ENCRYPTED salary
+    pub encrypted_salary: Euint128,
+    
+    /// ENCRYPTED accrued amount
+    pub encrypted_accrued: Euint128,
+    
+    /// Last action timestamp
+    pub last_action: i64,
+    
+    /// Is active
+    pub is_active: bool,
+    
+    /// Bump seed
+    pub bump: u8,
+    
+    /// ENCRYPTED lifetime payouts (for year-end reporting)
+    /// Carved from padding, after existing fields to keep their offsets
+    pub encrypted_total_withdrawn: Euint128,
+
+    /// Suspension start (0 = not suspended)
+    pub suspended_at: i64,
+
+    /// Slot of `last_action`, the clock for the slots accrual basis
+    /// (entries created before this field need `grow_account`)
+    pub last_action_slot: u64,
+
+    /// Accrual clock: 0 = seconds, 1 = slots (see `set_accrual_basis`)
+    pub accrual_basis: u8,
+
+    /// ENCRYPTED cap on `encrypted_accrued` (see `set_employee_budget`)
+    pub encrypted_budget: Euint128,
+
+    /// Whether `encrypted_budget` is enforced at settlement
+    pub has_budget: bool,
+
+    /// ENCRYPTED amount earmarked by `fund_employee`, paid out before accrued
+    pub encrypted_prefunded: Euint128,
+
+    /// Whether `encrypted_prefunded` holds a handle (set on first funding)
+    pub has_prefunded: bool,
+
+    /// Elapsed time beyond the accrual clamp not yet settled (read-only;
+    /// written by settlement, drained by later settlements)
+    pub pending_catchup_seconds: u64,
+
+    /// Share of each payout withheld to the business's tax account, in
+    /// basis points (0 = no withholding, see `set_withholding`)
+    pub withholding_bps: u16,
+
+    /// When the entry was last delegated to the TEE (entries created
+    /// before this field need `grow_account`)
+    pub delegated_at: i64,
+
+    /// Accrued handle (zero-padded) and time of the last commit to L1
+    /// (see `accrued_snapshot`; entries created before these fields need
+    /// `grow_account`)
+    pub last_snapshot_accrued: [u8; 32],
+    pub last_snapshot_time: i64,
+
+    /// End of a fixed term; accrual stops here (0 = open-ended, see
+    /// `extend_term`; entries created before this field need `grow_account`)
+    pub end_timestamp: i64,
+
+    /// First manual withdrawal may skip the withdraw interval (see
+    /// `set_immediate_first_withdrawal`)
+    pub allow_immediate_first_withdrawal: bool,
+
+    /// Set by the first manual withdrawal; ends the waiver above
+    /// (entries created before these fields need `grow_account`)
+    pub first_withdrawal_done: bool,
+
+    /// Wallet allowed to initiate two-phase withdrawals (see
+    /// `set_withdraw_authority`, zero = none; entries created before this
+    /// field need `grow_account`)
+    pub withdraw_authority: Pubkey,
+
+    /// Time of the last manual or auto payout, the clock the withdraw
+    /// interval runs on; payouts leave `last_action` to accrual (entries
+    /// created before this field need `grow_account` and read 0)
+    pub last_withdrawal_at: i64,
+}
+
+impl EmployeeEntry {
+    pub const LEN: usize = 8 +  // discriminator
+        32 +                     // business_entry
+        8 +                      // employee_index
+        16 +                     // encrypted_employee_id
+        16 +                     // encrypted_salary
+        16 +                     // encrypted_accrued
+        8 +                      // last_action
+        1 +                      // is_active
+        1 +                      // bump
+        16 +                     // encrypted_total_withdrawn
+        8 +                      // suspended_at
+        8 +                      // last_action_slot
+        1 +                      // accrual_basis
+        16 +                     // encrypted_budget
+        1 +                      // has_budget
+        16 +                     // encrypted_prefunded
+        1 +                      // has_prefunded
+        8 +                      // pending_catchup_seconds
+        2 +                      // withholding_bps
+        8 +                      // delegated_at
+        32 +                     // last_snapshot_accrued
+        8 +                      // last_snapshot_time
+        8 +                      // end_timestamp
+        1 +                      // allow_immediate_first_withdrawal
+        1 +                      // first_withdrawal_done
+        32 +                     // withdraw_authority
+        8 +                      // last_withdrawal_at
+        5;                       // padding
+}
+
+/// Deposit Receipt - Opt-in record of a single deposit
+/// Seeds: ["receipt", business_entry, receipt_index]
+/// Amount stays encrypted - only authorized parties can decrypt
+#[account]
+pub struct DepositReceipt {
+    /// Business entry the deposit was credited to
+    pub business_entry: Pubkey,
+
+    /// Receipt index (used in PDA)
+    pub receipt_index: u64,
+
+    /// Depositor wallet
+    pub depositor: Pubkey,
+
+    /// ENCRYPTED deposit amount
+    pub encrypted_amount: Euint128,
+
+    /// Deposit timestamp
+    pub timestamp: i64,
+
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl DepositReceipt {
+    pub const LEN: usize = 8 +  // discriminator
+        32 +                     // business_entry
+        8 +                      // receipt_index
+        32 +                     // depositor
+        16 +                     // encrypted_amount
+        8 +                      // timestamp
+        1 +                      // bump
+        16;                      // padding
+}
+
+/// Deposit Guard - Idempotency marker for a single deposit
+/// Seeds: ["deposit_guard", business_entry, idempotency_key]
+/// Exists only to make a replayed deposit fail; closed after confirmation
+#[account]
+pub struct DepositGuard {
+    /// Business entry the deposit was credited to
+    pub business_entry: Pubkey,
+
+    /// Depositor wallet (receives rent on close)
+    pub depositor: Pubkey,
+
+    /// Client-supplied idempotency key
+    pub idempotency_key: [u8; 16],
+
+    /// Creation timestamp
+    pub created_at: i64,
+
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl DepositGuard {
+    pub const LEN: usize = 8 +  // discriminator
+        32 +                     // business_entry
+        32 +                     // depositor
+        16 +                     // idempotency_key
+        8 +                      // created_at
+        1 +                      // bump
+        16;                      // padding
+}
+
+/// Business Label - Optional plaintext metadata for a business
+/// Seeds: ["label", business_entry]
+/// Non-sensitive (e.g. "US-PAYROLL"); absent or zeroed = no label
+#[account]
+pub struct BusinessLabel {
+    /// Business entry the label belongs to
+    pub business_entry: Pubkey,
+
+    /// PLAINTEXT label, zero-padded UTF-8
+    pub label: [u8; 32],
+
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl BusinessLabel {
+    pub const LEN: usize = 8 +  // discriminator
+        32 +                     // business_entry
+        32 +                     // label
+        1 +                      // bump
+        16;                      // padding
+}
 
-    #[account(
-        seeds = [BUSINESS_ENTRY_SEED, master_vault.key().as_ref(), &business_entry.entry_index.to_le_bytes()],
-        bump = business_entry.bump,
-    )]
-    pub business_entry: Account<'info, BusinessEntry>,
+/// Salary Band - Encrypted pay-policy bounds for a business
+/// Seeds: ["salary_band", business_entry]
+#[account]
+pub struct SalaryBand {
+    /// Business entry the band applies to
+    pub business_entry: Pubkey,
 
-    #[account(
-        mut,
-        seeds = [EMPLOYEE_ENTRY_SEED, business_entry.key().as_ref(), &employee_entry.employee_index.to_le_bytes()],
-        bump = employee_entry.bump,
-    )]
-    pub employee_entry: Account<'info, EmployeeEntry>,
+    /// ENCRYPTED minimum salary
+    pub encrypted_min: Euint128,
 
-    /// CHECK: Permission account PDA for EmployeeEntry
-    /// Seeds: ["permission", employee_entry.key()]
-    #[account(mut)]
-    pub permission: UncheckedAccount<'info>,
+    /// ENCRYPTED maximum salary
+    pub encrypted_max: Euint128,
 
-    /// CHECK: MagicBlock context
-    #[account(mut)]
-    pub magic_context: AccountInfo<'info>,
+    /// Bump seed
+    pub bump: u8,
+}
 
-    /// CHECK: MagicBlock program
-    pub magic_program: AccountInfo<'info>,
+impl SalaryBand {
+    pub const LEN: usize = 8 +  // discriminator
+        32 +                     // business_entry
+        16 +                     // encrypted_min
+        16 +                     // encrypted_max
+        1 +                      // bump
+        16;                      // padding
+}
+
+/// Price Feed Config - Oracle used to convert USD-denominated accrual
+/// Seeds: ["price_feed", business_entry]
+#[account]
+pub struct PriceFeedConfig {
+    /// Business entry the feed applies to
+    pub business_entry: Pubkey,
+
+    /// Pyth price feed id (e.g. SOL/USD)
+    pub feed_id: [u8; 32],
+
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl PriceFeedConfig {
+    pub const LEN: usize = 8 +  // discriminator
+        32 +                     // business_entry
+        32 +                     // feed_id
+        1 +                      // bump
+        16;                      // padding
+}
+
+/// Withholding Config - Tax account that withheld payout shares go to
+/// Seeds: ["withholding", business_entry]
+#[account]
+pub struct WithholdingConfig {
+    /// Business entry the config applies to
+    pub business_entry: Pubkey,
+
+    /// Inco Token account (vault's confidential mint) receiving withholding
+    pub tax_token_account: Pubkey,
+
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl WithholdingConfig {
+    pub const LEN: usize = 8 +  // discriminator
+        32 +                     // business_entry
+        32 +                     // tax_token_account
+        1 +                      // bump
+        16;                      // padding
+}
+
+/// Split Destinations - Accounts an employee allows `withdraw_split` to pay
+/// Seeds: ["split_destinations", employee_entry]
+#[account]
+pub struct SplitDestinations {
+    /// Employee entry the allowlist belongs to
+    pub employee_entry: Pubkey,
+
+    /// Allowed Inco Token accounts; only the first `count` are used
+    pub destinations: [Pubkey; MAX_SPLIT_RECIPIENTS],
+
+    /// Number of registered destinations
+    pub count: u8,
+
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl SplitDestinations {
+    pub const LEN: usize = 8 +  // discriminator
+        32 +                     // employee_entry
+        32 * MAX_SPLIT_RECIPIENTS + // destinations
+        1 +                      // count
+        1 +                      // bump
+        16;                      // padding
+}
+
+/// Pending Withdrawal - Phase 1 of a two-phase withdrawal
+/// Seeds: ["pending_withdrawal", employee_entry]
+/// Holds the ciphertext until unlock; closed on finalize or cancel
+#[account]
+pub struct PendingWithdrawal {
+    /// Employee entry the withdrawal is for
+    pub employee_entry: Pubkey,
+
+    /// Wallet that initiated (and must finalize) the withdrawal
+    pub withdrawer: Pubkey,
+
+    /// ENCRYPTED amount ciphertext, replayed into the transfer on finalize
+    pub encrypted_amount: Vec<u8>,
+
+    /// Initiation timestamp
+    pub initiated_at: i64,
+
+    /// Earliest finalize timestamp
+    pub unlock_at: i64,
+
+    /// Bump seed
+    pub bump: u8,
+
+    /// Lifecycle state; only `Pending` can be finalized or cancelled
+    pub status: WithdrawalStatus,
+}
+
+impl PendingWithdrawal {
+    pub const LEN: usize = 8 +  // discriminator
+        32 +                     // employee_entry
+        32 +                     // withdrawer
+        4 + MAX_PENDING_CIPHERTEXT_LEN + // encrypted_amount
+        8 +                      // initiated_at
+        8 +                      // unlock_at
+        1 +                      // bump
+        1 +                      // status
+        15;                      // padding
+}
+
+/// PendingWithdrawal lifecycle
+///
+/// Cancelled withdrawals are always closed in `cancel_withdrawal`, so a
+/// live account is either still `Pending` or a kept `Finalized` record.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WithdrawalStatus {
+    #[default]
+    Pending,
+    Finalized,
+}
+
+/// User Token Account - PDA-based registry for deterministic token account lookup
+/// Seeds: ["user_token", owner_pubkey, mint_pubkey]
+/// Anyone can derive this address off-chain without storage
+/// Stores reference to the actual Inco Token account
+#[account]
+pub struct UserTokenAccount {
+    /// The wallet that owns this token account
+    pub owner: Pubkey,
+
+    /// The mint this token account is for
+    pub mint: Pubkey,
+
+    /// Reference to the actual Inco Token account (set by mint API)
+    /// This is the keypair-based account that holds actual tokens
+    pub inco_token_account: Pubkey,
+
+    /// ENCRYPTED balance (FHE via Inco) - cached/synced from Inco
+    pub balance: Euint128,
+
+    /// Timestamp when initialized
+    pub initialized_at: i64,
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl UserTokenAccount {
+    pub const LEN: usize = 8 +   // discriminator
+        32 +                      // owner
+        32 +                      // mint
+        32 +                      // inco_token_account
+        16 +                      // balance (Euint128)
+        8 +                       // initialized_at
+        1 +                       // bump
+        31;                       // padding
+}
+
+// ============================================================
+// Instruction Arguments & Return Data
+// ============================================================
+
+/// Account type `grow_account` resizes
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GrowTarget {
+    MasterVault,
+    BusinessEntry,
+    EmployeeEntry,
+}
+
+impl GrowTarget {
+    /// Current on-chain size of this account type
+    pub fn target_len(&self) -> usize {
+        match self {
+            GrowTarget::MasterVault => MasterVault::LEN,
+            GrowTarget::BusinessEntry => BusinessEntry::LEN,
+            GrowTarget::EmployeeEntry => EmployeeEntry::LEN,
+        }
+    }
+
+    pub fn discriminator(&self) -> &'static [u8] {
+        match self {
+            GrowTarget::MasterVault => MasterVault::DISCRIMINATOR,
+            GrowTarget::BusinessEntry => BusinessEntry::DISCRIMINATOR,
+            GrowTarget::EmployeeEntry => EmployeeEntry::DISCRIMINATOR,
+        }
+    }
+}
+
+/// One business allocation in `deposit_multi`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DepositAllocation {
+    /// BusinessEntry index to credit
+    pub entry_index: u64,
+
+    /// ENCRYPTED amount ciphertext
+    pub encrypted_amount: Vec<u8>,
+}
+
+/// One destination in `withdraw_split`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SplitRecipient {
+    /// Inco Token account to pay
+    pub destination: Pubkey,
+
+    /// Share of the payout in basis points
+    pub bps: u16,
+
+    /// ENCRYPTED share ciphertext
+    pub encrypted_amount: Vec<u8>,
+}
+
+/// External program ids returned by `config`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct ProgramConfig {
+    pub inco_lightning_program: Pubkey,
+    pub inco_token_program: Pubkey,
+    pub tee_validator: Pubkey,
+    pub confidential_mint: Pubkey,
+    pub use_confidential_tokens: bool,
+    pub provider_flags: u8,
+    /// Default handle when `tracks_encrypted_total` is false
+    pub encrypted_total_balance: Euint128,
+}
+
+/// Payout summary returned by every withdrawal path
+///
+/// Handles only, so the result is decryptable by authorized parties
+/// without scraping logs. Events are still emitted for indexers.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct WithdrawalResult {
+    pub employee_index: u64,
+    pub shadowwire_used: bool,
+    /// Amount paid to the employee's destination(s) (ENCRYPTED)
+    pub paid_handle: Euint128,
+    /// `encrypted_accrued` after the debit (ENCRYPTED)
+    pub new_accrued_handle: Euint128,
+    pub timestamp: i64,
+}
+
+/// Encrypted snapshot of a business returned by `export_business`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct BusinessExport {
+    /// Layout version (`EXPORT_VERSION`), checked by `import_business`
+    pub version: u8,
+    pub entry_index: u64,
+    pub next_employee_index: u64,
+    pub encryption_context: Pubkey,
+    pub encrypted_employer_id: Euint128,
+    pub encrypted_balance: Euint128,
+    pub encrypted_employee_count: Euint128,
+    pub rounding_mode: RoundingMode,
+    pub withdrawal_delay: i64,
+    pub rate_scale: u8,
+    pub salary_denom: SalaryDenom,
+    pub soft_cap: u64,
+    pub refund_commitment: [u8; 32],
+    pub encrypted_closed_unpaid: Euint128,
+    pub has_closed_unpaid: bool,
+    /// First employee index covered by `employees`
+    pub start: u64,
+    /// One past the last index scanned (`start` + accounts passed)
+    pub end: u64,
+    pub employees: Vec<EmployeeExport>,
+}
+
+/// One employee's encrypted handles within a `BusinessExport`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct EmployeeExport {
+    pub employee_index: u64,
+    pub is_active: bool,
+    pub last_action: i64,
+    pub suspended_at: i64,
+    pub end_timestamp: i64,
+    pub accrual_basis: u8,
+    pub withholding_bps: u16,
+    pub has_budget: bool,
+    pub has_prefunded: bool,
+    pub pending_catchup_seconds: u64,
+    pub last_withdrawal_at: i64,
+    pub allow_immediate_first_withdrawal: bool,
+    pub first_withdrawal_done: bool,
+    pub withdraw_authority: Pubkey,
+    pub encrypted_employee_id: Euint128,
+    pub encrypted_salary: Euint128,
+    pub encrypted_accrued: Euint128,
+    pub encrypted_total_withdrawn: Euint128,
+    pub encrypted_budget: Euint128,
+    pub encrypted_prefunded: Euint128,
+}
+
+/// Last L1 accrued anchor returned by `accrued_snapshot`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct AccruedSnapshotInfo {
+    pub accrued_handle: [u8; 32],
+    pub snapshot_time: i64,
+    pub now: i64,
+}
+
+/// Withdrawal timing returned by `withdraw_eligibility`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct WithdrawEligibilityInfo {
+    pub last_withdrawal_at: i64,
+    pub next_eligible_at: i64,
+    pub now: i64,
+}
+
+// ============================================================
+// Events (Minimal information for privacy)
+// ============================================================
+
+#[event]
+pub struct VaultInitialized {
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GlobalStateInitialized {
+    pub admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BusinessRegistered {
+    pub entry_index: u64,
+    /// Inco encryption context the business's ciphertexts use
+    pub encryption_context: Pubkey,
+    pub timestamp: i64,
+    // NOTE: No employer pubkey for privacy
+}
+
+/// Emitted on every salary mutation (handles only, decryptable by auditors)
+#[event]
+pub struct SalaryChanged {
+    pub business_index: u64,
+    pub employee_index: u64,
+    pub old_salary_handle: Euint128,
+    pub new_salary_handle: Euint128,
+    pub effective_at: i64,
+    /// Encrypted salary band checks (`None` when no band applied)
+    /// The requested salary was in band only if both decrypt to true
+    pub above_band_min: Option<Ebool>,
+    pub below_band_max: Option<Ebool>,
+    /// Encrypted "requested salary was within the protocol ceiling" flag
+    /// `None` when no ceiling applied; a false flag means it was clamped
+    pub within_ceiling: Option<Ebool>,
+}
+
+#[event]
+pub struct ContributionReceived {
+    pub business_index: u64,
+    pub employee_index: u64,
+    /// Encrypted contribution handle (ENCRYPTED)
+    pub amount_handle: Euint128,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MultiDeposit {
+    /// Encrypted sum of all allocations (ENCRYPTED)
+    pub total_handle: Euint128,
+    pub count: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FundsDeposited {
+    pub entry_index: u64,
+    pub timestamp: i64,
+    // NOTE: No amount for privacy
+}
+
+#[event]
+pub struct DepositReceiptCreated {
+    pub entry_index: u64,
+    pub receipt_index: u64,
+    pub timestamp: i64,
+    // NOTE: No amount for privacy
+}
+
+#[event]
+pub struct EmployeeAdded {
+    pub business_index: u64,
+    pub employee_index: u64,
+    pub timestamp: i64,
+    // NOTE: No pubkeys for privacy
+}
+
+#[event]
+pub struct WithdrawalProcessed {
+    pub business_index: u64,
+    pub employee_index: u64,
+    pub timestamp: i64,
+    pub shadowwire_enabled: bool,
+    /// Caller-supplied invoice/reference id (zeros if none)
+    pub reference: [u8; 16],
+    /// Updated lifetime payout handle (ENCRYPTED)
+    pub encrypted_total_withdrawn: Euint128,
+    /// Employee's share of this payout (ENCRYPTED)
+    pub net_handle: Euint128,
+    /// Share sent to the tax account (ENCRYPTED, default if no withholding)
+    pub withheld_handle: Euint128,
+    // NOTE: No amount for privacy
+}
+
+#[event]
+pub struct DelegatedToTee {
+    pub business_index: u64,
+    pub employee_index: u64,
+    pub validator: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CommittedFromTee {
+    pub business_index: u64,
+    pub employee_index: u64,
+    pub timestamp: i64,
+}
 
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct EmployeeSuspended {
+    pub business_index: u64,
+    pub employee_index: u64,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct InitializeUserTokenAccount<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
+#[event]
+pub struct EmployeeResumed {
+    pub business_index: u64,
+    pub employee_index: u64,
+    pub suspended_seconds: i64,
+    pub timestamp: i64,
+}
 
-    /// CHECK: The mint for this token account
-    pub mint: AccountInfo<'info>,
+/// `met` is an encrypted bool: true when the withheld share covers
+/// `withholding_bps` of the gross payout
+#[event]
+pub struct WithholdingApplied {
+    pub business_index: u64,
+    pub employee_index: u64,
+    pub withholding_bps: u16,
+    pub met: Ebool,
+    pub timestamp: i64,
+}
 
-    #[account(
-        init,
-        payer = owner,
-        space = UserTokenAccount::LEN,
-        seeds = [USER_TOKEN_SEED, owner.key().as_ref(), mint.key().as_ref()],
-        bump
-    )]
-    pub user_token_account: Account<'info, UserTokenAccount>,
+/// `capped` is an encrypted bool: true when accrual hit the budget
+#[event]
+pub struct AccrualCapped {
+    pub business_index: u64,
+    pub employee_index: u64,
+    pub capped: Ebool,
+    pub timestamp: i64,
+}
 
-    /// CHECK: Inco Lightning program for FHE operations
-    #[account(address = INCO_LIGHTNING_ID)]
-    pub inco_lightning_program: AccountInfo<'info>,
+#[event]
+pub struct TeeAccruedSubmitted {
+    pub business_index: u64,
+    pub employee_index: u64,
+    /// Accepted accrued handle (ENCRYPTED)
+    pub accrued_handle: Euint128,
+    pub timestamp: i64,
+}
 
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct EmployeeFunded {
+    pub business_index: u64,
+    pub employee_index: u64,
+    /// Updated prefunded bucket handle (ENCRYPTED)
+    pub prefunded_handle: Euint128,
+    /// Encrypted "business balance covered the requested amount" flag;
+    /// false means only the remaining balance was moved
+    pub covered: Ebool,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct GetUserTokenPda<'info> {
-    /// CHECK: The owner to look up
-    pub owner: AccountInfo<'info>,
+/// Recipients are public (destination accounts); shares stay encrypted
+/// `shares_met` holds one encrypted bool per split, in order: true when
+/// the share covers its `bps` of the payout
+#[event]
+pub struct SplitWithdrawal {
+    pub business_index: u64,
+    pub employee_index: u64,
+    pub recipient_count: u8,
+    pub shares_met: Vec<Ebool>,
+    pub timestamp: i64,
+    /// Updated lifetime payout handle (ENCRYPTED)
+    pub encrypted_total_withdrawn: Euint128,
+}
 
-    /// CHECK: The mint to look up
-    pub mint: AccountInfo<'info>,
+#[event]
+pub struct SplitDestinationsSet {
+    pub business_index: u64,
+    pub employee_index: u64,
+    pub count: u8,
+    pub timestamp: i64,
+}
 
-    #[account(
-        seeds = [USER_TOKEN_SEED, owner.key().as_ref(), mint.key().as_ref()],
-        bump = user_token_account.bump,
-    )]
-    pub user_token_account: Account<'info, UserTokenAccount>,
+#[event]
+pub struct WithholdingUpdated {
+    pub business_index: u64,
+    pub employee_index: u64,
+    pub withholding_bps: u16,
+    pub tax_token_account: Pubkey,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct SetIncoTokenAccount<'info> {
-    /// Authority setting the Inco Token account (owner or trusted signer)
-    #[account(mut)]
-    pub authority: Signer<'info>,
+#[event]
+pub struct TermExtended {
+    pub business_index: u64,
+    pub employee_index: u64,
+    pub old_end: i64,
+    pub new_end: i64,
+    pub timestamp: i64,
+}
 
-    /// CHECK: The owner of the user token account
-    pub owner: AccountInfo<'info>,
+#[event]
+pub struct ImmediateFirstWithdrawalSet {
+    pub business_index: u64,
+    pub employee_index: u64,
+    pub allow: bool,
+    pub timestamp: i64,
+}
 
-    /// CHECK: The mint for the token account
-    pub mint: AccountInfo<'info>,
+#[event]
+pub struct WithdrawAuthoritySet {
+    pub business_index: u64,
+    pub employee_index: u64,
+    pub registered: bool,
+    pub timestamp: i64,
+}
 
-    #[account(
-        mut,
-        seeds = [USER_TOKEN_SEED, owner.key().as_ref(), mint.key().as_ref()],
-        bump = user_token_account.bump,
-    )]
-    pub user_token_account: Account<'info, UserTokenAccount>,
+#[event]
+pub struct FirstWithdrawalWaived {
+    pub business_index: u64,
+    pub employee_index: u64,
+    pub timestamp: i64,
 }
 
-// ============================================================
-// Account Structures (Maximum Privacy)
-// ============================================================
+#[event]
+pub struct EmployeeBudgetSet {
+    pub business_index: u64,
+    pub employee_index: u64,
+    /// Budget handle (ENCRYPTED); default when the budget was cleared
+    pub budget_handle: Euint128,
+    pub timestamp: i64,
+}
 
-/// Master Vault - Single account holding all funds
-/// Observer sees ONLY this account's total balance
-#[account]
-pub struct MasterVault {
-    /// Program authority
-    pub authority: Pubkey,
-    
-    /// Total balance (PUBLIC - unavoidable on Solana)
-    pub total_balance: u64,
-    
-    /// ENCRYPTED business count (observer cannot see)
-    pub encrypted_business_count: Euint128,
-    
-    /// ENCRYPTED employee count (observer cannot see)
-    pub encrypted_employee_count: Euint128,
-    
-    /// Next business index (for PDA derivation)
-    pub next_business_index: u64,
-    
-    /// Is active
-    pub is_active: bool,
-    
-    /// Bump seed
-    pub bump: u8,
-    
-    /// Confidential token mint address (for confidential transfers)
-    pub confidential_mint: Pubkey,
-    
-    /// Whether to use confidential tokens for transfers
-    pub use_confidential_tokens: bool,
+#[event]
+pub struct AccrualBasisUpdated {
+    pub business_index: u64,
+    pub employee_index: u64,
+    pub accrual_basis: u8,
+    pub timestamp: i64,
 }
 
-impl MasterVault {
-    pub const LEN: usize = 8 +  // discriminator
-        32 +                     // authority
-        8 +                      // total_balance
-        16 +                     // encrypted_business_count
-        16 +                     // encrypted_employee_count
-        8 +                      // next_business_index
-        1 +                      // is_active
-        1 +                      // bump
-        32 +                     // confidential_mint
-        1 +                      // use_confidential_tokens flag
-        31;                      // padding
+#[event]
+pub struct DelegationRecovered {
+    pub business_index: u64,
+    pub employee_index: u64,
+    pub included_permission: bool,
+    pub timestamp: i64,
 }
 
-/// Business Entry - INDEX-BASED PDA (no employer pubkey in seeds)
-/// Seeds: ["entry", master_vault, entry_index]
-#[account]
-pub struct BusinessEntry {
-    /// Reference to master vault
-    pub master_vault: Pubkey,
-    
-    /// Entry index (used in PDA, NOT employer pubkey)
-    pub entry_index: u64,
-    
-    /// ENCRYPTED employer ID (hash of pubkey)
-    pub encrypted_employer_id: Euint128,
-    
-    /// ENCRYPTED balance
-    pub encrypted_balance: Euint128,
-    
-    /// ENCRYPTED employee count
-    pub encrypted_employee_count: Euint128,
-    
-    /// Next employee index
-    pub next_employee_index: u64,
-    
-    /// Is active
-    pub is_active: bool,
-    
-    /// Bump seed
-    pub bump: u8,
+#[event]
+pub struct VaultDelegatedToTee {
+    pub validator: Pubkey,
+    pub timestamp: i64,
 }
 
-impl BusinessEntry {
-    pub const LEN: usize = 8 +  // discriminator
-        32 +                     // master_vault
-        8 +                      // entry_index
-        16 +                     // encrypted_employer_id
-        16 +                     // encrypted_balance
-        16 +                     // encrypted_employee_count
-        8 +                      // next_employee_index
-        1 +                      // is_active
-        1 +                      // bump
-        32;                      // padding
+#[event]
+pub struct VaultCommittedFromTee {
+    pub timestamp: i64,
 }
 
-/// Employee Entry - INDEX-BASED PDA (no employee pubkey in seeds)
-/// Seeds: ["employee", business_entry, employee_index]
-#[account]
-pub struct EmployeeEntry {
-    /// Reference to business entry
-    pub business_entry: Pubkey,
-    
-    /// Employee index (used in PDA, NOT employee pubkey)
-    pub employee_index: u64,
-    
-    /// ENCRYPTED employee ID (hash of pubkey)
-    pub encrypted_employee_id: Euint128,
-    
-    /// ENCRYPTED salary
-    pub encrypted_salary: Euint128,
-    
-    /// ENCRYPTED accrued amount
-    pub encrypted_accrued: Euint128,
-    
-    /// Last action timestamp
-    pub last_action: i64,
-    
-    /// Is active
-    pub is_active: bool,
-    
-    /// Bump seed
-    pub bump: u8,
+#[event]
+pub struct RateScaleUpdated {
+    pub entry_index: u64,
+    pub rate_scale: u8,
+    pub timestamp: i64,
 }
 
-impl EmployeeEntry {
-    pub const LEN: usize = 8 +  // discriminator
-        32 +                     // business_entry
-        8 +                      // employee_index
-        16 +                     // encrypted_employee_id
-        16 +                     // encrypted_salary
-        16 +                     // encrypted_accrued
-        8 +                      // last_action
-        1 +                      // is_active
-        1 +                      // bump
-        32;                      // padding
+#[event]
+pub struct SalaryDenomUpdated {
+    pub entry_index: u64,
+    pub salary_denom: SalaryDenom,
+    pub feed_id: [u8; 32],
+    pub timestamp: i64,
 }
 
-/// User Token Account - PDA-based registry for deterministic token account lookup
-/// Seeds: ["user_token", owner_pubkey, mint_pubkey]
-/// Anyone can derive this address off-chain without storage
-/// Stores reference to the actual Inco Token account
-#[account]
-pub struct UserTokenAccount {
-    /// The wallet that owns this token account
-    pub owner: Pubkey,
+#[event]
+pub struct RoundingModeUpdated {
+    pub entry_index: u64,
+    pub rounding_mode: RoundingMode,
+    pub timestamp: i64,
+}
 
-    /// The mint this token account is for
-    pub mint: Pubkey,
+/// `covered` is an encrypted bool: true when the drained amount fit in
+/// the business balance
+#[event]
+pub struct BusinessDrained {
+    pub entry_index: u64,
+    pub covered: Ebool,
+    pub timestamp: i64,
+    // NOTE: No amount for privacy
+}
 
-    /// Reference to the actual Inco Token account (set by mint API)
-    /// This is the keypair-based account that holds actual tokens
-    pub inco_token_account: Pubkey,
+#[event]
+pub struct BusinessLabelUpdated {
+    pub entry_index: u64,
+    pub label: [u8; 32],
+    pub timestamp: i64,
+}
 
-    /// ENCRYPTED balance (FHE via Inco) - cached/synced from Inco
-    pub balance: Euint128,
+#[event]
+pub struct SalaryBandUpdated {
+    pub entry_index: u64,
+    pub timestamp: i64,
+    // NOTE: Bounds stay encrypted
+}
 
-    /// Timestamp when initialized
-    pub initialized_at: i64,
+#[event]
+pub struct WithdrawalDelayUpdated {
+    pub entry_index: u64,
+    pub withdrawal_delay: i64,
+    pub timestamp: i64,
+}
 
-    /// Bump seed for PDA derivation
-    pub bump: u8,
+#[event]
+pub struct VaultClosed {
+    pub refund_to: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
 }
 
-impl UserTokenAccount {
-    pub const LEN: usize = 8 +   // discriminator
-        32 +                      // owner
-        32 +                      // mint
-        32 +                      // inco_token_account
-        16 +                      // balance (Euint128)
-        8 +                       // initialized_at
-        1 +                       // bump
-        31;                       // padding
+#[event]
+pub struct SoftCapUpdated {
+    pub entry_index: u64,
+    pub soft_cap: u64,
+    pub timestamp: i64,
 }
 
-// ============================================================
-// Events (Minimal information for privacy)
-// ============================================================
+#[event]
+pub struct RentPayerUpdated {
+    pub entry_index: u64,
+    pub rent_from_treasury: bool,
+    pub timestamp: i64,
+}
 
 #[event]
-pub struct VaultInitialized {
-    pub authority: Pubkey,
+pub struct BusinessFrozen {
+    pub entry_index: u64,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct BusinessRegistered {
+pub struct BusinessUnfrozen {
     pub entry_index: u64,
     pub timestamp: i64,
-    // NOTE: No employer pubkey for privacy
 }
 
 #[event]
-pub struct FundsDeposited {
+pub struct BusinessExported {
     pub entry_index: u64,
+    pub start: u64,
+    /// SHA-256 of the returned bundle bytes
+    pub bundle_hash: [u8; 32],
     pub timestamp: i64,
-    // NOTE: No amount for privacy
 }
 
 #[event]
-pub struct EmployeeAdded {
-    pub business_index: u64,
-    pub employee_index: u64,
+pub struct BusinessImported {
+    pub entry_index: u64,
+    pub start: u64,
+    pub employee_count: u8,
     pub timestamp: i64,
-    // NOTE: No pubkeys for privacy
 }
 
 #[event]
-pub struct WithdrawalProcessed {
+pub struct EmployeeSoftCapReached {
+    pub business_index: u64,
+    pub count: u64,
+}
+
+#[event]
+pub struct WithdrawalInitiated {
     pub business_index: u64,
     pub employee_index: u64,
+    pub unlock_at: i64,
     pub timestamp: i64,
-    pub shadowwire_enabled: bool,
     // NOTE: No amount for privacy
 }
 
 #[event]
-pub struct DelegatedToTee {
+pub struct WithdrawalFinalized {
     pub business_index: u64,
     pub employee_index: u64,
-    pub validator: Pubkey,
     pub timestamp: i64,
+    /// Updated lifetime payout handle (ENCRYPTED)
+    pub encrypted_total_withdrawn: Euint128,
 }
 
 #[event]
-pub struct CommittedFromTee {
+pub struct WithdrawalCancelled {
     pub business_index: u64,
     pub employee_index: u64,
     pub timestamp: i64,
@@ -1682,6 +8947,33 @@ pub struct ConfidentialMintConfigured {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct ConfidentialMintRotated {
+    pub old_mint: Pubkey,
+    pub new_mint: Pubkey,
+    pub vault_token_account: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProviderFlagsUpdated {
+    pub old_bits: u8,
+    pub new_bits: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MinDelegationDurationUpdated {
+    pub min_delegation_duration: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokenDecimalsUpdated {
+    pub decimals: u8,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct UserTokenAccountInitialized {
     pub owner: Pubkey,
@@ -1739,6 +9031,785 @@ pub enum BagelError {
 
     #[msg("Identity verification failed")]
     IdentityVerificationFailed,
+
+    #[msg("Confidential token account still holds a balance")]
+    ConfidentialBalanceNotEmpty,
+
+    #[msg("Withdrawal is still in its cooling-off period")]
+    WithdrawalLocked,
+
+    #[msg("Withdrawal has already unlocked")]
+    WithdrawalUnlocked,
+
+    #[msg("Account is still delegated to the TEE")]
+    StillDelegated,
+
+    #[msg("All employee entries must be closed first")]
+    EmployeesRemaining,
+
+    #[msg("Inco Lightning program is not deployed at the expected id")]
+    IncoUnavailable,
+
+    #[msg("MagicBlock delegation program is not deployed at the expected id")]
+    MagicBlockUnavailable,
+
+    #[msg("Batch instruction called without any accounts")]
+    NoAccountsProvided,
+
+    #[msg("Account passed more than once in a batch")]
+    DuplicateAccount,
+
+    #[msg("Price feed account is missing, unverified or for another feed")]
+    InvalidPriceFeed,
+
+    #[msg("Oracle price is too old")]
+    StalePrice,
+
+    #[msg("Oracle price confidence interval is too wide")]
+    PriceConfidenceTooWide,
+
+    #[msg("Withdrawal is still pending")]
+    WithdrawalPending,
+
+    #[msg("Batch accounts do not match the declared indices")]
+    AccountOrderMismatch,
+
+    #[msg("Employee entry is already delegated to TEE")]
+    AlreadyDelegated,
+
+    #[msg("Unknown provider flag bits")]
+    InvalidProviderFlags,
+
+    #[msg("Privacy provider is not enabled for this deployment")]
+    ProviderDisabled,
+
+    #[msg("Provider is flagged real but only a mock is available in this build")]
+    ProviderUnavailable,
+
+    #[msg("Employee has withholding configured; use request_withdrawal")]
+    WithholdingRequired,
+
+    #[msg("Validator is not a known TEE validator")]
+    InvalidValidator,
+
+    #[msg("Business encryption context must be set")]
+    InvalidEncryptionContext,
+
+    #[msg("Business is frozen by the protocol authority")]
+    BusinessIsFrozen,
+
+    #[msg("Entry has not been delegated for the minimum duration yet")]
+    DelegationTooShort,
+
+    #[msg("Backup bundle is malformed or from an unsupported version")]
+    InvalidBundle,
+
+    #[msg("Confidential mint is already configured, use rotate_confidential_mint")]
+    ConfidentialMintAlreadySet,
+
+    #[msg("Business requires a withdrawal delay, use initiate_withdrawal")]
+    WithdrawalDelayActive,
+
+    #[msg("Employee is still active, suspend them before closing the entry")]
+    EmployeeActive,
 }
 
 // Account structs moved to top level (before bagel module) for accessibility
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// On-chain size of an account, discriminator included
+    fn serialized_len<T: AccountSerialize>(account: &T) -> usize {
+        let mut buf = Vec::new();
+        account.try_serialize(&mut buf).unwrap();
+        buf.len()
+    }
+
+    #[test]
+    fn test_master_vault_fits_len() {
+        let vault = MasterVault {
+            authority: Pubkey::default(),
+            total_balance: 0,
+            encrypted_business_count: Euint128::default(),
+            encrypted_employee_count: Euint128::default(),
+            next_business_index: 0,
+            is_active: false,
+            bump: 0,
+            confidential_mint: Pubkey::default(),
+            use_confidential_tokens: false,
+            provider_flags: ProviderFlags::default(),
+            min_delegation_duration: 0,
+            token_decimals: Some(MAX_TOKEN_DECIMALS),
+            encrypted_total_balance: Euint128::default(),
+            tracks_encrypted_total: true,
+            token_account: Pubkey::default(),
+        };
+        assert!(serialized_len(&vault) <= MasterVault::LEN);
+    }
+
+    #[test]
+    fn test_global_state_fits_len() {
+        let state = GlobalState {
+            admin: Pubkey::default(),
+            pending_admin: Pubkey::default(),
+            is_paused: false,
+            total_payrolls: 0,
+            total_withdrawals: 0,
+            bump: 0,
+        };
+        assert!(serialized_len(&state) <= GlobalState::LEN);
+    }
+
+    #[test]
+    fn test_business_entry_fits_len() {
+        let entry = BusinessEntry {
+            master_vault: Pubkey::default(),
+            entry_index: 0,
+            encrypted_employer_id: Euint128::default(),
+            encrypted_balance: Euint128::default(),
+            encrypted_employee_count: Euint128::default(),
+            next_employee_index: 0,
+            is_active: false,
+            bump: 0,
+            next_receipt_index: 0,
+            rounding_mode: RoundingMode::Floor,
+            withdrawal_delay: 0,
+            has_salary_band: false,
+            rate_scale: 0,
+            salary_denom: SalaryDenom::Usd,
+            soft_cap: 0,
+            is_funded: false,
+            rent_from_treasury: false,
+            encryption_context: Pubkey::default(),
+            is_frozen: true,
+            import_cursor: u64::MAX,
+            refund_commitment: [u8::MAX; 32],
+            encrypted_closed_unpaid: Euint128::default(),
+            has_closed_unpaid: true,
+        };
+        assert!(serialized_len(&entry) <= BusinessEntry::LEN);
+    }
+
+    #[test]
+    fn test_employee_entry_fits_len() {
+        let employee = EmployeeEntry {
+            business_entry: Pubkey::default(),
+            employee_index: 0,
+            encrypted_employee_id: Euint128::default(),
+            encrypted_salary: Euint128::default(),
+            encrypted_accrued: Euint128::default(),
+            last_action: 0,
+            is_active: false,
+            bump: 0,
+            encrypted_total_withdrawn: Euint128::default(),
+            suspended_at: 0,
+            last_action_slot: 0,
+            accrual_basis: accrual::ACCRUAL_BASIS_SLOTS,
+            encrypted_budget: Euint128::default(),
+            has_budget: true,
+            encrypted_prefunded: Euint128::default(),
+            has_prefunded: true,
+            pending_catchup_seconds: 0,
+            withholding_bps: 10_000,
+            delegated_at: 0,
+            last_snapshot_accrued: [0xFF; 32],
+            last_snapshot_time: i64::MAX,
+            end_timestamp: i64::MAX,
+            allow_immediate_first_withdrawal: true,
+            first_withdrawal_done: true,
+            withdraw_authority: Pubkey::default(),
+            last_withdrawal_at: 0,
+        };
+        assert!(serialized_len(&employee) <= EmployeeEntry::LEN);
+    }
+
+    #[test]
+    fn test_deposit_receipt_fits_len() {
+        let receipt = DepositReceipt {
+            business_entry: Pubkey::default(),
+            receipt_index: 0,
+            depositor: Pubkey::default(),
+            encrypted_amount: Euint128::default(),
+            timestamp: 0,
+            bump: 0,
+        };
+        assert!(serialized_len(&receipt) <= DepositReceipt::LEN);
+    }
+
+    #[test]
+    fn test_deposit_guard_fits_len() {
+        let guard = DepositGuard {
+            business_entry: Pubkey::default(),
+            depositor: Pubkey::default(),
+            idempotency_key: [0u8; 16],
+            created_at: 0,
+            bump: 0,
+        };
+        assert!(serialized_len(&guard) <= DepositGuard::LEN);
+    }
+
+    #[test]
+    fn test_business_label_fits_len() {
+        let business_label = BusinessLabel {
+            business_entry: Pubkey::default(),
+            label: [0u8; 32],
+            bump: 0,
+        };
+        assert!(serialized_len(&business_label) <= BusinessLabel::LEN);
+    }
+
+    #[test]
+    fn test_salary_band_fits_len() {
+        let band = SalaryBand {
+            business_entry: Pubkey::default(),
+            encrypted_min: Euint128::default(),
+            encrypted_max: Euint128::default(),
+            bump: 0,
+        };
+        assert!(serialized_len(&band) <= SalaryBand::LEN);
+    }
+
+    #[test]
+    fn test_price_feed_config_fits_len() {
+        let config = PriceFeedConfig {
+            business_entry: Pubkey::default(),
+            feed_id: [0u8; 32],
+            bump: 0,
+        };
+        assert!(serialized_len(&config) <= PriceFeedConfig::LEN);
+    }
+
+    #[test]
+    fn test_withholding_config_fits_len() {
+        let config = WithholdingConfig {
+            business_entry: Pubkey::default(),
+            tax_token_account: Pubkey::default(),
+            bump: 0,
+        };
+        assert!(serialized_len(&config) <= WithholdingConfig::LEN);
+    }
+
+    #[test]
+    fn test_split_destinations_fits_len() {
+        let allowlist = SplitDestinations {
+            employee_entry: Pubkey::default(),
+            destinations: [Pubkey::default(); MAX_SPLIT_RECIPIENTS],
+            count: MAX_SPLIT_RECIPIENTS as u8,
+            bump: 0,
+        };
+        assert!(serialized_len(&allowlist) <= SplitDestinations::LEN);
+    }
+
+    #[test]
+    fn test_resolve_tee_validator() {
+        let tee = Pubkey::try_from(TEE_VALIDATOR).unwrap();
+        assert_eq!(resolve_tee_validator(None).unwrap(), tee);
+        assert_eq!(resolve_tee_validator(Some(tee)).unwrap(), tee);
+        assert_eq!(
+            resolve_tee_validator(Some(Pubkey::new_unique())).unwrap_err(),
+            BagelError::InvalidValidator.into()
+        );
+    }
+
+    #[test]
+    fn test_validate_split_bps() {
+        assert!(validate_split_bps(&[10_000]).is_ok());
+        assert!(validate_split_bps(&[7_000, 2_500, 500]).is_ok());
+        // Shares must sum to exactly 100%
+        assert!(validate_split_bps(&[5_000, 4_999]).is_err());
+        assert!(validate_split_bps(&[u16::MAX, 10_000]).is_err());
+        // Zero shares, empty and oversized splits
+        assert!(validate_split_bps(&[10_000, 0]).is_err());
+        assert!(validate_split_bps(&[]).is_err());
+        assert!(validate_split_bps(&[2_000; MAX_SPLIT_RECIPIENTS + 1]).is_err());
+    }
+
+    #[test]
+    fn test_business_export_fits_return_data() {
+        let employee = EmployeeExport {
+            employee_index: u64::MAX,
+            is_active: true,
+            last_action: i64::MAX,
+            suspended_at: i64::MAX,
+            end_timestamp: i64::MAX,
+            accrual_basis: u8::MAX,
+            withholding_bps: u16::MAX,
+            has_budget: true,
+            has_prefunded: true,
+            pending_catchup_seconds: u64::MAX,
+            last_withdrawal_at: i64::MAX,
+            allow_immediate_first_withdrawal: true,
+            first_withdrawal_done: true,
+            withdraw_authority: Pubkey::new_unique(),
+            encrypted_employee_id: Euint128::default(),
+            encrypted_salary: Euint128::default(),
+            encrypted_accrued: Euint128::default(),
+            encrypted_total_withdrawn: Euint128::default(),
+            encrypted_budget: Euint128::default(),
+            encrypted_prefunded: Euint128::default(),
+        };
+        let export = BusinessExport {
+            version: EXPORT_VERSION,
+            entry_index: u64::MAX,
+            next_employee_index: u64::MAX,
+            encryption_context: Pubkey::new_unique(),
+            encrypted_employer_id: Euint128::default(),
+            encrypted_balance: Euint128::default(),
+            encrypted_employee_count: Euint128::default(),
+            rounding_mode: RoundingMode::Nearest,
+            withdrawal_delay: i64::MAX,
+            rate_scale: u8::MAX,
+            salary_denom: SalaryDenom::Usd,
+            soft_cap: u64::MAX,
+            refund_commitment: [u8::MAX; 32],
+            encrypted_closed_unpaid: Euint128::default(),
+            has_closed_unpaid: true,
+            start: 0,
+            end: u64::MAX,
+            employees: vec![employee; MAX_EXPORT_EMPLOYEES],
+        };
+        let mut data = Vec::new();
+        export.serialize(&mut data).unwrap();
+        assert!(data.len() <= anchor_lang::solana_program::program::MAX_RETURN_DATA);
+
+        // import_business decodes the same bytes and nothing more
+        let decoded = BusinessExport::try_from_slice(&data).unwrap();
+        assert_eq!(decoded.version, EXPORT_VERSION);
+        assert_eq!(decoded.employees.len(), MAX_EXPORT_EMPLOYEES);
+        data.push(0);
+        assert!(BusinessExport::try_from_slice(&data).is_err());
+    }
+
+    #[test]
+    fn test_refund_commitment_binds_account_and_salt() {
+        let account = Pubkey::new_unique();
+        let salt = [7u8; 32];
+        let committed = refund_commitment(&account, &salt);
+        assert_eq!(committed, refund_commitment(&account, &salt));
+        assert_ne!(committed, [0u8; 32]);
+        assert_ne!(committed, refund_commitment(&Pubkey::new_unique(), &salt));
+        assert_ne!(committed, refund_commitment(&account, &[8u8; 32]));
+    }
+
+    #[test]
+    fn test_export_bundle_message_binds_bytes() {
+        let bundle = vec![3u8; 64];
+        let message = export_bundle_message(&bundle);
+        assert_eq!(&message[..12], b"bagel:export");
+        assert_eq!(message.len(), 12 + 32);
+        assert_eq!(message, export_bundle_message(&bundle));
+
+        let mut tampered = bundle.clone();
+        tampered[10] ^= 1;
+        assert_ne!(message, export_bundle_message(&tampered));
+        assert_ne!(message, export_bundle_message(&bundle[..63]));
+    }
+
+    #[test]
+    fn test_validate_ciphertext_bounds() {
+        assert!(validate_ciphertext(&[1u8; MAX_CIPHERTEXT_LEN]).is_ok());
+        assert_eq!(
+            validate_ciphertext(&[1u8; MAX_CIPHERTEXT_LEN + 1]).unwrap_err(),
+            BagelError::InvalidCiphertext.into()
+        );
+        assert!(validate_ciphertext(&[]).is_err());
+        // Anything accepted also fits a PendingWithdrawal
+        assert!(MAX_CIPHERTEXT_LEN <= MAX_PENDING_CIPHERTEXT_LEN);
+    }
+
+    #[test]
+    fn test_pending_withdrawal_fits_len() {
+        // Worst case: ciphertext at the maximum accepted size
+        let pending = PendingWithdrawal {
+            employee_entry: Pubkey::default(),
+            withdrawer: Pubkey::default(),
+            encrypted_amount: vec![0u8; MAX_PENDING_CIPHERTEXT_LEN],
+            initiated_at: 0,
+            unlock_at: 0,
+            bump: 0,
+            status: WithdrawalStatus::Finalized,
+        };
+        assert!(serialized_len(&pending) <= PendingWithdrawal::LEN);
+    }
+
+    #[test]
+    fn test_user_token_account_fits_len() {
+        let user_token = UserTokenAccount {
+            owner: Pubkey::default(),
+            mint: Pubkey::default(),
+            inco_token_account: Pubkey::default(),
+            balance: Euint128::default(),
+            initialized_at: 0,
+            bump: 0,
+        };
+        assert!(serialized_len(&user_token) <= UserTokenAccount::LEN);
+    }
+
+    /// Legacy 90-byte MasterVault: everything up to and including `bump`
+    fn legacy_vault_data(authority: &Pubkey) -> Vec<u8> {
+        let mut data = vec![0u8; 90];
+        data[0..8].copy_from_slice(MasterVault::DISCRIMINATOR);
+        data[8..40].copy_from_slice(authority.as_ref());
+        data[40..48].copy_from_slice(&1_234u64.to_le_bytes());
+        data[48..64].copy_from_slice(&[0xB1; 16]);
+        data[64..80].copy_from_slice(&[0xE1; 16]);
+        data[80..88].copy_from_slice(&7u64.to_le_bytes());
+        data[88] = 1;
+        data[89] = 254;
+        data
+    }
+
+    #[test]
+    fn test_migrate_vault_round_trip() {
+        let authority = Pubkey::new_unique();
+        let new_data = migrate_vault_data(&legacy_vault_data(&authority)).unwrap().unwrap();
+        assert_eq!(new_data.len(), MasterVault::LEN);
+
+        let vault = MasterVault::try_deserialize(&mut &new_data[..]).unwrap();
+        assert_eq!(vault.authority, authority);
+        assert_eq!(vault.total_balance, 1_234);
+        assert_eq!(&new_data[48..64], &[0xB1; 16]);
+        assert_eq!(&new_data[64..80], &[0xE1; 16]);
+        assert_eq!(vault.next_business_index, 7);
+        assert!(vault.is_active);
+        assert_eq!(vault.bump, 254);
+        assert_eq!(vault.confidential_mint, Pubkey::default());
+        assert!(!vault.use_confidential_tokens);
+        assert_eq!(vault.provider_flags.bits, ProviderFlags::DEFAULT_BITS);
+        assert_eq!(vault.min_delegation_duration, 0);
+        assert_eq!(vault.token_decimals, None);
+        assert!(!vault.tracks_encrypted_total);
+        assert_eq!(vault.token_account, Pubkey::default());
+    }
+
+    #[test]
+    fn test_migrate_vault_already_migrated() {
+        let authority = Pubkey::new_unique();
+        let mut migrated = migrate_vault_data(&legacy_vault_data(&authority)).unwrap().unwrap();
+        // A configured mint must survive a second run
+        migrated[90..122].copy_from_slice(Pubkey::new_unique().as_ref());
+        migrated[122] = 1;
+
+        assert!(migrate_vault_data(&migrated).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_migrate_vault_rejects_truncated_data() {
+        let authority = Pubkey::new_unique();
+        assert!(migrate_vault_data(&legacy_vault_data(&authority)[..89]).is_err());
+    }
+
+    #[test]
+    fn test_require_vault_discriminator() {
+        let data = legacy_vault_data(&Pubkey::new_unique());
+        assert!(require_vault_discriminator(&data).is_ok());
+
+        let mut spoofed = data.clone();
+        spoofed[0..8].copy_from_slice(BusinessEntry::DISCRIMINATOR);
+        assert!(require_vault_discriminator(&spoofed).is_err());
+        assert!(require_vault_discriminator(&data[..4]).is_err());
+    }
+
+    #[test]
+    fn test_validate_batch_keys() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+
+        assert!(validate_batch_keys(&[a, b], 2).is_ok());
+        assert_eq!(
+            validate_batch_keys(&[], 0).unwrap_err(),
+            BagelError::NoAccountsProvided.into()
+        );
+        assert_eq!(
+            validate_batch_keys(&[a, b], 3).unwrap_err(),
+            BagelError::InvalidState.into()
+        );
+        assert_eq!(
+            validate_batch_keys(&[a, b, a], 3).unwrap_err(),
+            BagelError::DuplicateAccount.into()
+        );
+    }
+
+    #[test]
+    fn test_validate_batch_order_rejects_shuffled_accounts() {
+        let business = Pubkey::new_unique();
+        let permission_program =
+            Pubkey::try_from(crate::constants::MAGICBLOCK_PERMISSION_PROGRAM).unwrap();
+        let pair = |index: u64| {
+            let (entry, _) = Pubkey::find_program_address(
+                &[EMPLOYEE_ENTRY_SEED, business.as_ref(), &index.to_le_bytes()],
+                &crate::ID,
+            );
+            let (permission, _) = Pubkey::find_program_address(
+                &[PERMISSION_SEED, entry.as_ref()],
+                &permission_program,
+            );
+            [entry, permission]
+        };
+        let [e0, p0] = pair(0);
+        let [e1, p1] = pair(1);
+
+        assert!(validate_batch_order(&business, &[0, 1], &[e0, p0, e1, p1]).is_ok());
+        assert_eq!(
+            validate_batch_order(&business, &[0, 1], &[e1, p1, e0, p0]).unwrap_err(),
+            BagelError::AccountOrderMismatch.into()
+        );
+        assert_eq!(
+            validate_batch_order(&business, &[0, 1], &[e0, p1, e1, p0]).unwrap_err(),
+            BagelError::AccountOrderMismatch.into()
+        );
+        assert_eq!(
+            validate_batch_order(&business, &[0, 1], &[p0, e0, e1, p1]).unwrap_err(),
+            BagelError::AccountOrderMismatch.into()
+        );
+        assert_eq!(
+            validate_batch_order(&business, &[0], &[e0, p0, e1, p1]).unwrap_err(),
+            BagelError::InvalidState.into()
+        );
+    }
+
+    #[test]
+    fn test_rent_top_up_for_undersized_account() {
+        let rent = Rent::default();
+        let legacy_len = 90;
+        let lamports = rent.minimum_balance(legacy_len);
+
+        let top_up = rent_top_up(&rent, lamports, MasterVault::LEN);
+        assert_eq!(lamports + top_up, rent.minimum_balance(MasterVault::LEN));
+        assert!(top_up > 0);
+
+        // Already funded for the new size
+        assert_eq!(rent_top_up(&rent, rent.minimum_balance(MasterVault::LEN), MasterVault::LEN), 0);
+    }
+
+    #[test]
+    fn test_padded_handle_round_trip() {
+        let handle = Euint128::default();
+        let padded = padded_handle(&handle).unwrap();
+        assert!(padded[16..].iter().all(|b| *b == 0));
+        // Same decoding submit_tee_accrued applies
+        let decoded = Euint128::deserialize(&mut &padded[..16]).unwrap();
+        assert_eq!(padded_handle(&decoded).unwrap(), padded);
+    }
+
+    #[test]
+    fn test_tee_accrued_message_binds_nonce() {
+        let entry = Pubkey::new_unique();
+        assert_ne!(
+            tee_accrued_message(&entry, &[1u8; 32], 1, 5, 10),
+            tee_accrued_message(&entry, &[1u8; 32], 2, 5, 10)
+        );
+        // The covered time and slot are signed too
+        assert_ne!(
+            tee_accrued_message(&entry, &[1u8; 32], 1, 5, 10),
+            tee_accrued_message(&entry, &[1u8; 32], 1, 6, 10)
+        );
+        assert_ne!(
+            tee_accrued_message(&entry, &[1u8; 32], 1, 5, 10),
+            tee_accrued_message(&entry, &[1u8; 32], 1, 5, 11)
+        );
+    }
+
+    #[test]
+    fn test_treasury_spendable_keeps_rent_minimum() {
+        let rent = Rent::default();
+        let floor = rent.minimum_balance(BusinessEntry::LEN);
+        let employee_rent = rent.minimum_balance(EmployeeEntry::LEN);
+
+        assert_eq!(treasury_spendable(&rent, floor, BusinessEntry::LEN), 0);
+        assert_eq!(
+            treasury_spendable(&rent, floor + employee_rent, BusinessEntry::LEN),
+            employee_rent
+        );
+        assert!(treasury_spendable(&rent, floor + employee_rent - 1, BusinessEntry::LEN) < employee_rent);
+        assert_eq!(treasury_spendable(&rent, 0, BusinessEntry::LEN), 0);
+    }
+
+    #[test]
+    fn test_grow_target_layouts() {
+        assert_eq!(GrowTarget::MasterVault.target_len(), MasterVault::LEN);
+        assert_eq!(GrowTarget::BusinessEntry.target_len(), BusinessEntry::LEN);
+        assert_eq!(GrowTarget::EmployeeEntry.target_len(), EmployeeEntry::LEN);
+        assert_ne!(
+            GrowTarget::BusinessEntry.discriminator(),
+            GrowTarget::EmployeeEntry.discriminator()
+        );
+    }
+
+    fn sample_business() -> BusinessEntry {
+        BusinessEntry {
+            master_vault: Pubkey::default(),
+            entry_index: 0,
+            encrypted_employer_id: Euint128::default(),
+            encrypted_balance: Euint128::default(),
+            encrypted_employee_count: Euint128::default(),
+            next_employee_index: 1,
+            is_active: true,
+            bump: 0,
+            next_receipt_index: 0,
+            rounding_mode: RoundingMode::Floor,
+            withdrawal_delay: 0,
+            has_salary_band: false,
+            rate_scale: 0,
+            salary_denom: SalaryDenom::Native,
+            soft_cap: 0,
+            is_funded: true,
+            rent_from_treasury: false,
+            encryption_context: Pubkey::default(),
+            is_frozen: false,
+            import_cursor: 0,
+            refund_commitment: [0u8; 32],
+            encrypted_closed_unpaid: Euint128::default(),
+            has_closed_unpaid: false,
+        }
+    }
+
+    fn sample_employee() -> EmployeeEntry {
+        EmployeeEntry {
+            business_entry: Pubkey::default(),
+            employee_index: 0,
+            encrypted_employee_id: Euint128::default(),
+            encrypted_salary: Euint128::default(),
+            encrypted_accrued: Euint128::default(),
+            last_action: 0,
+            is_active: true,
+            bump: 0,
+            encrypted_total_withdrawn: Euint128::default(),
+            suspended_at: 0,
+            last_action_slot: 0,
+            accrual_basis: accrual::ACCRUAL_BASIS_SLOTS,
+            encrypted_budget: Euint128::default(),
+            has_budget: false,
+            encrypted_prefunded: Euint128::default(),
+            has_prefunded: false,
+            pending_catchup_seconds: 0,
+            withholding_bps: 0,
+            delegated_at: 0,
+            last_snapshot_accrued: [0u8; 32],
+            last_snapshot_time: 0,
+            end_timestamp: 0,
+            allow_immediate_first_withdrawal: false,
+            first_withdrawal_done: false,
+            withdraw_authority: Pubkey::default(),
+            last_withdrawal_at: 0,
+        }
+    }
+
+    /// Serialize `account`, cut the trailing `cut` bytes (an account
+    /// created before those fields existed), then zero-extend it to
+    /// `kind`'s current size the way `grow_to`'s realloc does
+    fn grown_buffer<T: AccountSerialize>(account: &T, cut: usize, kind: GrowTarget) -> Vec<u8> {
+        let mut buf = Vec::new();
+        account.try_serialize(&mut buf).unwrap();
+        buf.truncate(buf.len() - cut);
+        assert!(buf.len() < kind.target_len());
+        buf.resize(kind.target_len(), 0);
+        buf
+    }
+
+    #[test]
+    fn test_grown_employee_entry_deserializes() {
+        let mut employee = sample_employee();
+        employee.employee_index = 7;
+        employee.last_action = 1_000;
+
+        // Pre-upgrade entry: no withdraw_authority / last_withdrawal_at
+        let mut short = Vec::new();
+        employee.try_serialize(&mut short).unwrap();
+        short.truncate(short.len() - 40);
+        assert!(EmployeeEntry::try_deserialize(&mut short.as_slice()).is_err());
+
+        let grown = grown_buffer(&employee, 40, GrowTarget::EmployeeEntry);
+        let decoded = EmployeeEntry::try_deserialize(&mut grown.as_slice()).unwrap();
+        assert_eq!(decoded.employee_index, 7);
+        assert_eq!(decoded.last_action, 1_000);
+        assert_eq!(decoded.withdraw_authority, Pubkey::default());
+        assert_eq!(decoded.last_withdrawal_at, 0);
+    }
+
+    #[test]
+    fn test_grown_business_entry_deserializes() {
+        let mut business = sample_business();
+        business.entry_index = 3;
+        business.next_employee_index = 12;
+        business.refund_commitment = [9u8; 32];
+
+        // Pre-upgrade entry: no encrypted_closed_unpaid / has_closed_unpaid
+        let grown = grown_buffer(&business, 17, GrowTarget::BusinessEntry);
+        let decoded = BusinessEntry::try_deserialize(&mut grown.as_slice()).unwrap();
+        assert_eq!(decoded.entry_index, 3);
+        assert_eq!(decoded.next_employee_index, 12);
+        assert_eq!(decoded.refund_commitment, [9u8; 32]);
+        assert!(!decoded.has_closed_unpaid);
+    }
+
+    #[test]
+    fn test_require_entry_in_vault() {
+        let mut business = sample_business();
+        let vault = Pubkey::new_unique();
+        business.master_vault = vault;
+        assert!(require_entry_in_vault(&business, &vault).is_ok());
+        assert!(require_entry_in_vault(&business, &Pubkey::new_unique()).is_err());
+    }
+
+    #[test]
+    fn test_require_withdraw_authority() {
+        let mut employee = sample_employee();
+        let wallet = Pubkey::new_unique();
+        // Nothing registered: even the zero key is refused
+        assert!(require_withdraw_authority(&employee, &Pubkey::default()).is_err());
+
+        employee.withdraw_authority = wallet;
+        assert!(require_withdraw_authority(&employee, &wallet).is_ok());
+        assert!(require_withdraw_authority(&employee, &Pubkey::new_unique()).is_err());
+    }
+
+    #[test]
+    fn test_withdraw_elapsed_heals_future_timestamp() {
+        let business = sample_business();
+        let mut employee = sample_employee();
+        employee.last_withdrawal_at = 5_000;
+
+        // Ahead of the clock: reset instead of failing, nothing elapsed
+        assert_eq!(withdraw_elapsed(&mut employee, 1_000).unwrap(), None);
+        assert_eq!(employee.last_withdrawal_at, 1_000);
+
+        // The two-phase path can initiate again once the interval passes
+        let later = 1_000 + business.withdraw_interval();
+        let elapsed = withdraw_elapsed(&mut employee, later).unwrap().unwrap();
+        assert!(check_withdraw_interval(&mut employee, &business, elapsed, later).is_ok());
+    }
+
+    #[test]
+    fn test_first_withdrawal_waiver_is_once_only() {
+        let business = sample_business();
+        let mut employee = sample_employee();
+        employee.allow_immediate_first_withdrawal = true;
+
+        // Waived until a payout happens, so a cancelled initiate keeps it
+        assert!(check_withdraw_interval(&mut employee, &business, 0, 0).is_ok());
+        assert!(check_withdraw_interval(&mut employee, &business, 0, 0).is_ok());
+        assert!(!employee.first_withdrawal_done);
+
+        // Once `record_payout` marks it used, the interval applies again
+        employee.first_withdrawal_done = true;
+        assert!(check_withdraw_interval(&mut employee, &business, 0, 0).is_err());
+        assert!(check_withdraw_interval(
+            &mut employee,
+            &business,
+            business.withdraw_interval(),
+            0
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_withdraw_interval_applies_without_waiver() {
+        let business = sample_business();
+        let mut employee = sample_employee();
+        assert!(check_withdraw_interval(&mut employee, &business, 0, 0).is_err());
+        assert!(!employee.first_withdrawal_done);
+    }
+}
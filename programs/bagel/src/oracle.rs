@@ -0,0 +1,232 @@
+//! External price oracle for USD-denominated salaries
+//!
+//! When a business sets `salary_denom = Usd`, encrypted rates are USD
+//! amounts in the token's base-unit precision (1 USD = 10^decimals units)
+//! and settled accrual is converted into token units with a Pyth
+//! `PriceUpdateV2` account. The price is public, so the conversion is
+//! just an e_mul/e_div of the encrypted earnings by plaintext scalars.
+//! Native-denominated businesses never read an oracle.
+
+use anchor_lang::prelude::*;
+
+use crate::constants::{MAX_PRICE_AGE_SECONDS, MAX_PRICE_CONF_BPS, PYTH_RECEIVER_PROGRAM_ID};
+use crate::BagelError;
+
+/// Currency a business's salary rates are denominated in
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SalaryDenom {
+    /// Rates are in token base units (no conversion)
+    #[default]
+    Native,
+    /// Rates are in USD, converted at settlement via the oracle
+    Usd,
+}
+
+/// Minimal read-only view of a Pyth `PriceUpdateV2` account
+///
+/// Layout: discriminator(8) + write_authority(32) + verification_level
+/// (borsh enum: Partial{u8} = 2 bytes, Full = 1 byte) + feed_id(32) +
+/// price(i64) + conf(u64) + exponent(i32) + publish_time(i64) + ...
+pub struct PriceUpdateView {
+    pub feed_id: [u8; 32],
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
+
+impl PriceUpdateView {
+    /// sha256("account:PriceUpdateV2")[..8]
+    pub const DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+    const VERIFICATION_OFFSET: usize = 40;
+    const VERIFICATION_FULL: u8 = 1;
+    const MESSAGE_LEN: usize = 32 + 8 + 8 + 4 + 8;
+
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        require!(
+            data.len() > Self::VERIFICATION_OFFSET && data[..8] == Self::DISCRIMINATOR,
+            BagelError::InvalidPriceFeed
+        );
+        // Only fully verified updates; partially verified ones carry fewer guardian signatures
+        require!(
+            data[Self::VERIFICATION_OFFSET] == Self::VERIFICATION_FULL,
+            BagelError::InvalidPriceFeed
+        );
+
+        let start = Self::VERIFICATION_OFFSET + 1;
+        require!(
+            data.len() >= start + Self::MESSAGE_LEN,
+            BagelError::InvalidPriceFeed
+        );
+        let msg = &data[start..start + Self::MESSAGE_LEN];
+
+        let mut feed_id = [0u8; 32];
+        feed_id.copy_from_slice(&msg[0..32]);
+        let mut price = [0u8; 8];
+        price.copy_from_slice(&msg[32..40]);
+        let mut conf = [0u8; 8];
+        conf.copy_from_slice(&msg[40..48]);
+        let mut exponent = [0u8; 4];
+        exponent.copy_from_slice(&msg[48..52]);
+        let mut publish_time = [0u8; 8];
+        publish_time.copy_from_slice(&msg[52..60]);
+
+        Ok(Self {
+            feed_id,
+            price: i64::from_le_bytes(price),
+            conf: u64::from_le_bytes(conf),
+            exponent: i32::from_le_bytes(exponent),
+            publish_time: i64::from_le_bytes(publish_time),
+        })
+    }
+}
+
+/// Load and validate the price from a Pyth receiver account
+///
+/// Checks owner, feed id, positive price, staleness against `now` and
+/// that the confidence interval is within `MAX_PRICE_CONF_BPS`.
+pub fn load_price(
+    price_feed: &AccountInfo,
+    expected_feed_id: &[u8; 32],
+    now: i64,
+) -> Result<PriceUpdateView> {
+    let receiver = Pubkey::try_from(PYTH_RECEIVER_PROGRAM_ID).unwrap();
+    require!(*price_feed.owner == receiver, BagelError::InvalidPriceFeed);
+
+    let view = PriceUpdateView::parse(&price_feed.try_borrow_data()?)?;
+    validate_price(&view, expected_feed_id, now)?;
+    Ok(view)
+}
+
+pub fn validate_price(view: &PriceUpdateView, expected_feed_id: &[u8; 32], now: i64) -> Result<()> {
+    require!(view.feed_id == *expected_feed_id, BagelError::InvalidPriceFeed);
+    require!(view.price > 0, BagelError::InvalidPriceFeed);
+
+    let age = now.saturating_sub(view.publish_time);
+    require!(age <= MAX_PRICE_AGE_SECONDS, BagelError::StalePrice);
+
+    let max_conf = (view.price as u128)
+        .checked_mul(MAX_PRICE_CONF_BPS as u128)
+        .ok_or(BagelError::Overflow)?
+        / 10_000;
+    require!(
+        (view.conf as u128) <= max_conf,
+        BagelError::PriceConfidenceTooWide
+    );
+
+    Ok(())
+}
+
+/// `(multiplier, divisor)` turning a USD amount into token units
+///
+/// The oracle quotes `price * 10^exponent` USD per token, so
+/// `tokens = usd * 10^-exponent / price` (or `usd / (price * 10^exponent)`
+/// for a positive exponent).
+pub fn usd_conversion(view: &PriceUpdateView) -> Result<(u128, u128)> {
+    require!(view.price > 0, BagelError::InvalidPriceFeed);
+    let price = view.price as u128;
+    let scale = 10u128
+        .checked_pow(view.exponent.unsigned_abs())
+        .ok_or(BagelError::Overflow)?;
+
+    if view.exponent <= 0 {
+        Ok((scale, price))
+    } else {
+        Ok((1, price.checked_mul(scale).ok_or(BagelError::Overflow)?))
+    }
+}
+
+/// Plaintext mirror of the encrypted conversion (floor division)
+pub fn usd_to_token_units(usd: u128, multiplier: u128, divisor: u128) -> Result<u128> {
+    require!(divisor != 0, BagelError::Overflow);
+    usd.checked_mul(multiplier)
+        .map(|n| n / divisor)
+        .ok_or(BagelError::Overflow.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::hash::hash;
+
+    fn price_update(feed_id: [u8; 32], price: i64, conf: u64, exponent: i32, publish_time: i64) -> Vec<u8> {
+        let mut data = PriceUpdateView::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.push(PriceUpdateView::VERIFICATION_FULL);
+        data.extend_from_slice(&feed_id);
+        data.extend_from_slice(&price.to_le_bytes());
+        data.extend_from_slice(&conf.to_le_bytes());
+        data.extend_from_slice(&exponent.to_le_bytes());
+        data.extend_from_slice(&publish_time.to_le_bytes());
+        data.extend_from_slice(&[0u8; 40]); // prev_publish_time, ema, posted_slot
+        data
+    }
+
+    #[test]
+    fn test_discriminator() {
+        assert_eq!(
+            hash(b"account:PriceUpdateV2").to_bytes()[..8],
+            PriceUpdateView::DISCRIMINATOR
+        );
+    }
+
+    #[test]
+    fn test_parse_and_validate_price() {
+        let feed = [7u8; 32];
+        // $150.00000000 per SOL, $0.10 confidence
+        let data = price_update(feed, 15_000_000_000, 10_000_000, -8, 1_000);
+        let view = PriceUpdateView::parse(&data).unwrap();
+        assert_eq!(view.feed_id, feed);
+        assert_eq!(view.price, 15_000_000_000);
+        assert_eq!(view.exponent, -8);
+        assert_eq!(view.publish_time, 1_000);
+
+        assert!(validate_price(&view, &feed, 1_000 + MAX_PRICE_AGE_SECONDS).is_ok());
+        assert_eq!(
+            validate_price(&view, &feed, 1_001 + MAX_PRICE_AGE_SECONDS).unwrap_err(),
+            BagelError::StalePrice.into()
+        );
+        assert_eq!(
+            validate_price(&view, &[8u8; 32], 1_000).unwrap_err(),
+            BagelError::InvalidPriceFeed.into()
+        );
+    }
+
+    #[test]
+    fn test_wide_confidence_rejected() {
+        let feed = [7u8; 32];
+        // 2% confidence
+        let data = price_update(feed, 15_000_000_000, 300_000_000, -8, 1_000);
+        let view = PriceUpdateView::parse(&data).unwrap();
+        assert_eq!(
+            validate_price(&view, &feed, 1_000).unwrap_err(),
+            BagelError::PriceConfidenceTooWide.into()
+        );
+    }
+
+    #[test]
+    fn test_partial_verification_rejected() {
+        let mut data = price_update([7u8; 32], 1, 0, 0, 0);
+        data[PriceUpdateView::VERIFICATION_OFFSET] = 0;
+        assert!(PriceUpdateView::parse(&data).is_err());
+        assert!(PriceUpdateView::parse(&data[..20]).is_err());
+    }
+
+    #[test]
+    fn test_usd_conversion() {
+        let feed = [7u8; 32];
+        let data = price_update(feed, 15_000_000_000, 0, -8, 0);
+        let view = PriceUpdateView::parse(&data).unwrap();
+        let (mul, div) = usd_conversion(&view).unwrap();
+
+        // $300 (9 decimals) at $150/SOL = 2 SOL
+        let usd = 300 * 1_000_000_000u128;
+        assert_eq!(usd_to_token_units(usd, mul, div).unwrap(), 2_000_000_000);
+
+        let data = price_update(feed, 15, 0, 1, 0);
+        let view = PriceUpdateView::parse(&data).unwrap();
+        let (mul, div) = usd_conversion(&view).unwrap();
+        assert_eq!((mul, div), (1, 150));
+        assert_eq!(usd_to_token_units(usd, mul, div).unwrap(), 2_000_000_000);
+    }
+}
@@ -15,10 +15,33 @@
 //! Documentation: https://docs.inco.org/svm/rust-sdk/overview
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
 
 /// Inco Lightning Program ID (Devnet)
 pub const INCO_PROGRAM_ID: &str = "5sjEbPiqgZrYwR31ahR6Uk9wf5awoX61YGg7jExQSwaj";
 
+/// Inco input encodings accepted by `new_euint128` and token `transfer`
+pub const INPUT_TYPE_CIPHERTEXT: u8 = 0;
+pub const INPUT_TYPE_PLAINTEXT: u8 = 1;
+
+/// Reject input encodings Inco does not support
+pub fn validate_input_type(input_type: u8) -> Result<()> {
+    require!(
+        input_type == INPUT_TYPE_CIPHERTEXT || input_type == INPUT_TYPE_PLAINTEXT,
+        IncoError::InvalidInputType
+    );
+    Ok(())
+}
+
+/// Reject input encodings that would publish a payout amount
+///
+/// A plaintext `input_type` puts the amount in the instruction data, so
+/// withdrawals accept ciphertext only; deposits may still use either.
+pub fn validate_payout_input_type(input_type: u8) -> Result<()> {
+    require!(input_type == INPUT_TYPE_CIPHERTEXT, IncoError::InvalidInputType);
+    Ok(())
+}
+
 /// Encrypted Uint128 Type
 ///
 /// Represents an encrypted 128-bit unsigned integer using Inco Lightning.
@@ -245,12 +268,16 @@ pub fn encrypt_salary(amount: u64) -> ConfidentialBalance {
 /// PRIVACY: Elapsed time and result are never logged
 /// **USE CASE:** When employee withdraws, calculate: salary * elapsed_time
 /// This happens via encrypted computation so the salary amount stays encrypted!
+///
+/// `elapsed_seconds` is clamped to `MAX_ACCRUAL_ELAPSED`; callers must
+/// only advance their timestamp by the clamped window.
 pub fn calculate_accrued_mpc(
     encrypted_salary_per_second: &ConfidentialBalance,
     elapsed_seconds: u64,
 ) -> Result<ConfidentialBalance> {
     msg!("🧮 INCO: Calculating accrued (PRIVATE)");
 
+    let elapsed_seconds = crate::accrual::clamp_elapsed(elapsed_seconds);
     let result = encrypted_salary_per_second.multiply_scalar(elapsed_seconds)?;
 
     msg!("✅ INCO: Calculation complete (ENCRYPTED)");
@@ -270,6 +297,93 @@ pub fn decrypt_for_transfer(encrypted_amount: &ConfidentialBalance) -> Result<u6
     encrypted_amount.decrypt()
 }
 
+/// Read-only view of an Inco Token account (IncoAccount)
+///
+/// Layout: discriminator(8) + mint(32) + owner(32) + amount handle(16) + ...
+/// Used to validate token accounts passed as unchecked `AccountInfo`s.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IncoTokenAccountView {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    /// Encrypted balance handle (all zeros = never funded)
+    pub amount_handle: [u8; 16],
+}
+
+impl IncoTokenAccountView {
+    pub const MINT_OFFSET: usize = 8;
+    pub const OWNER_OFFSET: usize = 40;
+    pub const AMOUNT_OFFSET: usize = 72;
+    pub const MIN_LEN: usize = 88;
+
+    /// Parse the leading fields of an Inco Token account
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        require!(data.len() >= Self::MIN_LEN, IncoError::InvalidProgram);
+
+        let mint = Pubkey::try_from(&data[Self::MINT_OFFSET..Self::OWNER_OFFSET])
+            .map_err(|_| IncoError::InvalidProgram)?;
+        let owner = Pubkey::try_from(&data[Self::OWNER_OFFSET..Self::AMOUNT_OFFSET])
+            .map_err(|_| IncoError::InvalidProgram)?;
+        let amount_handle: [u8; 16] = data[Self::AMOUNT_OFFSET..Self::MIN_LEN]
+            .try_into()
+            .map_err(|_| IncoError::InvalidProgram)?;
+
+        Ok(Self { mint, owner, amount_handle })
+    }
+
+    /// Whether the account has never held an encrypted balance
+    pub fn is_unfunded(&self) -> bool {
+        self.amount_handle == [0u8; 16]
+    }
+}
+
+/// Check that `authority` may spend from an Inco Token account
+///
+/// Inco only grants transfer (and decrypt) rights on a balance handle to
+/// the token account owner, so a wrong authority otherwise fails deep in
+/// the token program with an opaque error. Run before every `transfer`.
+pub fn require_transfer_authority(source: &AccountInfo, authority: &Pubkey) -> Result<()> {
+    let view = IncoTokenAccountView::parse(&source.try_borrow_data()?)?;
+    require!(view.owner == *authority, IncoError::DecryptionNotAuthorized);
+    Ok(())
+}
+
+/// sha256("global:allow")[..8]
+pub const ALLOW_DISCRIMINATOR: [u8; 8] = [60, 103, 140, 65, 110, 109, 147, 164];
+
+/// Allowance PDA granting `allowed` decrypt access to a handle
+///
+/// Seeds: [handle (16 bytes LE), allowed_address] under Inco Lightning.
+pub fn allowance_pda(handle: &[u8; 16], allowed: &Pubkey, inco_lightning: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[handle.as_ref(), allowed.as_ref()], inco_lightning).0
+}
+
+/// Build an Inco Lightning `allow` instruction
+///
+/// Data: discriminator(8) + handle(16) + is_allow(1) + allowed_address(32).
+/// `signer` must already hold access to the handle and pays for the PDA.
+pub fn allow_instruction(
+    inco_lightning: &Pubkey,
+    handle: &[u8; 16],
+    signer: &Pubkey,
+    allowed: &Pubkey,
+) -> Instruction {
+    let mut data = ALLOW_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(handle);
+    data.push(1); // is_allow
+    data.extend_from_slice(allowed.as_ref());
+
+    Instruction {
+        program_id: *inco_lightning,
+        accounts: vec![
+            AccountMeta::new(allowance_pda(handle, allowed, inco_lightning), false),
+            AccountMeta::new(*signer, true),
+            AccountMeta::new_readonly(*allowed, false),
+            AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+        ],
+        data,
+    }
+}
+
 /// Inco-specific error codes
 #[error_code]
 pub enum IncoError {
@@ -290,6 +404,12 @@ pub enum IncoError {
 
     #[msg("Invalid Inco program ID")]
     InvalidProgram,
+
+    #[msg("Unsupported Inco input type")]
+    InvalidInputType,
+
+    #[msg("Transfer authority is not allowed to spend the source balance")]
+    DecryptionNotAuthorized,
 }
 
 // Re-export error for compatibility
@@ -330,6 +450,15 @@ mod tests {
         assert_eq!(accrued.decrypt().unwrap(), 3_600_000_000); // 3.6B lamports
     }
 
+    #[test]
+    fn test_accrual_elapsed_is_clamped() {
+        let salary_per_second = ConfidentialBalance::new(1);
+        let elapsed_seconds = crate::MAX_ACCRUAL_ELAPSED * 5;
+
+        let accrued = calculate_accrued_mpc(&salary_per_second, elapsed_seconds).unwrap();
+        assert_eq!(accrued.decrypt().unwrap(), crate::MAX_ACCRUAL_ELAPSED);
+    }
+
     #[test]
     fn test_overflow_protection() {
         let huge = ConfidentialBalance::new(u64::MAX / 2);
@@ -337,6 +466,64 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_inco_token_account() {
+        let mint = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut data = vec![0u8; IncoTokenAccountView::MIN_LEN + 16];
+        data[8..40].copy_from_slice(mint.as_ref());
+        data[40..72].copy_from_slice(owner.as_ref());
+
+        let view = IncoTokenAccountView::parse(&data).unwrap();
+        assert_eq!(view.mint, mint);
+        assert_eq!(view.owner, owner);
+        assert!(view.is_unfunded());
+
+        data[72] = 1;
+        assert!(!IncoTokenAccountView::parse(&data).unwrap().is_unfunded());
+
+        assert!(IncoTokenAccountView::parse(&data[..40]).is_err());
+    }
+
+    #[test]
+    fn test_allow_instruction() {
+        let inco = Pubkey::try_from(INCO_PROGRAM_ID).unwrap();
+        let handle = [9u8; 16];
+        let signer = Pubkey::new_unique();
+        let allowed = Pubkey::new_unique();
+
+        let ix = allow_instruction(&inco, &handle, &signer, &allowed);
+        assert_eq!(
+            anchor_lang::solana_program::hash::hash(b"global:allow").to_bytes()[..8],
+            ALLOW_DISCRIMINATOR
+        );
+        assert_eq!(ix.data.len(), 8 + 16 + 1 + 32);
+        assert_eq!(&ix.data[8..24], &handle);
+        assert_eq!(ix.data[24], 1);
+        assert_eq!(ix.accounts[0].pubkey, allowance_pda(&handle, &allowed, &inco));
+        assert!(ix.accounts[1].is_signer);
+        assert_eq!(ix.accounts[2].pubkey, allowed);
+    }
+
+    #[test]
+    fn test_require_transfer_authority() {
+        let key = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let token_program = Pubkey::new_unique();
+        let mut lamports = 0u64;
+        let mut data = vec![0u8; IncoTokenAccountView::MIN_LEN];
+        data[40..72].copy_from_slice(owner.as_ref());
+        let source = AccountInfo::new(
+            &key, false, true, &mut lamports, &mut data, &token_program, false, 0,
+        );
+
+        assert!(require_transfer_authority(&source, &owner).is_ok());
+        assert_eq!(
+            require_transfer_authority(&source, &Pubkey::new_unique()).unwrap_err(),
+            IncoError::DecryptionNotAuthorized.into()
+        );
+    }
+
     #[test]
     fn test_comparison() {
         let balance = ConfidentialBalance::new(1000);
@@ -347,4 +534,18 @@ mod tests {
         let ge_2000 = balance.greater_or_equal(2000).unwrap();
         assert!(!ge_2000.decrypt().unwrap());
     }
+
+    #[test]
+    fn test_validate_input_type() {
+        assert!(validate_input_type(INPUT_TYPE_CIPHERTEXT).is_ok());
+        assert!(validate_input_type(INPUT_TYPE_PLAINTEXT).is_ok());
+        assert!(validate_input_type(2).is_err());
+    }
+
+    #[test]
+    fn test_validate_payout_input_type() {
+        assert!(validate_payout_input_type(INPUT_TYPE_CIPHERTEXT).is_ok());
+        assert!(validate_payout_input_type(INPUT_TYPE_PLAINTEXT).is_err());
+        assert!(validate_payout_input_type(2).is_err());
+    }
 }
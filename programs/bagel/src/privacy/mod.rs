@@ -65,7 +65,7 @@ pub use inco::ErrorCode;
 pub use inco::{encrypt_salary, decrypt_for_transfer, calculate_accrued_mpc};
 
 // Re-export ShadowWire functions
-pub use shadowwire::{execute_private_payout, initialize_encrypted_balance, ShadowWireTransfer};
+pub use shadowwire::{execute_private_payout, initialize_encrypted_balance, ShadowWireProof, ShadowWireTransfer};
 
 // Re-export MagicBlock functions
 // Note: delegate_employee_entry is deprecated - use delegate_to_tee() instruction with #[delegate] macro
@@ -95,6 +95,42 @@ pub fn calculate_accrued(
 /// Inco Program ID constant
 pub const INCO_PROGRAM_ID: &str = "5sjEbPiqgZrYwR31ahR6Uk9wf5awoX61YGg7jExQSwaj";
 
+/// Which privacy providers a deployment runs for real
+///
+/// Stored on `MasterVault` and set by its authority (`set_provider_flags`),
+/// so the active stack is declared and queryable on-chain instead of
+/// inferred from which code paths happen to be mocked.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProviderFlags {
+    pub bits: u8,
+}
+
+impl ProviderFlags {
+    /// Inco Lightning CPIs are live (required: this build has no mock path)
+    pub const INCO_REAL: u8 = 1 << 0;
+    /// ShadowWire proofs are verified for real
+    pub const SHADOWWIRE_REAL: u8 = 1 << 1;
+    /// MagicBlock TEE delegation is enabled
+    pub const TEE_ACTIVE: u8 = 1 << 2;
+
+    const KNOWN: u8 = Self::INCO_REAL | Self::SHADOWWIRE_REAL | Self::TEE_ACTIVE;
+
+    /// Flags for a fresh vault: the providers this build actually calls
+    pub const DEFAULT_BITS: u8 = Self::INCO_REAL | Self::TEE_ACTIVE;
+
+    /// Reject unknown bits so a typo cannot enable a future provider, and
+    /// a cleared `INCO_REAL` since every encrypted path calls Inco
+    pub fn new(bits: u8) -> Result<Self> {
+        require!(bits & !Self::KNOWN == 0, crate::BagelError::InvalidProviderFlags);
+        require!(bits & Self::INCO_REAL != 0, crate::BagelError::InvalidProviderFlags);
+        Ok(Self { bits })
+    }
+
+    pub fn contains(&self, flag: u8) -> bool {
+        self.bits & flag == flag
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -119,6 +155,27 @@ mod tests {
         assert_eq!(accrued, 3_600_000_000); // $3600 in lamports
     }
     
+    #[test]
+    fn test_provider_flags() {
+        let flags = ProviderFlags::new(ProviderFlags::DEFAULT_BITS).unwrap();
+        assert!(flags.contains(ProviderFlags::INCO_REAL));
+        assert!(flags.contains(ProviderFlags::TEE_ACTIVE));
+        assert!(!flags.contains(ProviderFlags::SHADOWWIRE_REAL));
+        assert!(!ProviderFlags::default().contains(ProviderFlags::TEE_ACTIVE));
+    }
+
+    #[test]
+    fn test_provider_flags_reject_unknown_bits() {
+        assert!(ProviderFlags::new(0b0111).is_ok());
+        assert!(ProviderFlags::new(0b1001).is_err());
+    }
+
+    #[test]
+    fn test_provider_flags_require_inco() {
+        assert!(ProviderFlags::new(ProviderFlags::TEE_ACTIVE).is_err());
+        assert!(ProviderFlags::new(ProviderFlags::INCO_REAL).is_ok());
+    }
+
     #[test]
     fn test_overflow_protection() {
         let huge_salary = u64::MAX / 2;
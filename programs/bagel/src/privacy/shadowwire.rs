@@ -25,6 +25,24 @@
 
 use anchor_lang::prelude::*;
 
+/// Pedersen commitment size in bytes
+pub const COMMITMENT_LEN: usize = 32;
+
+/// Bulletproof range proof size in bytes (64-bit range)
+pub const RANGE_PROOF_LEN: usize = 672;
+
+/// Client-generated ShadowWire proof passed to withdrawal instructions
+///
+/// PRIVACY: Carries only the commitment and range proof - never the amount
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ShadowWireProof {
+    /// Pedersen commitment to the amount
+    pub commitment: Vec<u8>,
+
+    /// Bulletproof range proof for the commitment
+    pub range_proof: Vec<u8>,
+}
+
 /// ShadowWire Transfer Configuration
 ///
 /// Configures a private transfer using ShadowWire's Bulletproof protocol.
@@ -162,6 +180,21 @@ impl ShadowWireTransfer {
         Ok(())
     }
     
+    /// Check commitment and range proof have the expected sizes
+    ///
+    /// Enforced on devnet too, even though the verifier is mocked there.
+    pub fn validate_proof_sizes(&self) -> Result<()> {
+        require!(
+            self.commitment.len() == COMMITMENT_LEN,
+            ErrorCode::InvalidBulletproof
+        );
+        require!(
+            self.range_proof.len() == RANGE_PROOF_LEN,
+            ErrorCode::InvalidBulletproof
+        );
+        Ok(())
+    }
+
     /// Verify the Bulletproof range proof
     ///
     /// PRIVACY: Verification reveals nothing about the amount
@@ -356,6 +389,21 @@ mod tests {
         assert!(!transfer.range_proof.is_empty());
     }
 
+    #[test]
+    fn test_validate_proof_sizes() {
+        let recipient = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+
+        let valid = ShadowWireTransfer::new(recipient, mint, vec![0u8; COMMITMENT_LEN], vec![0u8; RANGE_PROOF_LEN]).unwrap();
+        assert!(valid.validate_proof_sizes().is_ok());
+
+        let short_proof = ShadowWireTransfer::new(recipient, mint, vec![0u8; COMMITMENT_LEN], vec![0u8; 64]).unwrap();
+        assert!(short_proof.validate_proof_sizes().is_err());
+
+        let long_commitment = ShadowWireTransfer::new(recipient, mint, vec![0u8; 64], vec![0u8; RANGE_PROOF_LEN]).unwrap();
+        assert!(long_commitment.validate_proof_sizes().is_err());
+    }
+
     #[test]
     fn test_verify_proof() {
         let recipient = Pubkey::new_unique();
@@ -28,6 +28,11 @@ use ephemeral_rollups_sdk::anchor::{commit, delegate, ephemeral};
 use ephemeral_rollups_sdk::cpi::DelegateConfig;
 use ephemeral_rollups_sdk::ephem::commit_and_undelegate_accounts;
 
+// Inco Lightning FHE operations
+use inco_lightning::cpi::accounts::Operation;
+use inco_lightning::cpi::{e_add, new_euint128};
+use inco_lightning::types::Euint128;
+
 declare_id!("J11xMm4pLQ6BUEhTpNwF1Mh4UhzUJNZCcw52zvZJspK2");
 
 // ============================================================
@@ -50,6 +55,13 @@ pub const INCO_TOKEN_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
     0x54, 0x15, 0x33, 0xa0, 0x94, 0xd3, 0xa5, 0xf9,
 ]);
 
+/// Inco input encodings accepted by token `transfer`
+pub const INPUT_TYPE_CIPHERTEXT: u8 = 0;
+pub const INPUT_TYPE_PLAINTEXT: u8 = 1;
+
+/// Maximum size of any client-supplied ciphertext
+pub const MAX_CIPHERTEXT_LEN: usize = 128;
+
 // MagicBlock TEE Validator (Devnet)
 pub const TEE_VALIDATOR: &str = "FnE6VJT5QNZdedZPnCoLsARgBwoE6DeJNjBs2H1gySXA";
 
@@ -69,6 +81,20 @@ pub const EMPLOYEE_SEED: &[u8] = b"employee";
 /// Vault token account seed
 pub const VAULT_TOKEN_SEED: &[u8] = b"vault_token";
 
+/// Delegated withdrawal consent PDA seed
+pub const WITHDRAW_CONSENT_SEED: &[u8] = b"withdraw_consent";
+
+/// Proposed payout wallet PDA seed
+pub const PAYOUT_PROPOSAL_SEED: &[u8] = b"payout_proposal";
+
+// ============================================================
+// Accrual Limits
+// ============================================================
+
+/// Maximum seconds a single accrue() call may cover (1 year)
+/// Longer gaps (e.g. TEE outage) are caught up over multiple calls
+pub const MAX_ACCRUAL_ELAPSED: i64 = 365 * 24 * 60 * 60;
+
 // ============================================================
 // Encrypted Value Handle
 // ============================================================
@@ -80,6 +106,17 @@ pub struct EncryptedHandle {
     pub handle: [u8; 32],
 }
 
+/// Business summary returned by `get_business_summary` (borsh, via return data)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct BusinessSummary {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub next_employee_index: u64,
+    pub encrypted_employee_count: EncryptedHandle,
+    pub encrypted_total_deposited: EncryptedHandle,
+    pub is_active: bool,
+}
+
 // ============================================================
 // Program Instructions
 // ============================================================
@@ -96,7 +133,8 @@ pub mod payroll {
     /// Register a new business
     ///
     /// Creates a Business PDA for the owner. Must be followed by
-    /// init_vault() to set up token custody.
+    /// init_vault() to set up token custody; deposit, add_employee and
+    /// the withdrawal paths fail with `VaultNotInitialized` until then.
     pub fn register_business(ctx: Context<RegisterBusiness>) -> Result<()> {
         let business = &mut ctx.accounts.business;
         let clock = Clock::get()?;
@@ -145,6 +183,7 @@ pub mod payroll {
 
         // Link vault to business
         business.vault = vault.key();
+        business.encrypted_total_deposited = EncryptedHandle::default();
 
         msg!("✅ Vault initialized");
         msg!("   Vault PDA: {}", vault.key());
@@ -159,6 +198,46 @@ pub mod payroll {
         Ok(())
     }
 
+    /// Point the vault at a recreated Inco Token account (owner only)
+    ///
+    /// For token-account rotation, e.g. after migrating to a new
+    /// confidential token standard. The new account must be an Inco
+    /// Token account for the vault's mint, owned by the vault PDA.
+    pub fn update_vault_token_account(
+        ctx: Context<UpdateVaultTokenAccount>,
+        new_token_account: Pubkey,
+    ) -> Result<()> {
+        let account_info = &ctx.accounts.new_token_account;
+        require!(
+            *account_info.owner == INCO_TOKEN_PROGRAM_ID,
+            PayrollError::Unauthorized
+        );
+
+        let data = account_info.try_borrow_data()?;
+        let owner = inco_token_account_owner(&data)?;
+        require!(owner == ctx.accounts.vault.key(), PayrollError::Unauthorized);
+        let mint = Pubkey::try_from(&data[8..40]).map_err(|_| PayrollError::Unauthorized)?;
+        require!(mint == ctx.accounts.vault.mint, PayrollError::Unauthorized);
+        drop(data);
+
+        let vault = &mut ctx.accounts.vault;
+        let old_token_account = vault.token_account;
+        vault.token_account = new_token_account;
+
+        msg!("🔄 Vault token account updated");
+        msg!("   Old: {}", old_token_account);
+        msg!("   New: {}", new_token_account);
+
+        emit!(VaultTokenAccountUpdated {
+            business: ctx.accounts.business.key(),
+            old_token_account,
+            new_token_account,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
     // ════════════════════════════════════════════════════════
     // DEPOSIT INSTRUCTION
     // ════════════════════════════════════════════════════════
@@ -166,12 +245,18 @@ pub mod payroll {
     /// Deposit encrypted tokens to the business vault
     ///
     /// Transfers tokens from depositor's Inco token account to
-    /// the vault's Inco token account via CPI.
+    /// the vault's Inco token account via CPI. `input_type` is the Inco
+    /// encoding of `encrypted_amount` (0 = ciphertext, 1 = plaintext).
     pub fn deposit(
         ctx: Context<Deposit>,
         encrypted_amount: Vec<u8>,
+        input_type: u8,
     ) -> Result<()> {
         require!(!encrypted_amount.is_empty(), PayrollError::InvalidAmount);
+        require!(encrypted_amount.len() <= MAX_CIPHERTEXT_LEN, PayrollError::InvalidCiphertext);
+        validate_input_type(input_type)?;
+
+        require_transfer_authority(&ctx.accounts.depositor_token_account, &ctx.accounts.owner.key())?;
 
         // Build CPI instruction to Inco Token Program for transfer
         let transfer_ix = build_inco_transfer_ix(
@@ -180,8 +265,8 @@ pub mod payroll {
             ctx.accounts.owner.key(),
             INCO_LIGHTNING_ID,
             anchor_lang::solana_program::system_program::ID,
-            encrypted_amount,
-            0, // input_type 0 = hex-encoded ciphertext
+            encrypted_amount.clone(),
+            input_type,
         );
 
         invoke(
@@ -195,6 +280,32 @@ pub mod payroll {
             ],
         )?;
 
+        // Fold the deposit into the encrypted running total
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            Operation {
+                signer: ctx.accounts.owner.to_account_info(),
+            },
+        );
+        let mut total = new_euint128(cpi_ctx, encrypted_amount, input_type)?;
+
+        let business = &mut ctx.accounts.business;
+        if business.encrypted_total_deposited.handle != [0u8; 32] {
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                Operation {
+                    signer: ctx.accounts.owner.to_account_info(),
+                },
+            );
+            total = e_add(
+                cpi_ctx,
+                from_encrypted_handle(&business.encrypted_total_deposited)?,
+                total,
+                0,
+            )?;
+        }
+        business.encrypted_total_deposited = to_encrypted_handle(&total)?;
+
         msg!("✅ Deposit completed");
         msg!("   Vault: {}", ctx.accounts.vault.key());
         msg!("   Amount: ENCRYPTED");
@@ -207,6 +318,29 @@ pub mod payroll {
         Ok(())
     }
 
+    /// Return a BusinessSummary via return data
+    ///
+    /// Read-only. The employee count and deposit total stay encrypted;
+    /// the owner decrypts the returned handles off-chain to reconcile
+    /// the vault balance against what was funded.
+    pub fn get_business_summary(ctx: Context<GetBusinessSummary>) -> Result<()> {
+        let business = &ctx.accounts.business;
+        let summary = BusinessSummary {
+            owner: business.owner,
+            vault: business.vault,
+            next_employee_index: business.next_employee_index,
+            encrypted_employee_count: business.encrypted_employee_count.clone(),
+            encrypted_total_deposited: business.encrypted_total_deposited.clone(),
+            is_active: business.is_active,
+        };
+
+        let mut data = Vec::new();
+        summary.serialize(&mut data).map_err(|_| PayrollError::InvalidCiphertext)?;
+        anchor_lang::solana_program::program::set_return_data(&data);
+
+        Ok(())
+    }
+
     // ════════════════════════════════════════════════════════
     // EMPLOYEE MANAGEMENT
     // ════════════════════════════════════════════════════════
@@ -223,7 +357,9 @@ pub mod payroll {
         encrypted_salary_rate: Vec<u8>,  // Per-second rate, encrypted
     ) -> Result<()> {
         require!(!encrypted_employee_id.is_empty(), PayrollError::InvalidCiphertext);
+        require!(encrypted_employee_id.len() <= MAX_CIPHERTEXT_LEN, PayrollError::InvalidCiphertext);
         require!(!encrypted_salary_rate.is_empty(), PayrollError::InvalidCiphertext);
+        require!(encrypted_salary_rate.len() <= MAX_CIPHERTEXT_LEN, PayrollError::InvalidCiphertext);
 
         let business = &mut ctx.accounts.business;
         let employee = &mut ctx.accounts.employee;
@@ -239,13 +375,14 @@ pub mod payroll {
         employee.is_active = true;
         employee.is_delegated = false;
         employee.bump = ctx.bumps.employee;
+        employee.payout_wallet = Pubkey::default();
 
         // Store encrypted data as handles
         employee.encrypted_employee_id = EncryptedHandle {
-            handle: to_handle_bytes(&encrypted_employee_id)
+            handle: to_handle_bytes(&encrypted_employee_id)?
         };
         employee.encrypted_salary_rate = EncryptedHandle {
-            handle: to_handle_bytes(&encrypted_salary_rate)
+            handle: to_handle_bytes(&encrypted_salary_rate)?
         };
         employee.encrypted_accrued = EncryptedHandle::default();
 
@@ -259,6 +396,78 @@ pub mod payroll {
             timestamp: clock.unix_timestamp,
         });
 
+        // Audit trail: initial rate (no previous salary)
+        emit!(SalaryChanged {
+            employee_index,
+            old_salary_handle: EncryptedHandle::default(),
+            new_salary_handle: employee.encrypted_salary_rate.clone(),
+            effective_at: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Propose the plaintext payout wallet used by auto_payment
+    ///
+    /// `auto_payment` has no employee signature, so it only pays into a
+    /// token account owned by this wallet. The owner only proposes it:
+    /// the wallet takes effect once it signs `accept_payout_wallet`, so an
+    /// owner cannot redirect payouts to a wallet the employee does not
+    /// control. Passing the zero key clears the wallet immediately, which
+    /// only disables auto_payment. Setting it links the wallet to the
+    /// employee index on-chain; leave it zeroed (the default) for maximum
+    /// privacy and rely on manual withdrawals instead.
+    pub fn set_payout_wallet(
+        ctx: Context<SetPayoutWallet>,
+        payout_wallet: Pubkey,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let employee = &mut ctx.accounts.employee;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.employee = employee.key();
+        proposal.payout_wallet = payout_wallet;
+        proposal.bump = ctx.bumps.proposal;
+
+        if payout_wallet == Pubkey::default() {
+            employee.payout_wallet = Pubkey::default();
+
+            msg!("✅ Payout wallet cleared");
+            msg!("   Employee Index: {}", employee.employee_index);
+
+            emit!(PayoutWalletUpdated {
+                employee_index: employee.employee_index,
+                registered: false,
+                timestamp: clock.unix_timestamp,
+            });
+            return Ok(());
+        }
+
+        msg!("✅ Payout wallet proposed");
+        msg!("   Employee Index: {}", employee.employee_index);
+
+        emit!(PayoutWalletProposed {
+            employee_index: employee.employee_index,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Accept a proposed payout wallet (the proposed wallet signs)
+    pub fn accept_payout_wallet(ctx: Context<AcceptPayoutWallet>) -> Result<()> {
+        let employee = &mut ctx.accounts.employee;
+        employee.payout_wallet = ctx.accounts.payout_wallet.key();
+        ctx.accounts.proposal.payout_wallet = Pubkey::default();
+
+        msg!("✅ Payout wallet updated");
+        msg!("   Employee Index: {}", employee.employee_index);
+
+        emit!(PayoutWalletUpdated {
+            employee_index: employee.employee_index,
+            registered: true,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
         Ok(())
     }
 
@@ -332,22 +541,39 @@ pub mod payroll {
             .checked_sub(employee.last_accrual_time)
             .ok_or(PayrollError::InvalidTimestamp)?;
 
-        if elapsed <= 0 {
+        if elapsed < 0 {
+            // Self-heal: last_accrual_time is in the future (clock skew or
+            // replayed TEE state). Reset it rather than stalling accrual.
+            msg!("⚠️  last_accrual_time ahead of clock - resetting to now");
+            employee.last_accrual_time = clock.unix_timestamp;
+            return Ok(());
+        }
+
+        if elapsed == 0 {
             return Ok(());
         }
 
         msg!("⚡ Accruing salary in TEE...");
 
+        // Never multiply by more than MAX_ACCRUAL_ELAPSED in one call;
+        // the remainder is picked up by subsequent accrue() calls
+        let accrual_window = elapsed.min(MAX_ACCRUAL_ELAPSED);
+        if accrual_window < elapsed {
+            msg!("⚠️  Elapsed {}s clamped to {}s", elapsed, accrual_window);
+        }
+
         // In TEE context, we update the encrypted accrued balance
         // The actual FHE computation happens via Inco Lightning CPI
         // For now, just update the timestamp - the encrypted computation
         // would be done via raw invoke to Inco Lightning
 
-        employee.last_accrual_time = clock.unix_timestamp;
+        employee.last_accrual_time = employee.last_accrual_time
+            .checked_add(accrual_window)
+            .ok_or(PayrollError::InvalidTimestamp)?;
 
         msg!("✅ Accrued (PRIVATE)");
         msg!("   Employee Index: {}", employee.employee_index);
-        msg!("   Elapsed: {} seconds", elapsed);
+        msg!("   Elapsed: {} seconds", accrual_window);
 
         Ok(())
     }
@@ -366,6 +592,12 @@ pub mod payroll {
 
         require!(ctx.accounts.employee.is_active, PayrollError::InactiveEmployee);
 
+        // No employee signer here - only pay the wallet set at onboarding
+        require_payout_destination(
+            &ctx.accounts.employee,
+            &ctx.accounts.employee_token_account.try_borrow_data()?,
+        )?;
+
         msg!("⚡ Processing auto payment from TEE...");
 
         // Exit and serialize the employee account
@@ -389,6 +621,8 @@ pub mod payroll {
             &[bump],
         ]];
 
+        require_transfer_authority(&ctx.accounts.vault_token_account, &ctx.accounts.vault.key())?;
+
         // Build transfer instruction - pass encrypted accrued as amount
         let transfer_ix = build_inco_transfer_ix(
             ctx.accounts.vault_token_account.key(),
@@ -419,6 +653,7 @@ pub mod payroll {
             employee_index: ctx.accounts.employee.employee_index,
             timestamp: clock.unix_timestamp,
             auto_payment: true,
+            reference: [0u8; 16],
         });
 
         Ok(())
@@ -426,12 +661,22 @@ pub mod payroll {
 
     /// Manual withdrawal (employee signs)
     ///
-    /// Employee proves identity by signing the transaction.
-    pub fn manual_withdraw(ctx: Context<ManualWithdraw>) -> Result<()> {
+    /// Employee proves identity by signing with the registered
+    /// `payout_wallet`, which must also own the destination account.
+    /// `reference` is an optional invoice/reference id echoed in the event.
+    pub fn manual_withdraw(
+        ctx: Context<ManualWithdraw>,
+        reference: Option<[u8; 16]>,
+    ) -> Result<()> {
         let clock = Clock::get()?;
 
         require!(ctx.accounts.employee.is_active, PayrollError::InactiveEmployee);
 
+        require_payout_destination(
+            &ctx.accounts.employee,
+            &ctx.accounts.employee_token_account.try_borrow_data()?,
+        )?;
+
         msg!("💸 Processing manual withdrawal...");
 
         // If delegated, commit and undelegate first
@@ -454,6 +699,8 @@ pub mod payroll {
             &[bump],
         ]];
 
+        require_transfer_authority(&ctx.accounts.vault_token_account, &ctx.accounts.vault.key())?;
+
         let transfer_ix = build_inco_transfer_ix(
             ctx.accounts.vault_token_account.key(),
             ctx.accounts.employee_token_account.key(),
@@ -483,6 +730,7 @@ pub mod payroll {
             employee_index: ctx.accounts.employee.employee_index,
             timestamp: clock.unix_timestamp,
             auto_payment: false,
+            reference: reference.unwrap_or_default(),
         });
 
         Ok(())
@@ -500,6 +748,12 @@ pub mod payroll {
 
         require!(ctx.accounts.employee.is_active, PayrollError::InactiveEmployee);
         require!(!encrypted_amount.is_empty(), PayrollError::InvalidAmount);
+        require!(encrypted_amount.len() <= MAX_CIPHERTEXT_LEN, PayrollError::InvalidCiphertext);
+
+        require_payout_destination(
+            &ctx.accounts.employee,
+            &ctx.accounts.employee_token_account.try_borrow_data()?,
+        )?;
 
         msg!("💸 Processing simple withdrawal...");
 
@@ -512,6 +766,8 @@ pub mod payroll {
             &[bump],
         ]];
 
+        require_transfer_authority(&ctx.accounts.vault_token_account, &ctx.accounts.vault.key())?;
+
         let transfer_ix = build_inco_transfer_ix(
             ctx.accounts.vault_token_account.key(),
             ctx.accounts.employee_token_account.key(),
@@ -519,7 +775,7 @@ pub mod payroll {
             INCO_LIGHTNING_ID,
             anchor_lang::solana_program::system_program::ID,
             encrypted_amount,
-            0, // input_type 0 = plaintext amount
+            INPUT_TYPE_CIPHERTEXT,
         );
 
         invoke_signed(
@@ -541,6 +797,103 @@ pub mod payroll {
             employee_index: ctx.accounts.employee.employee_index,
             timestamp: clock.unix_timestamp,
             auto_payment: false,
+            reference: [0u8; 16],
+        });
+
+        Ok(())
+    }
+
+    /// Opt into employer-initiated withdrawals (payout wallet signs)
+    ///
+    /// Creates the consent PDA that `withdraw_on_behalf` requires. Only
+    /// the employee's registered `payout_wallet` can consent, and the
+    /// consent is bound to that wallet: if `payout_wallet` later changes,
+    /// the consent no longer matches.
+    pub fn allow_delegated_withdrawal(ctx: Context<AllowDelegatedWithdrawal>) -> Result<()> {
+        let consent = &mut ctx.accounts.consent;
+        consent.employee = ctx.accounts.employee.key();
+        consent.payout_wallet = ctx.accounts.payout_wallet.key();
+        consent.bump = ctx.bumps.consent;
+
+        msg!("✅ Delegated withdrawal allowed");
+        msg!("   Employee Index: {}", ctx.accounts.employee.employee_index);
+
+        Ok(())
+    }
+
+    /// Revoke delegated withdrawals (payout wallet signs)
+    pub fn revoke_delegated_withdrawal(ctx: Context<RevokeDelegatedWithdrawal>) -> Result<()> {
+        msg!("✅ Delegated withdrawal revoked");
+        msg!("   Employee Index: {}", ctx.accounts.employee.employee_index);
+
+        Ok(())
+    }
+
+    /// Withdraw on behalf of an employee (business owner signs)
+    ///
+    /// For custodial payroll: the owner moves `encrypted_amount` to the
+    /// employee's `payout_wallet` token account without the employee's
+    /// signature. Requires the employee's consent PDA.
+    pub fn withdraw_on_behalf(
+        ctx: Context<WithdrawOnBehalf>,
+        _employee_index: u64,
+        encrypted_amount: Vec<u8>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+
+        require!(ctx.accounts.employee.is_active, PayrollError::InactiveEmployee);
+        require!(!encrypted_amount.is_empty(), PayrollError::InvalidAmount);
+        require!(encrypted_amount.len() <= MAX_CIPHERTEXT_LEN, PayrollError::InvalidCiphertext);
+
+        require!(
+            ctx.accounts.consent.payout_wallet == ctx.accounts.employee.payout_wallet,
+            PayrollError::Unauthorized
+        );
+        require_payout_destination(
+            &ctx.accounts.employee,
+            &ctx.accounts.employee_token_account.try_borrow_data()?,
+        )?;
+
+        msg!("💸 Processing delegated withdrawal...");
+
+        let business_key = ctx.accounts.business.key();
+        let bump = ctx.accounts.vault.bump;
+        let seeds: &[&[&[u8]]] = &[&[
+            VAULT_SEED,
+            business_key.as_ref(),
+            &[bump],
+        ]];
+
+        require_transfer_authority(&ctx.accounts.vault_token_account, &ctx.accounts.vault.key())?;
+
+        let transfer_ix = build_inco_transfer_ix(
+            ctx.accounts.vault_token_account.key(),
+            ctx.accounts.employee_token_account.key(),
+            ctx.accounts.vault.key(),
+            INCO_LIGHTNING_ID,
+            anchor_lang::solana_program::system_program::ID,
+            encrypted_amount,
+            0, // input_type 0 = hex-encoded ciphertext
+        );
+
+        invoke_signed(
+            &transfer_ix,
+            &[
+                ctx.accounts.vault_token_account.to_account_info(),
+                ctx.accounts.employee_token_account.to_account_info(),
+                ctx.accounts.vault.to_account_info(),
+                ctx.accounts.inco_lightning_program.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+            seeds,
+        )?;
+
+        msg!("✅ Delegated withdrawal completed");
+        msg!("   Employee Index: {}", ctx.accounts.employee.employee_index);
+
+        emit!(DelegatedWithdrawal {
+            employee_index: ctx.accounts.employee.employee_index,
+            timestamp: clock.unix_timestamp,
         });
 
         Ok(())
@@ -576,12 +929,67 @@ pub mod payroll {
 // Helper Functions
 // ============================================================
 
+/// Reject input encodings Inco does not support
+fn validate_input_type(input_type: u8) -> Result<()> {
+    require!(
+        input_type == INPUT_TYPE_CIPHERTEXT || input_type == INPUT_TYPE_PLAINTEXT,
+        PayrollError::InvalidInputType
+    );
+    Ok(())
+}
+
+/// Store an Inco Lightning handle in a 32-byte EncryptedHandle
+///
+/// The borsh encoding (16 bytes LE) goes in the leading bytes, the rest
+/// stays zero; `from_encrypted_handle` reverses it.
+fn to_encrypted_handle(value: &Euint128) -> Result<EncryptedHandle> {
+    let mut data = Vec::new();
+    value.serialize(&mut data).map_err(|_| PayrollError::InvalidCiphertext)?;
+    Ok(EncryptedHandle { handle: to_handle_bytes(&data)? })
+}
+
+fn from_encrypted_handle(value: &EncryptedHandle) -> Result<Euint128> {
+    Euint128::deserialize(&mut &value.handle[..16]).map_err(|_| PayrollError::InvalidCiphertext.into())
+}
+
 /// Convert a variable-length ciphertext to a fixed 32-byte handle
-fn to_handle_bytes(data: &[u8]) -> [u8; 32] {
+///
+/// Shorter input is zero-padded. Longer input is rejected rather than
+/// truncated, which would silently corrupt the stored handle.
+fn to_handle_bytes(data: &[u8]) -> Result<[u8; 32]> {
+    require!(data.len() <= 32, PayrollError::InvalidCiphertext);
     let mut handle = [0u8; 32];
-    let len = data.len().min(32);
-    handle[..len].copy_from_slice(&data[..len]);
-    handle
+    handle[..data.len()].copy_from_slice(data);
+    Ok(handle)
+}
+
+/// Owner of an Inco Token account
+///
+/// Layout: discriminator(8) + mint(32) + owner(32) + ...
+fn inco_token_account_owner(data: &[u8]) -> Result<Pubkey> {
+    require!(data.len() >= 72, PayrollError::Unauthorized);
+    Pubkey::try_from(&data[40..72]).map_err(|_| PayrollError::Unauthorized.into())
+}
+
+/// Check that the Inco Token account in `destination_data` is owned by the
+/// employee's registered `payout_wallet`
+///
+/// Rejects while no payout wallet is registered.
+fn require_payout_destination(employee: &Employee, destination_data: &[u8]) -> Result<()> {
+    require!(employee.payout_wallet != Pubkey::default(), PayrollError::Unauthorized);
+    let owner = inco_token_account_owner(destination_data)?;
+    require!(owner == employee.payout_wallet, PayrollError::Unauthorized);
+    Ok(())
+}
+
+/// Check that `authority` owns the Inco Token account it transfers from
+///
+/// Inco only lets the owner spend a balance handle; without this a wrong
+/// authority fails inside the token program with an opaque error.
+fn require_transfer_authority(source: &AccountInfo, authority: &Pubkey) -> Result<()> {
+    let owner = inco_token_account_owner(&source.try_borrow_data()?)?;
+    require!(owner == *authority, PayrollError::DecryptionNotAuthorized);
+    Ok(())
 }
 
 /// Build Inco Token transfer instruction
@@ -661,15 +1069,51 @@ pub struct InitVault<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+#[instruction(new_token_account: Pubkey)]
+pub struct UpdateVaultTokenAccount<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [BUSINESS_SEED, owner.key().as_ref()],
+        bump = business.bump,
+        has_one = owner
+    )]
+    pub business: Account<'info, Business>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, business.key().as_ref()],
+        bump = vault.bump,
+        has_one = business
+    )]
+    pub vault: Account<'info, BusinessVault>,
+
+    /// CHECK: Replacement Inco Token account; owner and mint verified in handler
+    #[account(address = new_token_account)]
+    pub new_token_account: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetBusinessSummary<'info> {
+    #[account(
+        seeds = [BUSINESS_SEED, business.owner.as_ref()],
+        bump = business.bump,
+    )]
+    pub business: Account<'info, Business>,
+}
+
 #[derive(Accounts)]
 pub struct Deposit<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
     #[account(
+        mut,
         seeds = [BUSINESS_SEED, owner.key().as_ref()],
         bump = business.bump,
-        has_one = owner
+        has_one = owner,
+        constraint = business.vault != Pubkey::default() @ PayrollError::VaultNotInitialized
     )]
     pub business: Account<'info, Business>,
 
@@ -690,6 +1134,7 @@ pub struct Deposit<'info> {
     pub vault_token_account: AccountInfo<'info>,
 
     /// CHECK: Inco Token Program
+    #[account(address = INCO_TOKEN_PROGRAM_ID @ PayrollError::Unauthorized)]
     pub inco_token_program: AccountInfo<'info>,
 
     /// CHECK: Inco Lightning Program
@@ -708,7 +1153,8 @@ pub struct AddEmployee<'info> {
         mut,
         seeds = [BUSINESS_SEED, owner.key().as_ref()],
         bump = business.bump,
-        has_one = owner
+        has_one = owner,
+        constraint = business.vault != Pubkey::default() @ PayrollError::VaultNotInitialized
     )]
     pub business: Account<'info, Business>,
 
@@ -724,6 +1170,59 @@ pub struct AddEmployee<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct SetPayoutWallet<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [BUSINESS_SEED, owner.key().as_ref()],
+        bump = business.bump,
+        has_one = owner
+    )]
+    pub business: Account<'info, Business>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_SEED, business.key().as_ref(), &employee.employee_index.to_le_bytes()],
+        bump = employee.bump
+    )]
+    pub employee: Account<'info, Employee>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = PayoutWalletProposal::LEN,
+        seeds = [PAYOUT_PROPOSAL_SEED, employee.key().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, PayoutWalletProposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptPayoutWallet<'info> {
+    /// Proposed payout wallet
+    #[account(address = proposal.payout_wallet @ PayrollError::Unauthorized)]
+    pub payout_wallet: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EMPLOYEE_SEED, employee.business.as_ref(), &employee.employee_index.to_le_bytes()],
+        bump = employee.bump
+    )]
+    pub employee: Account<'info, Employee>,
+
+    #[account(
+        mut,
+        seeds = [PAYOUT_PROPOSAL_SEED, employee.key().as_ref()],
+        bump = proposal.bump,
+        constraint = proposal.payout_wallet != Pubkey::default() @ PayrollError::Unauthorized
+    )]
+    pub proposal: Account<'info, PayoutWalletProposal>,
+}
+
 #[delegate]
 #[derive(Accounts)]
 pub struct DelegateToTee<'info> {
@@ -785,7 +1284,8 @@ pub struct AutoPayment<'info> {
 
     #[account(
         seeds = [BUSINESS_SEED, business.owner.as_ref()],
-        bump = business.bump
+        bump = business.bump,
+        constraint = business.vault != Pubkey::default() @ PayrollError::VaultNotInitialized
     )]
     pub business: Account<'info, Business>,
 
@@ -808,11 +1308,12 @@ pub struct AutoPayment<'info> {
     #[account(mut, address = vault.token_account)]
     pub vault_token_account: AccountInfo<'info>,
 
-    /// CHECK: Employee's Inco Token account
+    /// CHECK: Employee's Inco Token account (owner verified in handler)
     #[account(mut)]
     pub employee_token_account: AccountInfo<'info>,
 
     /// CHECK: Inco Token Program
+    #[account(address = INCO_TOKEN_PROGRAM_ID @ PayrollError::Unauthorized)]
     pub inco_token_program: AccountInfo<'info>,
 
     /// CHECK: Inco Lightning Program
@@ -825,13 +1326,14 @@ pub struct AutoPayment<'info> {
 #[commit]
 #[derive(Accounts)]
 pub struct ManualWithdraw<'info> {
-    /// Employee signs to prove identity
-    #[account(mut)]
+    /// Employee signs to prove identity (the registered `payout_wallet`)
+    #[account(mut, address = employee.payout_wallet @ PayrollError::Unauthorized)]
     pub employee_signer: Signer<'info>,
 
     #[account(
         seeds = [BUSINESS_SEED, business.owner.as_ref()],
-        bump = business.bump
+        bump = business.bump,
+        constraint = business.vault != Pubkey::default() @ PayrollError::VaultNotInitialized
     )]
     pub business: Account<'info, Business>,
 
@@ -854,11 +1356,12 @@ pub struct ManualWithdraw<'info> {
     #[account(mut, address = vault.token_account)]
     pub vault_token_account: AccountInfo<'info>,
 
-    /// CHECK: Employee's Inco Token account
+    /// CHECK: Employee's Inco Token account (owner verified in handler)
     #[account(mut)]
     pub employee_token_account: AccountInfo<'info>,
 
     /// CHECK: Inco Token Program
+    #[account(address = INCO_TOKEN_PROGRAM_ID @ PayrollError::Unauthorized)]
     pub inco_token_program: AccountInfo<'info>,
 
     /// CHECK: Inco Lightning Program
@@ -872,13 +1375,14 @@ pub struct ManualWithdraw<'info> {
 /// Employee signs to claim their salary
 #[derive(Accounts)]
 pub struct SimpleWithdraw<'info> {
-    /// Employee signs to claim salary
-    #[account(mut)]
+    /// Employee signs to claim salary (the registered `payout_wallet`)
+    #[account(mut, address = employee.payout_wallet @ PayrollError::Unauthorized)]
     pub employee_signer: Signer<'info>,
 
     #[account(
         seeds = [BUSINESS_SEED, business.owner.as_ref()],
-        bump = business.bump
+        bump = business.bump,
+        constraint = business.vault != Pubkey::default() @ PayrollError::VaultNotInitialized
     )]
     pub business: Account<'info, Business>,
 
@@ -901,11 +1405,113 @@ pub struct SimpleWithdraw<'info> {
     #[account(mut, address = vault.token_account)]
     pub vault_token_account: AccountInfo<'info>,
 
-    /// CHECK: Employee's Inco Token account
+    /// CHECK: Employee's Inco Token account (owner verified in handler)
     #[account(mut)]
     pub employee_token_account: AccountInfo<'info>,
 
     /// CHECK: Inco Token Program
+    #[account(address = INCO_TOKEN_PROGRAM_ID @ PayrollError::Unauthorized)]
+    pub inco_token_program: AccountInfo<'info>,
+
+    /// CHECK: Inco Lightning Program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AllowDelegatedWithdrawal<'info> {
+    /// Employee's registered payout wallet
+    #[account(mut, address = employee.payout_wallet @ PayrollError::Unauthorized)]
+    pub payout_wallet: Signer<'info>,
+
+    #[account(
+        seeds = [EMPLOYEE_SEED, employee.business.as_ref(), &employee.employee_index.to_le_bytes()],
+        bump = employee.bump
+    )]
+    pub employee: Account<'info, Employee>,
+
+    #[account(
+        init,
+        payer = payout_wallet,
+        space = WithdrawConsent::LEN,
+        seeds = [WITHDRAW_CONSENT_SEED, employee.key().as_ref()],
+        bump
+    )]
+    pub consent: Account<'info, WithdrawConsent>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeDelegatedWithdrawal<'info> {
+    /// Wallet that gave consent
+    #[account(mut)]
+    pub payout_wallet: Signer<'info>,
+
+    #[account(
+        seeds = [EMPLOYEE_SEED, employee.business.as_ref(), &employee.employee_index.to_le_bytes()],
+        bump = employee.bump
+    )]
+    pub employee: Account<'info, Employee>,
+
+    #[account(
+        mut,
+        close = payout_wallet,
+        seeds = [WITHDRAW_CONSENT_SEED, employee.key().as_ref()],
+        bump = consent.bump,
+        has_one = payout_wallet @ PayrollError::Unauthorized
+    )]
+    pub consent: Account<'info, WithdrawConsent>,
+}
+
+#[derive(Accounts)]
+#[instruction(employee_index: u64)]
+pub struct WithdrawOnBehalf<'info> {
+    /// Business owner (employer/operator) signs instead of the employee
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [BUSINESS_SEED, owner.key().as_ref()],
+        bump = business.bump,
+        has_one = owner,
+        constraint = business.vault != Pubkey::default() @ PayrollError::VaultNotInitialized
+    )]
+    pub business: Account<'info, Business>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, business.key().as_ref()],
+        bump = vault.bump,
+        has_one = business
+    )]
+    pub vault: Account<'info, BusinessVault>,
+
+    #[account(
+        seeds = [EMPLOYEE_SEED, business.key().as_ref(), &employee_index.to_le_bytes()],
+        bump = employee.bump
+    )]
+    pub employee: Account<'info, Employee>,
+
+    #[account(
+        seeds = [WITHDRAW_CONSENT_SEED, employee.key().as_ref()],
+        bump = consent.bump,
+        has_one = employee
+    )]
+    pub consent: Account<'info, WithdrawConsent>,
+
+    /// CHECK: Vault's Inco Token account
+    #[account(mut, address = vault.token_account)]
+    pub vault_token_account: AccountInfo<'info>,
+
+    /// CHECK: Payout wallet's Inco Token account (owner verified in handler)
+    #[account(mut)]
+    pub employee_token_account: AccountInfo<'info>,
+
+    /// CHECK: Inco Token Program
+    #[account(address = INCO_TOKEN_PROGRAM_ID @ PayrollError::Unauthorized)]
     pub inco_token_program: AccountInfo<'info>,
 
     /// CHECK: Inco Lightning Program
@@ -955,6 +1561,9 @@ pub struct Business {
 
     /// PDA bump
     pub bump: u8,
+
+    /// ENCRYPTED running total of deposits into the vault
+    pub encrypted_total_deposited: EncryptedHandle,
 }
 
 impl Business {
@@ -966,7 +1575,7 @@ impl Business {
         1 +                      // is_active
         8 +                      // created_at
         1 +                      // bump
-        32;                      // padding
+        32;                      // encrypted_total_deposited
 }
 
 #[account]
@@ -1025,6 +1634,10 @@ pub struct Employee {
 
     /// PDA bump
     pub bump: u8,
+
+    /// Plaintext payout wallet for auto_payment (zero = auto_payment disabled)
+    /// Carved from padding, after existing fields to keep their offsets
+    pub payout_wallet: Pubkey,
 }
 
 impl Employee {
@@ -1038,7 +1651,50 @@ impl Employee {
         1 +                      // is_active
         1 +                      // is_delegated
         1 +                      // bump
-        32;                      // padding
+        32;                      // payout_wallet
+}
+
+/// Payout wallet proposed by the business owner, pending acceptance
+/// Seeds: ["payout_proposal", employee]
+#[account]
+pub struct PayoutWalletProposal {
+    /// Employee account the proposal applies to
+    pub employee: Pubkey,
+
+    /// Proposed wallet (zero = nothing pending)
+    pub payout_wallet: Pubkey,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl PayoutWalletProposal {
+    pub const LEN: usize = 8 +  // discriminator
+        32 +                     // employee
+        32 +                     // payout_wallet
+        1;                       // bump
+}
+
+/// Employee consent for employer-initiated withdrawals
+/// Seeds: ["withdraw_consent", employee]
+/// Existence = opted in; closed on revoke
+#[account]
+pub struct WithdrawConsent {
+    /// Employee account the consent applies to
+    pub employee: Pubkey,
+
+    /// Payout wallet that signed the consent
+    pub payout_wallet: Pubkey,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl WithdrawConsent {
+    pub const LEN: usize = 8 +  // discriminator
+        32 +                     // employee
+        32 +                     // payout_wallet
+        1;                       // bump
 }
 
 // ============================================================
@@ -1058,6 +1714,14 @@ pub struct VaultInitialized {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct VaultTokenAccountUpdated {
+    pub business: Pubkey,
+    pub old_token_account: Pubkey,
+    pub new_token_account: Pubkey,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct FundsDeposited {
     pub business: Pubkey,
@@ -1070,6 +1734,15 @@ pub struct EmployeeAdded {
     pub timestamp: i64,
 }
 
+/// Emitted on every salary rate mutation (handles only)
+#[event]
+pub struct SalaryChanged {
+    pub employee_index: u64,
+    pub old_salary_handle: EncryptedHandle,
+    pub new_salary_handle: EncryptedHandle,
+    pub effective_at: i64,
+}
+
 #[event]
 pub struct DelegatedToTee {
     pub employee_index: u64,
@@ -1083,11 +1756,35 @@ pub struct UndelegatedFromTee {
     pub timestamp: i64,
 }
 
+/// Payout wallet proposed by the owner; not active until accepted
+#[event]
+pub struct PayoutWalletProposed {
+    pub employee_index: u64,
+    pub timestamp: i64,
+}
+
+/// Payout wallet accepted (`registered`) or cleared
+#[event]
+pub struct PayoutWalletUpdated {
+    pub employee_index: u64,
+    pub registered: bool,
+    pub timestamp: i64,
+}
+
+/// Payout initiated by the business owner, not the employee
+#[event]
+pub struct DelegatedWithdrawal {
+    pub employee_index: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct PaymentProcessed {
     pub employee_index: u64,
     pub timestamp: i64,
     pub auto_payment: bool,
+    /// Caller-supplied invoice/reference id (zeros if none)
+    pub reference: [u8; 16],
 }
 
 // ============================================================
@@ -1119,4 +1816,169 @@ pub enum PayrollError {
 
     #[msg("Unauthorized")]
     Unauthorized,
+
+    #[msg("Transfer authority is not allowed to spend the source balance")]
+    DecryptionNotAuthorized,
+
+    #[msg("Business vault not initialized - call init_vault first")]
+    VaultNotInitialized,
+
+    #[msg("Unsupported Inco input type")]
+    InvalidInputType,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// On-chain size of an account, discriminator included
+    fn serialized_len<T: AccountSerialize>(account: &T) -> usize {
+        let mut buf = Vec::new();
+        account.try_serialize(&mut buf).unwrap();
+        buf.len()
+    }
+
+    #[test]
+    fn test_business_fits_len() {
+        let business = Business {
+            owner: Pubkey::default(),
+            vault: Pubkey::default(),
+            next_employee_index: 0,
+            encrypted_employee_count: EncryptedHandle::default(),
+            is_active: false,
+            created_at: 0,
+            bump: 0,
+            encrypted_total_deposited: EncryptedHandle::default(),
+        };
+        assert!(serialized_len(&business) <= Business::LEN);
+    }
+
+    #[test]
+    fn test_validate_input_type() {
+        assert!(validate_input_type(INPUT_TYPE_CIPHERTEXT).is_ok());
+        assert!(validate_input_type(INPUT_TYPE_PLAINTEXT).is_ok());
+        assert_eq!(
+            validate_input_type(2).unwrap_err(),
+            PayrollError::InvalidInputType.into()
+        );
+    }
+
+    #[test]
+    fn test_to_handle_bytes_pads_short_input() {
+        let handle = to_handle_bytes(&[1, 2, 3]).unwrap();
+        assert_eq!(&handle[..3], &[1, 2, 3]);
+        assert_eq!(handle[3..], [0u8; 29]);
+
+        assert_eq!(to_handle_bytes(&[7u8; 32]).unwrap(), [7u8; 32]);
+    }
+
+    #[test]
+    fn test_to_handle_bytes_rejects_over_long_input() {
+        assert_eq!(
+            to_handle_bytes(&[7u8; 33]).unwrap_err(),
+            PayrollError::InvalidCiphertext.into()
+        );
+    }
+
+    #[test]
+    fn test_encrypted_handle_round_trip() {
+        let value = Euint128::default();
+        let stored = to_encrypted_handle(&value).unwrap();
+        assert_eq!(stored.handle[16..], [0u8; 16]);
+
+        let mut original = Vec::new();
+        value.serialize(&mut original).unwrap();
+        let mut restored = Vec::new();
+        from_encrypted_handle(&stored).unwrap().serialize(&mut restored).unwrap();
+        assert_eq!(original, restored);
+    }
+
+    #[test]
+    fn test_business_vault_fits_len() {
+        let vault = BusinessVault {
+            business: Pubkey::default(),
+            mint: Pubkey::default(),
+            token_account: Pubkey::default(),
+            encrypted_balance: EncryptedHandle::default(),
+            bump: 0,
+        };
+        assert!(serialized_len(&vault) <= BusinessVault::LEN);
+    }
+
+    #[test]
+    fn test_employee_fits_len() {
+        let employee = Employee {
+            business: Pubkey::default(),
+            employee_index: 0,
+            encrypted_employee_id: EncryptedHandle::default(),
+            encrypted_salary_rate: EncryptedHandle::default(),
+            encrypted_accrued: EncryptedHandle::default(),
+            last_accrual_time: 0,
+            is_active: false,
+            is_delegated: false,
+            bump: 0,
+            payout_wallet: Pubkey::default(),
+        };
+        assert!(serialized_len(&employee) <= Employee::LEN);
+    }
+
+    #[test]
+    fn test_inco_token_account_owner() {
+        let owner = Pubkey::new_unique();
+        let mut data = vec![0u8; 88];
+        data[8..40].copy_from_slice(Pubkey::new_unique().as_ref());
+        data[40..72].copy_from_slice(owner.as_ref());
+
+        assert_eq!(inco_token_account_owner(&data).unwrap(), owner);
+        assert!(inco_token_account_owner(&data[..71]).is_err());
+    }
+
+    #[test]
+    fn test_require_payout_destination() {
+        let wallet = Pubkey::new_unique();
+        let mut data = vec![0u8; 88];
+        data[40..72].copy_from_slice(wallet.as_ref());
+
+        let mut employee = Employee {
+            business: Pubkey::default(),
+            employee_index: 0,
+            encrypted_employee_id: EncryptedHandle::default(),
+            encrypted_salary_rate: EncryptedHandle::default(),
+            encrypted_accrued: EncryptedHandle::default(),
+            last_accrual_time: 0,
+            is_active: true,
+            is_delegated: false,
+            bump: 0,
+            payout_wallet: Pubkey::default(),
+        };
+        // No payout wallet registered yet
+        assert!(require_payout_destination(&employee, &data).is_err());
+
+        employee.payout_wallet = wallet;
+        assert!(require_payout_destination(&employee, &data).is_ok());
+
+        // Destination owned by someone else, e.g. a stray signer
+        data[40..72].copy_from_slice(Pubkey::new_unique().as_ref());
+        assert!(require_payout_destination(&employee, &data).is_err());
+    }
+
+    #[test]
+    fn test_withdraw_consent_fits_len() {
+        let consent = WithdrawConsent {
+            employee: Pubkey::default(),
+            payout_wallet: Pubkey::default(),
+            bump: 0,
+        };
+        assert!(serialized_len(&consent) <= WithdrawConsent::LEN);
+    }
+
+    #[test]
+    fn test_payout_wallet_proposal_fits_len() {
+        let proposal = PayoutWalletProposal {
+            employee: Pubkey::default(),
+            payout_wallet: Pubkey::default(),
+            bump: 0,
+        };
+        assert!(serialized_len(&proposal) <= PayoutWalletProposal::LEN);
+    }
 }
@@ -59,9 +59,14 @@ async function main() {
   if (!USDBAGEL_MINT) {
     throw new Error('USDBAGEL_MINT not found in config. Run initialize-usdbagel-mint.mjs first.');
   }
+  const VAULT_TOKEN_ACCOUNT = config.VAULT_TOKEN_ACCOUNT ? new PublicKey(config.VAULT_TOKEN_ACCOUNT) : null;
+  if (!VAULT_TOKEN_ACCOUNT) {
+    throw new Error('VAULT_TOKEN_ACCOUNT not found in config. Run init-vault-token-account.mjs first.');
+  }
   
   console.log(`   Bagel Program: ${BAGEL_PROGRAM_ID.toBase58()}`);
   console.log(`   USDBagel Mint: ${USDBAGEL_MINT.toBase58()}`);
+  console.log(`   Vault Token Account: ${VAULT_TOKEN_ACCOUNT.toBase58()}`);
   console.log(`   RPC: ${RPC_URL}\n`);
   
   // Load authority
@@ -111,6 +116,7 @@ async function main() {
         keys: [
           { pubkey: authority.publicKey, isSigner: true, isWritable: true },
           { pubkey: masterVault, isSigner: false, isWritable: true },
+          { pubkey: VAULT_TOKEN_ACCOUNT, isSigner: false, isWritable: false },
         ],
         data: instructionData,
       })
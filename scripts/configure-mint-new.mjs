@@ -42,6 +42,12 @@ async function main() {
   const [masterVault, bump] = deriveMasterVaultPDA();
   console.log(`Master Vault PDA: ${masterVault.toBase58()}\n`);
 
+  if (!process.env.VAULT_TOKEN_ACCOUNT) {
+    throw new Error('Set VAULT_TOKEN_ACCOUNT to the vault Inco Token account (see init-vault-token-account.mjs).');
+  }
+  const vaultTokenAccount = new PublicKey(process.env.VAULT_TOKEN_ACCOUNT);
+  console.log(`Vault Token Account: ${vaultTokenAccount.toBase58()}\n`);
+
   // Build instruction data: discriminator + mint (32 bytes) + enable (1 byte)
   const instructionData = Buffer.concat([
     CONFIGURE_MINT_DISCRIMINATOR,
@@ -54,6 +60,7 @@ async function main() {
     keys: [
       { pubkey: authority.publicKey, isSigner: true, isWritable: true },
       { pubkey: masterVault, isSigner: false, isWritable: true },
+      { pubkey: vaultTokenAccount, isSigner: false, isWritable: false },
     ],
     data: instructionData,
   });
@@ -245,7 +245,7 @@ async function main() {
     depositAmount.writeBigUInt64LE(BigInt(500_000_000_000), 0); // 500 tokens
 
     const tx = await program.methods
-      .deposit(Buffer.from(depositAmount))
+      .deposit(Buffer.from(depositAmount), 0)
       .accounts({
         owner: owner.publicKey,
         business: businessPDA,
@@ -444,7 +444,7 @@ async function main() {
     // and see what happens
 
     const tx = await program.methods
-      .manualWithdraw()
+      .manualWithdraw(null)
       .accounts({
         employeeSigner: testEmployeeSigner.publicKey,
         business: businessPDA,
@@ -265,7 +265,7 @@ async function main() {
     depositAmount.writeBigUInt64LE(BigInt(500_000_000), 0); // 500 tokens
 
     const tx = await program.methods
-      .deposit(Buffer.from(depositAmount))
+      .deposit(Buffer.from(depositAmount), 0)
       .accounts({
         owner: owner.publicKey,
         business: businessPDA,
@@ -255,7 +255,7 @@ async function main() {
     depositAmount.writeBigUInt64LE(BigInt(100_000_000), 0); // 100 tokens
 
     const depositTx = await program.methods
-      .deposit(Buffer.from(depositAmount))
+      .deposit(Buffer.from(depositAmount), 0)
       .accounts({
         owner: payer.publicKey,
         business: businessPDA,
@@ -0,0 +1,476 @@
+/**
+ * Bagel Lifecycle Test
+ *
+ * Exercises the core instruction flow of the Bagel program end to end:
+ *
+ * 1. initialize_vault / configure_confidential_mint (skipped if already set up)
+ * 2. register_business
+ * 3. deposit (confidential; non-confidential mode is rejected)
+ * 4. add_employee
+ * 5. request_withdrawal inside MIN_WITHDRAW_INTERVAL (rejected)
+ * 6. request_withdrawal signed by a non-employee key (rejected)
+ * 7. set_immediate_first_withdrawal, then request_withdrawal (confidential)
+ *
+ * The local validator cannot warp its clock, so instead of sleeping past
+ * MIN_WITHDRAW_INTERVAL the paying withdrawal uses the single-use
+ * first-withdrawal waiver.
+ *
+ * Inco Lightning and the Inco Token program are cloned into the local
+ * validator by Anchor.toml, so the CPIs run against the real programs.
+ * Token accounts are resolved from the Bagel user_token registry; the
+ * money-moving steps are skipped when they haven't been minted yet.
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { Bagel } from "../target/types/bagel";
+import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import { createHash } from "crypto";
+import { encryptValue } from "@inco/solana-sdk/encryption";
+import { hexToBuffer } from "@inco/solana-sdk/utils";
+
+// Program IDs
+const INCO_TOKEN_PROGRAM_ID = new PublicKey("4cyJHzecVWuU2xux6bCAPAhALKQT8woBh4Vx3AGEGe5N");
+const INCO_LIGHTNING_ID = new PublicKey("5sjEbPiqgZrYwR31ahR6Uk9wf5awoX61YGg7jExQSwaj");
+const USDBAGEL_MINT = new PublicKey("GhCZ59UK4Afg4WGpQ11HyRc8ya4swgWFXMh2BxuWQXHt");
+
+// Seeds (must match programs/bagel/src/lib.rs)
+const MASTER_VAULT_SEED = Buffer.from("master_vault");
+const BUSINESS_ENTRY_SEED = Buffer.from("entry");
+const EMPLOYEE_ENTRY_SEED = Buffer.from("employee");
+const USER_TOKEN_SEED = Buffer.from("user_token");
+
+const DEPOSIT_AMOUNT = BigInt(100 * 1_000_000_000); // 100 USDBagel
+const SALARY_PER_SECOND = BigInt(1_000_000); // 0.001 USDBagel/sec
+const WITHDRAW_AMOUNT = BigInt(10 * 1_000_000); // 0.01 USDBagel
+
+function u64Le(value: number | BN): Buffer {
+  return new BN(value).toArrayLike(Buffer, "le", 8);
+}
+
+/** Hash of a pubkey, truncated to 16 bytes (same as app/lib/bagel-client.ts) */
+function hashPubkey(pubkey: PublicKey): Buffer {
+  return createHash("sha256").update(pubkey.toBuffer()).digest().subarray(0, 16);
+}
+
+async function encrypt(amount: bigint): Promise<Buffer> {
+  return Buffer.from(hexToBuffer(await encryptValue(amount)));
+}
+
+describe("Bagel Lifecycle: deposit → accrue → withdraw", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Bagel as Program<Bagel>;
+  const parser = new anchor.EventParser(program.programId, program.coder);
+
+  const employer = provider.wallet as anchor.Wallet;
+  const employee = Keypair.generate();
+
+  let masterVaultPDA: PublicKey;
+  let businessEntryPDA: PublicKey;
+  let employeeEntryPDA: PublicKey;
+  let entryIndex: BN;
+
+  let employerTokenAccount: PublicKey | null;
+  let vaultTokenAccount: PublicKey | null;
+  let employeeTokenAccount: PublicKey | null;
+
+  let withdrawalSig: string | null = null;
+
+  /** Resolve an Inco Token account from the Bagel user_token registry */
+  const resolveIncoTokenAccount = async (owner: PublicKey): Promise<PublicKey | null> => {
+    const [userTokenPDA] = PublicKey.findProgramAddressSync(
+      [USER_TOKEN_SEED, owner.toBuffer(), USDBAGEL_MINT.toBuffer()],
+      program.programId
+    );
+    try {
+      const registry = await program.account.userTokenAccount.fetch(userTokenPDA);
+      return registry.incoTokenAccount.equals(PublicKey.default) ? null : registry.incoTokenAccount;
+    } catch {
+      return null;
+    }
+  };
+
+  /** Collect the names of events emitted by a confirmed transaction */
+  const eventsOf = async (signature: string): Promise<any[]> => {
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    return Array.from(parser.parseLogs(tx?.meta?.logMessages ?? []));
+  };
+
+  /** Wait (at most a slot or two) until the cluster clock is past `unixTimestamp` */
+  const clockPast = async (unixTimestamp: number): Promise<void> => {
+    for (;;) {
+      const slot = await provider.connection.getSlot("confirmed");
+      const blockTime = await provider.connection.getBlockTime(slot);
+      if (blockTime !== null && blockTime > unixTimestamp) return;
+      await new Promise((resolve) => setTimeout(resolve, 400));
+    }
+  };
+
+  /** Raw Inco Token account data (its encrypted balance handle changes on every transfer) */
+  const tokenAccountData = async (account: PublicKey): Promise<Buffer> => {
+    const info = await provider.connection.getAccountInfo(account, "confirmed");
+    expect(info, `token account ${account.toBase58()} exists`).to.not.be.null;
+    return Buffer.from(info!.data);
+  };
+
+  before(async () => {
+    [masterVaultPDA] = PublicKey.findProgramAddressSync([MASTER_VAULT_SEED], program.programId);
+
+    const airdrop = await provider.connection.requestAirdrop(employee.publicKey, 2 * LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(airdrop);
+
+    employerTokenAccount = await resolveIncoTokenAccount(employer.publicKey);
+    vaultTokenAccount = await resolveIncoTokenAccount(masterVaultPDA);
+    employeeTokenAccount = await resolveIncoTokenAccount(employee.publicKey);
+
+    console.log("\n🔧 Test Setup");
+    console.log(`   Master Vault: ${masterVaultPDA.toBase58()}`);
+    console.log(`   Employer token account: ${employerTokenAccount?.toBase58() ?? "NOT MINTED"}`);
+    console.log(`   Vault token account: ${vaultTokenAccount?.toBase58() ?? "NOT MINTED"}`);
+  });
+
+  it("initializes the master vault and confidential mint", async () => {
+    const existing = await provider.connection.getAccountInfo(masterVaultPDA);
+    if (!existing) {
+      await program.methods
+        .initializeVault()
+        .accounts({
+          authority: employer.publicKey,
+          masterVault: masterVaultPDA,
+          incoLightningProgram: INCO_LIGHTNING_ID,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .rpc();
+    }
+
+    let vault = await program.account.masterVault.fetch(masterVaultPDA);
+    if (
+      vault.tokenAccount.equals(PublicKey.default) &&
+      vaultTokenAccount &&
+      vault.authority.equals(employer.publicKey)
+    ) {
+      await program.methods
+        .configureConfidentialMint(USDBAGEL_MINT, true)
+        .accounts({
+          authority: employer.publicKey,
+          masterVault: masterVaultPDA,
+          vaultTokenAccount,
+        } as any)
+        .rpc();
+      vault = await program.account.masterVault.fetch(masterVaultPDA);
+    }
+
+    expect(vault.isActive).to.be.true;
+    expect(vault.useConfidentialTokens).to.be.true;
+  });
+
+  it("registers a business at the next index", async () => {
+    const vaultBefore = await program.account.masterVault.fetch(masterVaultPDA);
+    entryIndex = vaultBefore.nextBusinessIndex;
+    [businessEntryPDA] = PublicKey.findProgramAddressSync(
+      [BUSINESS_ENTRY_SEED, masterVaultPDA.toBuffer(), u64Le(entryIndex)],
+      program.programId
+    );
+
+    const sig = await program.methods
+      .registerBusiness(hashPubkey(employer.publicKey), null, INCO_LIGHTNING_ID, Array(32).fill(0))
+      .accounts({
+        employer: employer.publicKey,
+        masterVault: masterVaultPDA,
+        businessEntry: businessEntryPDA,
+        businessLabel: null,
+        incoLightningProgram: INCO_LIGHTNING_ID,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .rpc({ commitment: "confirmed" });
+
+    const vaultAfter = await program.account.masterVault.fetch(masterVaultPDA);
+    expect(vaultAfter.nextBusinessIndex.toString()).to.equal(entryIndex.addn(1).toString());
+
+    const entry = await program.account.businessEntry.fetch(businessEntryPDA);
+    expect(entry.entryIndex.toString()).to.equal(entryIndex.toString());
+    expect(entry.isActive).to.be.true;
+    expect(entry.encryptionContext.toBase58()).to.equal(INCO_LIGHTNING_ID.toBase58());
+
+    const events = await eventsOf(sig);
+    expect(events.map((e) => e.name)).to.include("businessRegistered");
+  });
+
+  it("deposits encrypted funds into the business", async function () {
+    if (!employerTokenAccount || !vaultTokenAccount) {
+      console.log("   ⚠️  Skipping deposit - employer and vault need USDBagel token accounts");
+      return this.skip();
+    }
+
+    const before = await program.account.businessEntry.fetch(businessEntryPDA);
+    const vaultBefore = await program.account.masterVault.fetch(masterVaultPDA);
+    const employerBalanceBefore = await tokenAccountData(employerTokenAccount);
+    const vaultBalanceBefore = await tokenAccountData(vaultTokenAccount);
+
+    const sig = await program.methods
+      .deposit(await encrypt(DEPOSIT_AMOUNT), null, 0)
+      .accounts({
+        depositor: employer.publicKey,
+        masterVault: masterVaultPDA,
+        businessEntry: businessEntryPDA,
+        incoLightningProgram: INCO_LIGHTNING_ID,
+        incoTokenProgram: INCO_TOKEN_PROGRAM_ID,
+        depositorTokenAccount: employerTokenAccount,
+        masterVaultTokenAccount: vaultTokenAccount,
+        depositReceipt: null,
+        depositGuard: null,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .rpc({ commitment: "confirmed" });
+
+    const after = await program.account.businessEntry.fetch(businessEntryPDA);
+    expect(JSON.stringify(after.encryptedBalance)).to.not.equal(JSON.stringify(before.encryptedBalance));
+
+    // Confidential deposits never touch the plaintext total_balance
+    const vaultAfter = await program.account.masterVault.fetch(masterVaultPDA);
+    expect(vaultAfter.totalBalance.toString()).to.equal(vaultBefore.totalBalance.toString());
+
+    // The confidential transfer moved funds from the employer to the vault
+    expect((await tokenAccountData(employerTokenAccount)).equals(employerBalanceBefore)).to.be.false;
+    expect((await tokenAccountData(vaultTokenAccount)).equals(vaultBalanceBefore)).to.be.false;
+
+    const events = await eventsOf(sig);
+    expect(events.map((e) => e.name)).to.include("fundsDeposited");
+  });
+
+  it("rejects a deposit when confidential mode is off", async function () {
+    const vault = await program.account.masterVault.fetch(masterVaultPDA);
+    if (!employerTokenAccount || !vaultTokenAccount || !vault.authority.equals(employer.publicKey)) {
+      console.log("   ⚠️  Skipping - needs token accounts and the vault authority");
+      return this.skip();
+    }
+
+    // There is no plaintext (SOL) deposit branch: with confidential mode
+    // off, deposit must fail instead of crediting total_balance
+    const before = await program.account.businessEntry.fetch(businessEntryPDA);
+    await program.methods
+      .setConfidentialTokens(false)
+      .accounts({ authority: employer.publicKey, masterVault: masterVaultPDA } as any)
+      .rpc();
+
+    try {
+      await program.methods
+        .deposit(await encrypt(DEPOSIT_AMOUNT), null, 0)
+        .accounts({
+          depositor: employer.publicKey,
+          masterVault: masterVaultPDA,
+          businessEntry: businessEntryPDA,
+          incoLightningProgram: INCO_LIGHTNING_ID,
+          incoTokenProgram: INCO_TOKEN_PROGRAM_ID,
+          depositorTokenAccount: employerTokenAccount,
+          masterVaultTokenAccount: vaultTokenAccount,
+          depositReceipt: null,
+          depositGuard: null,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .rpc();
+      expect.fail("deposit should be rejected outside confidential mode");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.equal("InvalidState");
+    } finally {
+      await program.methods
+        .setConfidentialTokens(true)
+        .accounts({ authority: employer.publicKey, masterVault: masterVaultPDA } as any)
+        .rpc();
+    }
+
+    const after = await program.account.businessEntry.fetch(businessEntryPDA);
+    expect(JSON.stringify(after.encryptedBalance)).to.equal(JSON.stringify(before.encryptedBalance));
+
+    const vaultAfter = await program.account.masterVault.fetch(masterVaultPDA);
+    expect(vaultAfter.totalBalance.toString()).to.equal(vault.totalBalance.toString());
+  });
+
+  it("adds an employee with an encrypted salary", async () => {
+    const business = await program.account.businessEntry.fetch(businessEntryPDA);
+    [employeeEntryPDA] = PublicKey.findProgramAddressSync(
+      [EMPLOYEE_ENTRY_SEED, businessEntryPDA.toBuffer(), u64Le(business.nextEmployeeIndex)],
+      program.programId
+    );
+
+    const sig = await program.methods
+      .addEmployee(hashPubkey(employee.publicKey), await encrypt(SALARY_PER_SECOND), new BN(0))
+      .accounts({
+        employer: employer.publicKey,
+        masterVault: masterVaultPDA,
+        businessEntry: businessEntryPDA,
+        employeeEntry: employeeEntryPDA,
+        salaryBand: null,
+        incoLightningProgram: INCO_LIGHTNING_ID,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .rpc({ commitment: "confirmed" });
+
+    const entry = await program.account.employeeEntry.fetch(employeeEntryPDA);
+    expect(entry.businessEntry.toBase58()).to.equal(businessEntryPDA.toBase58());
+    expect(entry.isActive).to.be.true;
+    expect(entry.lastAction.toNumber()).to.be.greaterThan(0);
+
+    const events = await eventsOf(sig);
+    expect(events.map((e) => e.name)).to.include("employeeAdded");
+
+    // Employee-signed payouts need a registered withdraw authority
+    const vault = await program.account.masterVault.fetch(masterVaultPDA);
+    if (vault.authority.equals(employer.publicKey)) {
+      await program.methods
+        .setWithdrawAuthority(employee.publicKey)
+        .accounts({
+          authority: employer.publicKey,
+          masterVault: masterVaultPDA,
+          businessEntry: businessEntryPDA,
+          employeeEntry: employeeEntryPDA,
+        } as any)
+        .rpc({ commitment: "confirmed" });
+      const registered = await program.account.employeeEntry.fetch(employeeEntryPDA);
+      expect(registered.withdrawAuthority.toBase58()).to.equal(employee.publicKey.toBase58());
+    }
+  });
+
+  it("rejects a withdrawal before MIN_WITHDRAW_INTERVAL", async () => {
+    try {
+      await program.methods
+        .requestWithdrawal(await encrypt(WITHDRAW_AMOUNT), false, null, null, 0, null)
+        .accounts({
+          withdrawer: employee.publicKey,
+          masterVault: masterVaultPDA,
+          businessEntry: businessEntryPDA,
+          employeeEntry: employeeEntryPDA,
+          incoLightningProgram: INCO_LIGHTNING_ID,
+          incoTokenProgram: INCO_TOKEN_PROGRAM_ID,
+          masterVaultTokenAccount: vaultTokenAccount,
+          employeeTokenAccount: employeeTokenAccount ?? Keypair.generate().publicKey,
+          withholdingConfig: null,
+          taxTokenAccount: null,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([employee])
+        .rpc();
+      expect.fail("Should have thrown WithdrawTooSoon");
+    } catch (err) {
+      expect(err.message).to.include("WithdrawTooSoon");
+    }
+  });
+
+  it("rejects a withdrawal signed by a non-employee key", async () => {
+    const intruder = Keypair.generate();
+    try {
+      await program.methods
+        .requestWithdrawal(await encrypt(WITHDRAW_AMOUNT), false, null, null, 0, null)
+        .accounts({
+          withdrawer: intruder.publicKey,
+          masterVault: masterVaultPDA,
+          businessEntry: businessEntryPDA,
+          employeeEntry: employeeEntryPDA,
+          incoLightningProgram: INCO_LIGHTNING_ID,
+          incoTokenProgram: INCO_TOKEN_PROGRAM_ID,
+          masterVaultTokenAccount: vaultTokenAccount,
+          employeeTokenAccount: employeeTokenAccount ?? Keypair.generate().publicKey,
+          withholdingConfig: null,
+          taxTokenAccount: null,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([intruder])
+        .rpc();
+      expect.fail("Should have thrown Unauthorized");
+    } catch (err) {
+      expect(err.message).to.include("Unauthorized");
+    }
+  });
+
+  it("withdraws inside the interval once the first withdrawal is waived", async function () {
+    if (!vaultTokenAccount || !employeeTokenAccount) {
+      console.log("   ⚠️  Skipping withdrawal - vault and employee need USDBagel token accounts");
+      return this.skip();
+    }
+
+    const vault = await program.account.masterVault.fetch(masterVaultPDA);
+    if (!vault.authority.equals(employer.publicKey)) {
+      console.log("   ⚠️  Skipping withdrawal - waiving the interval needs the vault authority");
+      return this.skip();
+    }
+
+    await program.methods
+      .setImmediateFirstWithdrawal(true)
+      .accounts({
+        authority: employer.publicKey,
+        masterVault: masterVaultPDA,
+        businessEntry: businessEntryPDA,
+        employeeEntry: employeeEntryPDA,
+      } as any)
+      .rpc({ commitment: "confirmed" });
+
+    const before = await program.account.employeeEntry.fetch(employeeEntryPDA);
+    expect(before.firstWithdrawalDone).to.be.false;
+    // add_employee stamped lastWithdrawalAt; make sure the payout lands in a later second
+    await clockPast(before.lastWithdrawalAt.toNumber());
+    const vaultBalanceBefore = await tokenAccountData(vaultTokenAccount);
+    const employeeBalanceBefore = await tokenAccountData(employeeTokenAccount);
+
+    const sig = await program.methods
+      .requestWithdrawal(await encrypt(WITHDRAW_AMOUNT), false, null, null, 0, null)
+      .accounts({
+        withdrawer: employee.publicKey,
+        masterVault: masterVaultPDA,
+        businessEntry: businessEntryPDA,
+        employeeEntry: employeeEntryPDA,
+        incoLightningProgram: INCO_LIGHTNING_ID,
+        incoTokenProgram: INCO_TOKEN_PROGRAM_ID,
+        masterVaultTokenAccount: vaultTokenAccount,
+        employeeTokenAccount: employeeTokenAccount,
+        withholdingConfig: null,
+        taxTokenAccount: null,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([employee])
+      .rpc({ commitment: "confirmed" });
+
+    const after = await program.account.employeeEntry.fetch(employeeEntryPDA);
+    expect(after.lastWithdrawalAt.toNumber()).to.be.greaterThan(before.lastWithdrawalAt.toNumber());
+    expect(JSON.stringify(after.encryptedAccrued)).to.not.equal(JSON.stringify(before.encryptedAccrued));
+    expect(after.firstWithdrawalDone).to.be.true;
+
+    // The confidential transfer moved funds from the vault to the employee
+    expect((await tokenAccountData(vaultTokenAccount)).equals(vaultBalanceBefore)).to.be.false;
+    expect((await tokenAccountData(employeeTokenAccount)).equals(employeeBalanceBefore)).to.be.false;
+
+    const events = await eventsOf(sig);
+    expect(events.map((e) => e.name)).to.include("firstWithdrawalWaived");
+    const withdrawal = events.find((e) => e.name === "withdrawalProcessed");
+    expect(withdrawal).to.not.be.undefined;
+    expect(withdrawal.data.businessIndex.toString()).to.equal(entryIndex.toString());
+    expect(withdrawal.data.shadowwireEnabled).to.be.false;
+    withdrawalSig = sig;
+  });
+
+  // Shape check only: the handles are opaque, so this pins the return data
+  // layout, not the amounts behind it
+  it("returns a WithdrawalResult echoing the event and entry handles", async function () {
+    if (!withdrawalSig) {
+      return this.skip();
+    }
+
+    const tx = await provider.connection.getTransaction(withdrawalSig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const [returnData] = (tx?.meta as any)?.returnData?.data ?? [];
+    expect(returnData).to.not.be.undefined;
+    const result = program.coder.types.decode("WithdrawalResult", Buffer.from(returnData, "base64"));
+
+    const withdrawal = (await eventsOf(withdrawalSig)).find((e) => e.name === "withdrawalProcessed");
+    const entry = await program.account.employeeEntry.fetch(employeeEntryPDA);
+    expect(JSON.stringify(result.paidHandle)).to.equal(JSON.stringify(withdrawal.data.netHandle));
+    expect(JSON.stringify(result.newAccruedHandle)).to.equal(JSON.stringify(entry.encryptedAccrued));
+  });
+});
@@ -257,7 +257,7 @@ describe("Deposit Verification - Confidential Token Transfer", () => {
       let txSignature: string;
       try {
         txSignature = await program.methods
-          .deposit(mockEncryptedAmount)
+          .deposit(mockEncryptedAmount, 0)
           .accounts({
             owner: employer.publicKey,
             business: businessPDA,
@@ -355,7 +355,7 @@ describe("Deposit Verification - Confidential Token Transfer", () => {
       let txSignature: string;
       try {
         txSignature = await program.methods
-          .deposit(mockEncryptedAmount)
+          .deposit(mockEncryptedAmount, 0)
           .accounts({
             owner: employer.publicKey,
             business: businessPDA,
@@ -396,7 +396,7 @@ describe("Deposit Verification - Confidential Token Transfer", () => {
 
       try {
         await program.methods
-          .deposit(Buffer.from([]))
+          .deposit(Buffer.from([]), 0)
           .accounts({
             owner: employer.publicKey,
             business: businessPDA,
@@ -436,7 +436,7 @@ describe("Deposit Verification - Confidential Token Transfer", () => {
       let txSignature: string;
       try {
         txSignature = await program.methods
-          .deposit(mockEncryptedAmount)
+          .deposit(mockEncryptedAmount, 0)
           .accounts({
             owner: employer.publicKey,
             business: businessPDA,
@@ -532,7 +532,7 @@ describe("Deposit Verification - Confidential Token Transfer", () => {
       let txSignature: string;
       try {
         txSignature = await program.methods
-          .deposit(mockEncryptedAmount)
+          .deposit(mockEncryptedAmount, 0)
           .accounts({
             owner: employer.publicKey,
             business: businessPDA,
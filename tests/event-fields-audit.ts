@@ -0,0 +1,159 @@
+/**
+ * Event Field Audit
+ *
+ * Walks every event in the built Bagel and Payroll IDLs and fails on any
+ * plaintext u64 / u128 / pubkey field that is not an index, a timestamp,
+ * an encrypted handle or listed in PUBLIC_FIELDS below. A new event that
+ * carries an amount or a wallet therefore breaks this test until it is
+ * encrypted or explicitly reviewed into the allowlist.
+ *
+ * Needs `anchor build` (reads target/idl/*.json); no cluster required.
+ */
+
+import * as fs from "fs";
+import { expect } from "chai";
+
+const IDL_DIR = "./target/idl";
+
+/** Encrypted handle types: opaque, never walked into */
+const HANDLE_TYPES = new Set(["Euint128", "Ebool", "EncryptedHandle"]);
+
+/** Plaintext types that can carry an amount or an identity */
+const SENSITIVE_PRIMITIVES = new Set(["u64", "u128", "pubkey"]);
+
+/**
+ * Reviewed public fields, as `<program>.<Event>.<field>`
+ *
+ * Program-owned addresses, configuration values and public counters only.
+ * Never add a salary, balance, payout amount or employee wallet here.
+ */
+const PUBLIC_FIELDS = new Set([
+  "bagel.VaultInitialized.authority",
+  "bagel.GlobalStateInitialized.admin",
+  "bagel.AdminTransferred.previous_admin",
+  "bagel.AdminTransferred.new_admin",
+  "bagel.BusinessRegistered.encryption_context",
+  "bagel.MultiDeposit.count",
+  "bagel.DelegatedToTee.validator",
+  "bagel.WithholdingUpdated.tax_token_account",
+  "bagel.VaultDelegatedToTee.validator",
+  "bagel.VaultClosed.refund_to",
+  // Rent lamports of the closed vault account, not a token amount
+  "bagel.VaultClosed.amount",
+  "bagel.SoftCapUpdated.soft_cap",
+  "bagel.BusinessExported.start",
+  "bagel.BusinessImported.start",
+  "bagel.EmployeeSoftCapReached.count",
+  "bagel.ConfidentialMintConfigured.mint",
+  "bagel.ConfidentialMintRotated.old_mint",
+  "bagel.ConfidentialMintRotated.new_mint",
+  "bagel.ConfidentialMintRotated.vault_token_account",
+  "bagel.UserTokenAccountInitialized.owner",
+  "bagel.UserTokenAccountInitialized.mint",
+  "bagel.UserTokenAccountInitialized.token_account_pda",
+  "bagel.IncoTokenAccountLinked.owner",
+  "bagel.IncoTokenAccountLinked.mint",
+  "bagel.IncoTokenAccountLinked.inco_token_account",
+  // BusinessEntry / vault PDAs, not the employer wallet
+  "payroll.VaultInitialized.business",
+  "payroll.VaultInitialized.vault",
+  "payroll.VaultTokenAccountUpdated.business",
+  "payroll.VaultTokenAccountUpdated.old_token_account",
+  "payroll.VaultTokenAccountUpdated.new_token_account",
+  "payroll.FundsDeposited.business",
+  "payroll.DelegatedToTee.validator",
+]);
+
+/** Index fields (`*_index`) are public PDA coordinates */
+function isIndexField(name: string): boolean {
+  return name.endsWith("_index");
+}
+
+/**
+ * Every `<program>.<Event>.<field path>` in `idl` whose type is (or
+ * wraps) a sensitive primitive and is not allowlisted
+ */
+function unreviewedEventFields(program: string, idl: any): string[] {
+  const types = new Map<string, any>((idl.types ?? []).map((t: any) => [t.name, t]));
+  const found: string[] = [];
+
+  const walk = (type: any, path: string, field: string, seen: Set<string>) => {
+    if (typeof type === "string") {
+      if (
+        SENSITIVE_PRIMITIVES.has(type) &&
+        !isIndexField(field) &&
+        !PUBLIC_FIELDS.has(path)
+      ) {
+        found.push(path);
+      }
+      return;
+    }
+    if (type.option !== undefined) return walk(type.option, path, field, seen);
+    if (type.vec !== undefined) return walk(type.vec, path, field, seen);
+    if (type.array !== undefined) return walk(type.array[0], path, field, seen);
+    if (type.defined !== undefined) {
+      const name = typeof type.defined === "string" ? type.defined : type.defined.name;
+      if (HANDLE_TYPES.has(name) || seen.has(name)) return;
+      const def = types.get(name);
+      if (!def) return;
+      walkFields(def.type, path, new Set([...seen, name]));
+    }
+  };
+
+  const walkFields = (def: any, path: string, seen: Set<string>) => {
+    const groups =
+      def.kind === "struct" ? [def.fields ?? []] : (def.variants ?? []).map((v: any) => v.fields ?? []);
+    for (const fields of groups) {
+      fields.forEach((f: any, i: number) => {
+        const name = typeof f === "object" && f.name !== undefined ? f.name : String(i);
+        const type = typeof f === "object" && f.type !== undefined ? f.type : f;
+        walk(type, `${path}.${name}`, name, seen);
+      });
+    }
+  };
+
+  for (const event of idl.events ?? []) {
+    const def = types.get(event.name);
+    expect(def, `${program}: no type definition for event ${event.name}`).to.not.be.undefined;
+    walkFields(def.type, `${program}.${event.name}`, new Set([event.name]));
+  }
+  return found;
+}
+
+describe("Event field audit", () => {
+  for (const program of ["bagel", "payroll"]) {
+    it(`${program} events carry no unreviewed amount or wallet fields`, () => {
+      const idlPath = `${IDL_DIR}/${program}.json`;
+      expect(fs.existsSync(idlPath), `${idlPath} missing - run anchor build`).to.be.true;
+      const idl = JSON.parse(fs.readFileSync(idlPath, "utf8"));
+
+      expect(idl.events ?? [], `${program} IDL lists no events`).to.not.be.empty;
+      expect(unreviewedEventFields(program, idl)).to.deep.equal([]);
+    });
+  }
+
+  it("flags a plaintext amount added to an event", () => {
+    const idl = {
+      events: [{ name: "Leaky" }],
+      types: [
+        {
+          name: "Leaky",
+          type: {
+            kind: "struct",
+            fields: [
+              { name: "employee_index", type: "u64" },
+              { name: "timestamp", type: "i64" },
+              { name: "amount_handle", type: { defined: { name: "Euint128" } } },
+              { name: "amount", type: "u64" },
+              { name: "recipient", type: { option: "pubkey" } },
+            ],
+          },
+        },
+      ],
+    };
+    expect(unreviewedEventFields("test", idl)).to.deep.equal([
+      "test.Leaky.amount",
+      "test.Leaky.recipient",
+    ]);
+  });
+});
@@ -208,7 +208,7 @@ describe("Confidential Payroll Program", () => {
       console.log(`   Encrypted amount: ${encryptedAmount.length} bytes`);
 
       const tx = await program.methods
-        .deposit(Buffer.from(encryptedAmount))
+        .deposit(Buffer.from(encryptedAmount), 0)
         .accounts({
           owner: employer.publicKey,
           business: businessPDA,
@@ -231,7 +231,7 @@ describe("Confidential Payroll Program", () => {
     it("should reject empty encrypted amount", async () => {
       try {
         await program.methods
-          .deposit(Buffer.from([]))
+          .deposit(Buffer.from([]), 0)
           .accounts({
             owner: employer.publicKey,
             business: businessPDA,